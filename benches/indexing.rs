@@ -0,0 +1,50 @@
+//! Indexing and search timings over the built-in corpora, checked against the timings
+//! of an earlier run on the same machine.
+//! Run with `cargo bench --features bench --bench indexing`. The first run saves its
+//! timings as the baseline; later runs fail if a timing is more than `MAX_RATIO` times
+//! its baseline. Pass `--save-baseline` to replace the baseline with the current run.
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use ezstr::bench::{measure, Baseline, Corpus};
+use ezstr::EzStr;
+use regex::Regex;
+
+const MAX_RATIO: f64 = 1.25;
+
+fn baseline_path() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"), PathBuf::from);
+    target.join("ezstr-bench").join("indexing.baseline")
+}
+
+fn main() -> ExitCode {
+    let save = std::env::args().any(|arg| arg == "--save-baseline");
+    let path = baseline_path();
+    let baseline = if save { Baseline::new() } else { Baseline::load(&path).expect("readable baseline") };
+    let mut current = Baseline::new();
+    let mut regressions = Vec::new();
+
+    let word = Regex::new(r"\w+").unwrap();
+    for corpus in Corpus::all(1 << 20) {
+        let text = corpus.text.as_str();
+        let index = measure(10, || EzStr::new(text).indexed().len());
+        let indexed = EzStr::new(text).indexed();
+        let find = measure(10, || indexed.find_iter(&word).count());
+        println!("{:<10} index {:>10?}  find_iter {:>10?}", corpus.name, index, find);
+        for (name, time) in [(format!("{}/index", corpus.name), index), (format!("{}/find_iter", corpus.name), find)] {
+            current.insert(&name, time);
+            if let Err(regression) = baseline.check(&name, time, MAX_RATIO) {
+                regressions.push(regression);
+            }
+        }
+    }
+
+    if baseline.is_empty() {
+        current.save(&path).expect("writable baseline");
+        println!("saved baseline to {}", path.display());
+    }
+    for regression in &regressions {
+        eprintln!("{}", regression);
+    }
+    if regressions.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
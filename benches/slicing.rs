@@ -0,0 +1,56 @@
+//! Times cutting a text into many short slices, the pattern tokenizers produce, and
+//! counts the heap allocations made while doing it.
+//! Run with `cargo bench --bench slicing`.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use ezstr::EzStr;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Runs `f` and returns its result with the time taken and the allocations made.
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration, usize) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let out = f();
+    (out, start.elapsed(), ALLOCATIONS.load(Ordering::Relaxed) - allocations)
+}
+
+fn main() {
+    let text = EzStr::new("[Verse]\nNicotine dreams of you, Amm7 noël 夜 🎸\n".repeat(2_000)).indexed();
+    let len = text.len();
+    let offsets: Vec<usize> = text.grapheme_index().iter().collect();
+
+    let (bytes, inline, inline_allocs) = measure(|| {
+        (0..len - 4).map(|i| black_box(text.slice_strict(i, i + 4)).as_str().len()).sum::<usize>()
+    });
+    // what every slice cost before short strings were stored inline: an allocation each
+    let (heap_bytes, heap, heap_allocs) = measure(|| {
+        (0..len - 4)
+            .map(|i| black_box(text.as_str()[offsets[i]..offsets[i + 4]].to_string()).len())
+            .sum::<usize>()
+    });
+
+    assert_eq!(bytes, heap_bytes);
+    println!("{} four-grapheme slices", len - 4);
+    println!("  inline EzStr:        {:>10?} {:>7} allocations", inline, inline_allocs);
+    println!("  heap copy per slice: {:>10?} {:>7} allocations", heap, heap_allocs);
+}
@@ -0,0 +1,49 @@
+/*
+ * Smoke test of the C API. From the crate root:
+ *
+ *     cargo build --release --features ffi
+ *     cc -Iinclude examples/c/smoke.c target/release/libezstr.a -lpthread -ldl -lm -o smoke
+ *     ./smoke
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "ezstr.h"
+
+#define CHECK(cond)                                                     \
+    do {                                                                \
+        if (!(cond)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            return 1;                                                   \
+        }                                                               \
+    } while (0)
+
+int main(void) {
+    const char *text = "Th\xc3\xa9 \xf0\x9f\x87\xab\xf0\x9f\x87\xb7 Amm7";
+    EzStr *s = NULL;
+    CHECK(ezstr_new((const uint8_t *)text, strlen(text), &s) == EZSTR_OK);
+
+    size_t len = 0;
+    CHECK(ezstr_len(s, &len) == EZSTR_OK);
+    CHECK(len == 10);
+
+    size_t start = 0, end = 0;
+    const char *pattern = "Am+";
+    CHECK(ezstr_find(s, (const uint8_t *)pattern, strlen(pattern), &start, &end) == EZSTR_OK);
+    CHECK(start == 6 && end == 9);
+
+    EzStr *flag = NULL;
+    CHECK(ezstr_slice(s, 4, 5, &flag) == EZSTR_OK);
+    const uint8_t *data = NULL;
+    size_t bytes = 0;
+    CHECK(ezstr_as_utf8(flag, &data, &bytes) == EZSTR_OK);
+    CHECK(bytes == 8 && memcmp(data, "\xf0\x9f\x87\xab\xf0\x9f\x87\xb7", 8) == 0);
+
+    CHECK(ezstr_slice(s, 5, 11, &flag) == EZSTR_OUT_OF_BOUNDS);
+    CHECK(ezstr_new((const uint8_t *)"\xff", 1, &flag) == EZSTR_INVALID_UTF8);
+
+    ezstr_free(flag);
+    ezstr_free(s);
+    puts("ok");
+    return 0;
+}
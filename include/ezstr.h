@@ -0,0 +1,56 @@
+/*
+ * C API of ezstr, available when the crate is built with `--features ffi`.
+ *
+ * Strings are passed as UTF-8 pointer/length pairs and every call returns an
+ * EzStrStatus; results are written through out-pointers. Grapheme indices and
+ * lengths are counted in extended grapheme clusters.
+ */
+#ifndef EZSTR_H
+#define EZSTR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Values are stable. */
+typedef enum EzStrStatus {
+    EZSTR_OK = 0,
+    EZSTR_NULL_POINTER = 1,
+    EZSTR_INVALID_UTF8 = 2,
+    EZSTR_OUT_OF_BOUNDS = 3,
+    EZSTR_INVALID_REGEX = 4,
+    EZSTR_NOT_FOUND = 5,
+} EzStrStatus;
+
+/* Opaque; only ever handled through pointers returned by this API. */
+typedef struct EzStr EzStr;
+
+/* Creates an EzStr from `len` bytes of UTF-8 at `data`. Free it with ezstr_free. */
+EzStrStatus ezstr_new(const uint8_t *data, size_t len, EzStr **out);
+
+/* Frees an EzStr returned by this API. NULL is ignored. */
+void ezstr_free(EzStr *s);
+
+/* Writes the number of graphemes in `s` to `out`. */
+EzStrStatus ezstr_len(const EzStr *s, size_t *out);
+
+/* Points `out_data`/`out_len` at the UTF-8 text of `s`; valid until `s` is freed.
+ * The text is not NUL-terminated. */
+EzStrStatus ezstr_as_utf8(const EzStr *s, const uint8_t **out_data, size_t *out_len);
+
+/* Copies graphemes `start..end` of `s` into a new EzStr. */
+EzStrStatus ezstr_slice(const EzStr *s, size_t start, size_t end, EzStr **out);
+
+/* Finds the first match of the regex `pattern` and writes its grapheme span to
+ * `out_start`/`out_end`. Returns EZSTR_NOT_FOUND when there is no match. */
+EzStrStatus ezstr_find(const EzStr *s, const uint8_t *pattern, size_t pattern_len,
+                       size_t *out_start, size_t *out_end);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* EZSTR_H */
@@ -0,0 +1,145 @@
+use crate::{EzStr, GSpan, SpanMap};
+
+/// A label attached to a grapheme span of an `AnnotatedEzStr`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Annotation<T> {
+    pub span: GSpan,
+    pub label: T,
+}
+
+/// Text with labeled grapheme spans, e.g. token kinds for syntax highlighting.
+///
+/// Annotations may be layered (`annotate`) or kept disjoint (`annotate_exclusive`), and
+/// are kept in order of their start. Edits through `with_replaced` or a transformation
+/// described by a `SpanMap` move them along with the text they cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedEzStr<T> {
+    text: EzStr,
+    annotations: Vec<Annotation<T>>,
+}
+
+impl<T> AnnotatedEzStr<T> {
+    pub fn new<S: Into<EzStr>>(text: S) -> Self {
+        AnnotatedEzStr { text: text.into(), annotations: Vec::new() }
+    }
+
+    pub fn text(&self) -> &EzStr {
+        &self.text
+    }
+
+    /// All annotations, ordered by start, then by insertion.
+    pub fn annotations(&self) -> &[Annotation<T>] {
+        &self.annotations
+    }
+
+    /// Adds `label` over `span`, on top of any annotation already there.
+    /// Panics if `span` is out of bounds.
+    pub fn annotate<S: Into<GSpan>>(&mut self, span: S, label: T) {
+        let span = span.into();
+        assert!(
+            span.start <= span.end && span.end <= self.text.len(),
+            "annotation {:?} out of bounds for length {}",
+            span,
+            self.text.len()
+        );
+        let at = self.annotations.partition_point(|a| a.span.start <= span.start);
+        self.annotations.insert(at, Annotation { span, label });
+    }
+
+    /// Adds `label` over `span` unless it overlaps an existing annotation; returns
+    /// whether it was added.
+    pub fn annotate_exclusive<S: Into<GSpan>>(&mut self, span: S, label: T) -> bool {
+        let span = span.into();
+        if self.annotations.iter().any(|a| a.span.overlaps(span)) {
+            return false;
+        }
+        self.annotate(span, label);
+        true
+    }
+
+    /// Annotations covering grapheme `index`, in the order of `annotations`.
+    pub fn annotations_at(&self, index: usize) -> impl Iterator<Item = &Annotation<T>> {
+        self.annotations.iter().filter(move |a| a.span.contains(index))
+    }
+
+    /// Labels covering grapheme `index`.
+    pub fn labels_at(&self, index: usize) -> impl Iterator<Item = &T> {
+        self.annotations_at(index).map(|a| &a.label)
+    }
+
+    /// Splits the text at every annotation edge, giving each piece with the labels
+    /// covering it. Useful for rendering: each piece gets one style.
+    pub fn segments(&self) -> Vec<(GSpan, Vec<&T>)> {
+        let mut edges: Vec<usize> = self.annotations.iter().flat_map(|a| [a.span.start, a.span.end]).collect();
+        edges.extend([0, self.text.len()]);
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+            .windows(2)
+            .map(|w| {
+                let span = GSpan::new(w[0], w[1]);
+                let labels = self.annotations.iter().filter(|a| a.span.start <= span.start && span.end <= a.span.end);
+                (span, labels.map(|a| &a.label).collect())
+            })
+            .collect()
+    }
+
+    /// Replaces the graphemes in `range`, like `EzStr::with_replaced`. Annotations after
+    /// the edit move with the text, annotations around it grow or shrink with it, and
+    /// annotations partly inside it are cut back to the unchanged text. Text inserted
+    /// exactly at an annotation's edge is left outside it. Annotations that end up empty
+    /// are dropped.
+    pub fn with_replaced<S: Into<GSpan>, R: AsRef<str>>(&self, range: S, replacement: R) -> Self
+    where
+        T: Clone,
+    {
+        let range = range.into();
+        let text = self.text.with_replaced(range, replacement);
+        let new_end = (range.end + text.len()).saturating_sub(self.text.len()).max(range.start);
+        let moved = |i: usize| i + new_end - range.end;
+        let annotations = self
+            .annotations
+            .iter()
+            .filter_map(|a| {
+                let (s, e) = (a.span.start, a.span.end);
+                let start = if s < range.start || (s == range.start && !range.is_empty()) {
+                    s
+                } else if s >= range.end {
+                    moved(s)
+                } else {
+                    new_end
+                };
+                if a.span.is_empty() {
+                    return Some(Annotation { span: GSpan::new(start, start), label: a.label.clone() });
+                }
+                let end = if e <= range.start {
+                    e
+                } else if e >= range.end {
+                    moved(e)
+                } else {
+                    range.start
+                };
+                (start < end).then(|| Annotation { span: GSpan::new(start, end), label: a.label.clone() })
+            })
+            .collect();
+        AnnotatedEzStr { text, annotations }
+    }
+
+    /// Carries the annotations over to `text`, the result of a transformation of this
+    /// text described by `map` (for instance from `transliterate` or
+    /// `collapse_whitespace`). Annotations whose text was removed entirely are dropped.
+    pub fn remap(&self, text: EzStr, map: &SpanMap) -> Self
+    where
+        T: Clone,
+    {
+        let annotations = self
+            .annotations
+            .iter()
+            .filter_map(|a| {
+                let out = map.to_output(a.span.range())?;
+                (a.span.is_empty() || !out.is_empty()).then(|| Annotation { span: out.into(), label: a.label.clone() })
+            })
+            .collect();
+        AnnotatedEzStr { text, annotations }
+    }
+}
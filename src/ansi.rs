@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use crate::EzStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// One of the 256 palette colors.
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn sgr(self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match self {
+            Color::Black => base.to_string(),
+            Color::Red => (base + 1).to_string(),
+            Color::Green => (base + 2).to_string(),
+            Color::Yellow => (base + 3).to_string(),
+            Color::Blue => (base + 4).to_string(),
+            Color::Magenta => (base + 5).to_string(),
+            Color::Cyan => (base + 6).to_string(),
+            Color::White => (base + 7).to_string(),
+            Color::Fixed(n) => format!("{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+/// Terminal text style, built with chained calls: `Style::new().fg(Color::Red).bold()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// The SGR escape sequence that switches to this style.
+    pub fn prefix(&self) -> String {
+        let mut codes: Vec<String> = Vec::new();
+        if self.bold {
+            codes.push("1".into());
+        }
+        if self.italic {
+            codes.push("3".into());
+        }
+        if self.underline {
+            codes.push("4".into());
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.sgr(false));
+        }
+        if let Some(bg) = self.bg {
+            codes.push(bg.sgr(true));
+        }
+        if codes.is_empty() {
+            return String::new();
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+pub const RESET: &str = "\x1b[0m";
+
+/// Byte length of the ANSI escape sequence (CSI or OSC) at the start of `s`, if any.
+fn escape_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.first() != Some(&0x1b) {
+        return None;
+    }
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)).map(|p| p + 3),
+        Some(b']') => {
+            let bel = bytes[2..].iter().position(|&b| b == 0x07).map(|p| p + 3);
+            let st = s[2..].find("\x1b\\").map(|p| p + 4);
+            match (bel, st) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+        // a two-char escape such as ESC c; the second char may be multi-byte
+        Some(_) => s[1..].chars().next().map(|c| 1 + c.len_utf8()),
+        None => Some(1),
+    }
+}
+
+impl EzStr {
+    /// Wraps grapheme ranges in SGR escape sequences. Where ranges overlap the one
+    /// listed last wins; unstyled text between them is left as is.
+    pub fn style_spans(&self, spans: &[(Range<usize>, Style)]) -> EzStr {
+        // sweep the graphemes once, keeping the spans covering the current one by list
+        // position so the last listed is the highest
+        let len = self.len();
+        let ranges = spans.iter().enumerate().filter(|(_, (range, _))| range.start < range.end);
+        let mut starts: Vec<(usize, usize)> = ranges.clone().map(|(k, (range, _))| (range.start, k)).collect();
+        let mut ends: Vec<(usize, usize)> = ranges.map(|(k, (range, _))| (range.end, k)).collect();
+        starts.sort_unstable();
+        ends.sort_unstable();
+        let (mut next_start, mut next_end) = (0, 0);
+        let mut active = BTreeSet::new();
+
+        let data = self.as_str();
+        let mut out = String::with_capacity(data.len());
+        let mut current = Style::default();
+        let bounds: Vec<usize> = self.grapheme_index().iter().chain([data.len()]).collect();
+        for i in 0..len {
+            while next_start < starts.len() && starts[next_start].0 <= i {
+                active.insert(starts[next_start].1);
+                next_start += 1;
+            }
+            while next_end < ends.len() && ends[next_end].0 <= i {
+                active.remove(&ends[next_end].1);
+                next_end += 1;
+            }
+            let style = active.last().map_or(Style::default(), |&k| spans[k].1);
+            if style != current {
+                if current != Style::default() {
+                    out += RESET;
+                }
+                out += &style.prefix();
+                current = style;
+            }
+            out += &data[bounds[i]..bounds[i + 1]];
+        }
+        if current != Style::default() {
+            out += RESET;
+        }
+        EzStr::new(out)
+    }
+
+    /// Removes ANSI escape sequences (SGR colors, cursor movement, OSC links).
+    pub fn strip_ansi(&self) -> EzStr {
+        let data = self.as_str();
+        let mut out = String::with_capacity(data.len());
+        let mut i = 0;
+        while i < data.len() {
+            if let Some(len) = escape_len(&data[i..]) {
+                i += len;
+                continue;
+            }
+            let c = data[i..].chars().next().unwrap();
+            out.push(c);
+            i += c.len_utf8();
+        }
+        EzStr::new(out)
+    }
+
+    /// Display width ignoring ANSI escape sequences.
+    pub fn display_width_ansi(&self) -> usize {
+        self.strip_ansi().display_width()
+    }
+}
@@ -0,0 +1,180 @@
+//! Binary archive of an `EzStr` and its grapheme index, so a static corpus can be
+//! loaded without running grapheme segmentation again. `ArchivedEzStr` reads an
+//! archive in place, e.g. from a memory-mapped file, without copying the text or
+//! decoding the index.
+use regex::Regex;
+
+use crate::{EzStr, GraphemeIndex, GraphemeMatch};
+
+const MAGIC: &[u8; 4] = b"EZS\x01";
+
+/// Why `EzStr::from_archive` rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    BadMagic,
+    Truncated,
+    InvalidUtf8,
+    /// Grapheme boundaries that are unsorted, out of range or not on a char boundary.
+    InvalidIndex,
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::BadMagic => write!(f, "not an ezstr archive"),
+            ArchiveError::Truncated => write!(f, "archive is truncated"),
+            ArchiveError::InvalidUtf8 => write!(f, "archived text is not valid UTF-8"),
+            ArchiveError::InvalidIndex => write!(f, "archived grapheme index is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+fn read_u64(bytes: &[u8], at: &mut usize) -> Result<u64, ArchiveError> {
+    let chunk = bytes.get(*at..*at + 8).ok_or(ArchiveError::Truncated)?;
+    *at += 8;
+    Ok(u64::from_le_bytes(chunk.try_into().unwrap()))
+}
+
+impl EzStr {
+    /// Serializes the text and its grapheme boundaries: a 4 byte magic, the text length
+    /// and bytes, then the grapheme count and each grapheme's byte offset, all little-endian u64.
+    pub fn to_archive(&self) -> Vec<u8> {
+        let index = self.grapheme_index();
+        let mut out = Vec::with_capacity(4 + 16 + self.data.len() + index.len() * 8);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&(self.data.len() as u64).to_le_bytes());
+        out.extend_from_slice(self.data.as_bytes());
+        out.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        for byte in index.iter() {
+            out.extend_from_slice(&(byte as u64).to_le_bytes());
+        }
+        out
+    }
+
+    /// Copies an archive written by `to_archive` into an owned `EzStr` with its grapheme
+    /// index filled in; see `ArchivedEzStr::new` for the checks made and for using the
+    /// archive in place instead.
+    pub fn from_archive(bytes: &[u8]) -> Result<EzStr, ArchiveError> {
+        ArchivedEzStr::new(bytes).map(|archived| archived.to_ezstr())
+    }
+}
+
+/// An archive written by `EzStr::to_archive`, used in place: the text is borrowed from
+/// the buffer and grapheme offsets are read from it on demand.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchivedEzStr<'a> {
+    text: &'a str,
+    // `len` little-endian u64 byte offsets
+    offsets: &'a [u8],
+    len: usize,
+}
+
+impl<'a> ArchivedEzStr<'a> {
+    /// Checks the archive: the text must be UTF-8 and the boundaries ascending char
+    /// boundaries starting at 0. Boundaries are trusted to be grapheme boundaries, so
+    /// nothing is segmented.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        if bytes.get(..4) != Some(&MAGIC[..]) {
+            return Err(ArchiveError::BadMagic);
+        }
+        let mut at = 4;
+        let text_len = read_u64(bytes, &mut at)? as usize;
+        let text = bytes.get(at..at.saturating_add(text_len)).ok_or(ArchiveError::Truncated)?;
+        let text = std::str::from_utf8(text).map_err(|_| ArchiveError::InvalidUtf8)?;
+        at += text_len;
+
+        let len = read_u64(bytes, &mut at)? as usize;
+        if bytes.len().saturating_sub(at) / 8 < len {
+            return Err(ArchiveError::Truncated);
+        }
+        let archived = ArchivedEzStr { text, offsets: &bytes[at..at + len * 8], len };
+        let mut prev = None;
+        for i in 0..len {
+            let byte = archived.offset(i);
+            let follows = prev.map_or(byte == 0, |prev| byte > prev);
+            if !follows || byte >= text.len() || !text.is_char_boundary(byte) {
+                return Err(ArchiveError::InvalidIndex);
+            }
+            prev = Some(byte);
+        }
+        if len == 0 && !text.is_empty() {
+            return Err(ArchiveError::InvalidIndex);
+        }
+        Ok(archived)
+    }
+
+    fn offset(&self, index: usize) -> usize {
+        u64::from_le_bytes(self.offsets[index * 8..index * 8 + 8].try_into().unwrap()) as usize
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Number of graphemes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Byte offset at which grapheme `index` starts; `len()` maps to the end of the text.
+    pub fn byte_offset(&self, index: usize) -> Option<usize> {
+        match index.cmp(&self.len) {
+            std::cmp::Ordering::Less => Some(self.offset(index)),
+            std::cmp::Ordering::Equal => Some(self.text.len()),
+            std::cmp::Ordering::Greater => None,
+        }
+    }
+
+    /// Grapheme `index`, borrowed from the archive.
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        (index < self.len).then(|| &self.text[self.offset(index)..self.byte_offset(index + 1).unwrap()])
+    }
+
+    /// Graphemes `start..end`, borrowed from the archive. Panics if `start > end` or
+    /// `end > len()`.
+    pub fn slice(&self, start: usize, end: usize) -> &'a str {
+        assert!(start <= end && end <= self.len, "grapheme range {}..{} out of bounds for length {}", start, end, self.len);
+        &self.text[self.byte_offset(start).unwrap()..self.byte_offset(end).unwrap()]
+    }
+
+    /// First grapheme starting at or after `byte`.
+    fn grapheme_at_or_after(&self, byte: usize) -> usize {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.offset(mid) < byte { lo = mid + 1 } else { hi = mid }
+        }
+        lo
+    }
+
+    /// First match of `regex`, widened to whole graphemes. Only the match is copied.
+    pub fn find(&self, regex: &Regex) -> Option<GraphemeMatch> {
+        regex.find(self.text).map(|m| {
+            let (start, end) = (self.grapheme_at_or_after(m.start()), self.grapheme_at_or_after(m.end()));
+            let (byte_start, byte_end) = (self.byte_offset(start).unwrap(), self.byte_offset(end).unwrap());
+            let char_start = self.text[..byte_start].chars().count();
+            GraphemeMatch {
+                start,
+                end,
+                text: EzStr::new(&self.text[byte_start..byte_end]),
+                byte_start,
+                byte_end,
+                char_start,
+                char_end: char_start + self.text[byte_start..byte_end].chars().count(),
+            }
+        })
+    }
+
+    /// Copies the text into an `EzStr`, reusing the archived boundaries as its index.
+    pub fn to_ezstr(&self) -> EzStr {
+        let it = EzStr::new(self.text);
+        let _ = it.grapheme_index_data.set(GraphemeIndex::from_offsets((0..self.len).map(|i| self.offset(i))));
+        it
+    }
+}
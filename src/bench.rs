@@ -0,0 +1,168 @@
+//! Fixtures and timing helpers for benchmarking grapheme-heavy work on representative text.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::EzStr;
+
+/// A named benchmark text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corpus {
+    pub name: String,
+    pub text: EzStr,
+}
+
+const ENGLISH: &str = "[Verse]\nThe night bus hums along the river road,\nA1 C1 G1 and the radio low.\n";
+const CJK: &str = "夜の街に雨が降る。静かな歌が聞こえる。\n今夜も月がきれいですね。\n";
+const EMOJI: &str = "🎸🎹 tonight 👨‍👩‍👧 at the 🇫🇷 café ✨👍🏽\n";
+const COMBINING: &str = "noe\u{308}l cafe\u{301} Z\u{324}\u{354}a\u{308}\u{356}l\u{36e}g\u{30c}o\u{319} \u{915}\u{94d}\u{937}\n";
+const MIXED: &str = "Amm7 | 夜 | noël | שלום | مرحبا | 🎸 | Ελλάδα | Привет\n";
+
+impl Corpus {
+    pub fn new<S: Into<String>>(name: S, text: EzStr) -> Self {
+        Corpus { name: name.into(), text }
+    }
+
+    fn fixture(name: &str, unit: &str, bytes: usize) -> Self {
+        Corpus::new(name, EzStr::new(unit.repeat(bytes.div_ceil(unit.len()).max(1))))
+    }
+
+    /// Mostly ASCII lyrics and chord lines.
+    pub fn english(bytes: usize) -> Self {
+        Corpus::fixture("english", ENGLISH, bytes)
+    }
+
+    pub fn cjk(bytes: usize) -> Self {
+        Corpus::fixture("cjk", CJK, bytes)
+    }
+
+    /// Emoji with ZWJ sequences, flags and skin tone modifiers.
+    pub fn emoji(bytes: usize) -> Self {
+        Corpus::fixture("emoji", EMOJI, bytes)
+    }
+
+    /// Stacked combining marks and Devanagari conjuncts.
+    pub fn combining(bytes: usize) -> Self {
+        Corpus::fixture("combining", COMBINING, bytes)
+    }
+
+    /// Several scripts, including right-to-left ones, on each line.
+    pub fn mixed(bytes: usize) -> Self {
+        Corpus::fixture("mixed", MIXED, bytes)
+    }
+
+    /// Every built-in fixture at roughly `bytes` bytes each.
+    pub fn all(bytes: usize) -> Vec<Corpus> {
+        vec![
+            Corpus::english(bytes),
+            Corpus::cjk(bytes),
+            Corpus::emoji(bytes),
+            Corpus::combining(bytes),
+            Corpus::mixed(bytes),
+        ]
+    }
+
+    /// Loads a user corpus, named after the file.
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let name = path.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        Ok(Corpus::new(name, EzStr::new(std::fs::read_to_string(path)?)))
+    }
+}
+
+/// Median time of one call to `f` over `iterations` runs.
+pub fn measure<T, F: FnMut() -> T>(iterations: usize, mut f: F) -> Duration {
+    let mut times: Vec<Duration> = (0..iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[times.len() / 2]
+}
+
+/// A timing that exceeded its baseline by more than the allowed ratio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub name: String,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl std::fmt::Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} regressed: {:?} -> {:?}", self.name, self.baseline, self.current)
+    }
+}
+
+impl std::error::Error for Regression {}
+
+/// Fails when `current` is more than `max_ratio` times `baseline`, e.g. 1.10 allows 10%.
+pub fn check_regression(name: &str, baseline: Duration, current: Duration, max_ratio: f64) -> Result<(), Regression> {
+    if current.as_secs_f64() > baseline.as_secs_f64() * max_ratio {
+        return Err(Regression { name: name.to_string(), baseline, current });
+    }
+    Ok(())
+}
+
+/// Timings saved by an earlier run, to compare a new run against. Stored as one
+/// `name nanoseconds` line per timing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Baseline {
+    timings: BTreeMap<String, Duration>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Baseline::default()
+    }
+
+    /// Reads a baseline written by `save`; a missing file gives an empty baseline.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Baseline::new()),
+            Err(e) => return Err(e),
+        };
+        let mut baseline = Baseline::new();
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            let parsed = line.rsplit_once(' ').and_then(|(name, nanos)| Some((name, nanos.parse::<u64>().ok()?)));
+            let Some((name, nanos)) = parsed else {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("bad baseline line {:?}", line)));
+            };
+            baseline.insert(name, Duration::from_nanos(nanos));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let lines: String = self.timings.iter().map(|(name, time)| format!("{} {}\n", name, time.as_nanos())).collect();
+        std::fs::write(path, lines)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Duration> {
+        self.timings.get(name).copied()
+    }
+
+    pub fn insert(&mut self, name: &str, time: Duration) {
+        self.timings.insert(name.to_string(), time);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+
+    /// `check_regression` against the saved timing of `name`; passes if there is none.
+    pub fn check(&self, name: &str, current: Duration, max_ratio: f64) -> Result<(), Regression> {
+        match self.get(name) {
+            Some(baseline) => check_regression(name, baseline, current, max_ratio),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Bidirectional text analysis after UAX #9, without explicit embedding controls:
+//! the implicit rules (W1–W7, N0–N2, I1–I2, L1, L2 and bracket mirroring) are applied
+//! per paragraph, and explicit formatting characters are treated as boundary neutrals.
+
+use crate::EzStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    L,
+    R,
+    AL,
+    EN,
+    AN,
+    ES,
+    ET,
+    CS,
+    NSM,
+    BN,
+    B,
+    S,
+    WS,
+    ON,
+}
+
+use BidiClass::*;
+
+fn bidi_class(c: char) -> BidiClass {
+    let cp = c as u32;
+    match cp {
+        0x000A | 0x000D | 0x001C..=0x001E | 0x0085 | 0x2029 => B,
+        0x0009 | 0x000B | 0x001F => S,
+        0x000C | 0x0020 | 0x1680 | 0x2000..=0x200A | 0x2028 | 0x205F | 0x3000 => WS,
+        0x0030..=0x0039 | 0x00B2 | 0x00B3 | 0x00B9 | 0x06F0..=0x06F9 | 0x2070..=0x2079
+        | 0xFF10..=0xFF19 => EN,
+        0x0660..=0x0669 | 0x066B | 0x066C => AN,
+        0x002B | 0x002D | 0x207A | 0x207B | 0xFF0B | 0xFF0D => ES,
+        0x0023..=0x0025 | 0x00A2..=0x00A5 | 0x00B0 | 0x00B1 | 0x066A | 0x20A0..=0x20CF
+        | 0x2030..=0x2034 => ET,
+        0x002C | 0x002E | 0x002F | 0x003A | 0x00A0 | 0x060C | 0x202F | 0x2044 | 0xFF0C
+        | 0xFF0E | 0xFF0F | 0xFF1A => CS,
+        0x200B..=0x200D | 0x200E | 0x200F | 0x202A..=0x202E | 0x2060..=0x2069 | 0xFEFF => BN,
+        0x0591..=0x05BD | 0x05BF | 0x05C1 | 0x05C2 | 0x05C4 | 0x05C5 | 0x05C7 => NSM,
+        0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC | 0x06DF..=0x06E4 | 0x06E7 | 0x06E8
+        | 0x06EA..=0x06ED => NSM,
+        0x0590..=0x05FF | 0x07C0..=0x085F | 0xFB1D..=0xFB4F | 0x10800..=0x10FFF
+        | 0x1E800..=0x1EDFF => R,
+        0x0600..=0x07BF | 0x0860..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF
+        | 0x1EE00..=0x1EEFF => AL,
+        _ if crate::script::char_script(c) == crate::script::Script::Inherited => NSM,
+        _ if c.is_alphabetic() => L,
+        _ if c.is_ascii_control() => BN,
+        _ => ON,
+    }
+}
+
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        _ => c,
+    }
+}
+
+fn opening_bracket(c: char) -> Option<char> {
+    match c {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+/// Pairs of (opening, closing) bracket positions, sorted by opening position (BD16).
+fn bracket_pairs(chars: &[char], types: &[BidiClass]) -> Vec<(usize, usize)> {
+    let mut stack: Vec<(char, usize)> = Vec::new();
+    let mut pairs = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if types[i] != ON {
+            continue;
+        }
+        if let Some(closing) = opening_bracket(c) {
+            if stack.len() == 63 {
+                break;
+            }
+            stack.push((closing, i));
+        } else if let Some(depth) = stack.iter().rposition(|&(closing, _)| closing == c) {
+            pairs.push((stack[depth].1, i));
+            stack.truncate(depth);
+        }
+    }
+    pairs.sort();
+    pairs
+}
+
+/// Resolves the embedding level of every char of one paragraph.
+fn paragraph_levels(chars: &[char], classes: &[BidiClass]) -> Vec<u8> {
+    let para_level: u8 = classes
+        .iter()
+        .find_map(|&c| match c {
+            L => Some(0),
+            R | AL => Some(1),
+            _ => None,
+        })
+        .unwrap_or(0);
+    let sos = if para_level % 2 == 0 { L } else { R };
+    let mut types: Vec<BidiClass> = classes.to_vec();
+
+    // W1: NSM takes the type of the previous char
+    let mut prev = sos;
+    for t in types.iter_mut() {
+        if *t == NSM {
+            *t = prev;
+        }
+        if *t != BN {
+            prev = *t;
+        }
+    }
+    // W2, W3: EN after AL becomes AN, then AL becomes R
+    let mut last_strong = sos;
+    for t in types.iter_mut() {
+        match *t {
+            L | R | AL => last_strong = *t,
+            EN if last_strong == AL => *t = AN,
+            _ => {}
+        }
+    }
+    for t in types.iter_mut() {
+        if *t == AL {
+            *t = R;
+        }
+    }
+    // W4: a single separator between two numbers of the same kind joins them
+    for i in 1..types.len().saturating_sub(1) {
+        let (before, after) = (types[i - 1], types[i + 1]);
+        match types[i] {
+            ES if before == EN && after == EN => types[i] = EN,
+            CS if before == after && matches!(before, EN | AN) => types[i] = before,
+            _ => {}
+        }
+    }
+    // W5: terminators next to European numbers become EN
+    let mut i = 0;
+    while i < types.len() {
+        if types[i] == ET {
+            let start = i;
+            while i < types.len() && types[i] == ET {
+                i += 1;
+            }
+            let touches_en = (start > 0 && types[start - 1] == EN) || (i < types.len() && types[i] == EN);
+            if touches_en {
+                types[start..i].iter_mut().for_each(|t| *t = EN);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    // W6: leftover separators and terminators are neutral
+    for t in types.iter_mut() {
+        if matches!(*t, ES | ET | CS) {
+            *t = ON;
+        }
+    }
+    // W7: EN preceded by L is L
+    let mut last_strong = sos;
+    for t in types.iter_mut() {
+        match *t {
+            L | R => last_strong = *t,
+            EN if last_strong == L => *t = L,
+            _ => {}
+        }
+    }
+    // N0: bracket pairs take the direction of their content, or of the context before them
+    let strong_dir = |t: BidiClass| match t {
+        L => Some(L),
+        R | EN | AN => Some(R),
+        _ => None,
+    };
+    for (open, close) in bracket_pairs(chars, &types) {
+        let inside: Vec<BidiClass> = types[open + 1..close].iter().filter_map(|&t| strong_dir(t)).collect();
+        let resolved = if inside.contains(&sos) {
+            Some(sos)
+        } else if let Some(&opposite) = inside.first() {
+            let before = types[..open].iter().rev().find_map(|&t| strong_dir(t)).unwrap_or(sos);
+            Some(if before == opposite { opposite } else { sos })
+        } else {
+            None
+        };
+        if let Some(dir) = resolved {
+            types[open] = dir;
+            types[close] = dir;
+        }
+    }
+    // N1, N2: neutral runs take the surrounding direction, else the embedding direction
+    let is_neutral = |t: BidiClass| matches!(t, B | S | WS | ON | BN);
+    let direction = |t: BidiClass| if t == L { L } else { R };
+    let mut i = 0;
+    while i < types.len() {
+        if !is_neutral(types[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < types.len() && is_neutral(types[i]) {
+            i += 1;
+        }
+        let before = if start == 0 { sos } else { direction(types[start - 1]) };
+        let after = if i == types.len() { sos } else { direction(types[i]) };
+        let resolved = if before == after { before } else { sos };
+        types[start..i].iter_mut().for_each(|t| *t = resolved);
+    }
+    // I1, I2
+    let mut levels: Vec<u8> = types
+        .iter()
+        .map(|&t| match (para_level % 2, t) {
+            (0, R) => para_level + 1,
+            (0, AN) | (0, EN) => para_level + 2,
+            (1, L) | (1, EN) | (1, AN) => para_level + 1,
+            _ => para_level,
+        })
+        .collect();
+    // L1: separators and trailing whitespace go back to the paragraph level
+    let mut trailing = true;
+    for i in (0..classes.len()).rev() {
+        match classes[i] {
+            B | S => {
+                levels[i] = para_level;
+                trailing = true;
+            }
+            WS | BN if trailing => levels[i] = para_level,
+            _ => trailing = false,
+        }
+    }
+    levels
+}
+
+/// Graphemes of an EzStr in display order, from `EzStr::visual_order`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualOrder {
+    /// The reordered text, with brackets mirrored in right-to-left runs.
+    pub text: EzStr,
+    /// `logical_indices[v]` is the logical grapheme index shown at visual position `v`.
+    pub logical_indices: Vec<usize>,
+}
+
+impl EzStr {
+    /// Resolved embedding level of each grapheme (even is left-to-right, odd right-to-left).
+    pub fn bidi_levels(&self) -> Vec<u8> {
+        let data = self.as_str();
+        let chars: Vec<(usize, char)> = data.char_indices().collect();
+        let mut char_levels = Vec::with_capacity(chars.len());
+        let mut start = 0;
+        for i in 0..chars.len() {
+            if bidi_class(chars[i].1) == B || i + 1 == chars.len() {
+                let paragraph: Vec<char> = chars[start..=i].iter().map(|&(_, c)| c).collect();
+                let classes: Vec<BidiClass> = paragraph.iter().map(|&c| bidi_class(c)).collect();
+                char_levels.extend(paragraph_levels(&paragraph, &classes));
+                start = i + 1;
+            }
+        }
+        // a grapheme takes the level of its base char
+        let mut levels = Vec::with_capacity(self.len());
+        let mut ci = 0;
+        for byte in self.grapheme_index().iter() {
+            while chars[ci].0 < byte {
+                ci += 1;
+            }
+            levels.push(char_levels[ci]);
+        }
+        levels
+    }
+
+    /// Reorders the graphemes of each line for display (rule L2).
+    pub fn visual_order(&self) -> VisualOrder {
+        let levels = self.bidi_levels();
+        let graphemes = self.graphemes();
+        let mut order: Vec<usize> = Vec::with_capacity(graphemes.len());
+        let mut line_start = 0;
+        for i in 0..graphemes.len() {
+            let is_break = graphemes[i].value.chars().any(|c| bidi_class(c) == B);
+            if is_break || i + 1 == graphemes.len() {
+                // the separator ending a line stays at its end, outside the reversal
+                let end = if is_break { i } else { i + 1 };
+                let mut line: Vec<usize> = (line_start..end).collect();
+                let line_levels = &levels[line_start..end];
+                let highest = line_levels.iter().copied().max().unwrap_or(0);
+                let lowest_odd = line_levels.iter().copied().filter(|l| l % 2 == 1).min();
+                if let Some(lowest_odd) = lowest_odd {
+                    for level in (lowest_odd..=highest).rev() {
+                        let mut j = 0;
+                        while j < line.len() {
+                            if levels[line[j]] >= level {
+                                let run_start = j;
+                                while j < line.len() && levels[line[j]] >= level {
+                                    j += 1;
+                                }
+                                line[run_start..j].reverse();
+                            } else {
+                                j += 1;
+                            }
+                        }
+                    }
+                }
+                order.extend(line);
+                if is_break {
+                    order.push(i);
+                }
+                line_start = i + 1;
+            }
+        }
+        let text: String = order
+            .iter()
+            .map(|&i| {
+                if levels[i] % 2 == 1 {
+                    graphemes[i].value.chars().map(mirror).collect()
+                } else {
+                    graphemes[i].value.to_string()
+                }
+            })
+            .collect();
+        VisualOrder {
+            text: EzStr::new(text),
+            logical_indices: order,
+        }
+    }
+}
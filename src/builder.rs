@@ -0,0 +1,76 @@
+use std::ops::Range;
+
+use crate::{segment, EzStr, Grapheme, GraphemeIndex};
+
+/// Assembles an `EzStr` from many pieces, remembering where each piece went.
+///
+/// Pieces are joined first and segmented once in `build`, so a piece that combines with
+/// the next (a base letter followed by a pushed accent) ends up in the same grapheme.
+#[derive(Debug, Clone, Default)]
+pub struct EzStrBuilder {
+    data: String,
+    pieces: Vec<Range<usize>>,
+}
+
+impl EzStrBuilder {
+    pub fn new() -> Self {
+        EzStrBuilder::default()
+    }
+
+    /// Reserves room for `bytes` bytes of text and `pieces` pushes up front.
+    pub fn with_capacity(bytes: usize, pieces: usize) -> Self {
+        EzStrBuilder { data: String::with_capacity(bytes), pieces: Vec::with_capacity(pieces) }
+    }
+
+    pub fn reserve(&mut self, bytes: usize) {
+        self.data.reserve(bytes);
+    }
+
+    /// Appends a `&str`, `String`, `EzStr` or `Grapheme`.
+    pub fn push<T: AsRef<str>>(&mut self, piece: T) -> &mut Self {
+        let start = self.data.len();
+        self.data += piece.as_ref();
+        self.pieces.push(start..self.data.len());
+        self
+    }
+
+    /// Bytes pushed so far.
+    pub fn byte_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The text with its grapheme caches already built.
+    pub fn build(self) -> EzStr {
+        self.build_with_spans().0
+    }
+
+    /// The text plus the grapheme span of every pushed piece, in push order. A piece
+    /// that shares a grapheme with its neighbour is widened to whole graphemes.
+    pub fn build_with_spans(self) -> (EzStr, Vec<Range<usize>>) {
+        let starts = segment::grapheme_starts(&self.data);
+        let start_of = |byte: usize| starts.partition_point(|&s| s <= byte).saturating_sub(1);
+        let end_of = |byte: usize| starts.partition_point(|&s| s < byte);
+        let spans = self
+            .pieces
+            .iter()
+            .map(|r| if r.is_empty() { end_of(r.start)..end_of(r.start) } else { start_of(r.start)..end_of(r.end) })
+            .collect();
+        let text = EzStr::new(self.data);
+        let _ = text.grapheme_index_data.set(GraphemeIndex::from_offsets(starts));
+        text.graphemes();
+        (text, spans)
+    }
+}
+
+impl std::fmt::Write for EzStrBuilder {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.push(s);
+        Ok(())
+    }
+}
+
+impl AsRef<str> for Grapheme {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
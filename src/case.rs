@@ -0,0 +1,178 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{EzStr, GraphemeMatch};
+
+/// Full case folding of one char, e.g. `ß` to `ss` and final `ς` to `σ`.
+pub fn fold_char(c: char, out: &mut String) {
+    match c {
+        'ß' | 'ẞ' => out.push_str("ss"),
+        'ς' => out.push('σ'),
+        'ſ' => out.push('s'),
+        'µ' => out.push('μ'),
+        'ŉ' => out.push_str("ʼn"),
+        'ǰ' => out.push_str("j\u{30C}"),
+        'ﬀ' => out.push_str("ff"),
+        'ﬁ' => out.push_str("fi"),
+        'ﬂ' => out.push_str("fl"),
+        'ﬃ' => out.push_str("ffi"),
+        'ﬄ' => out.push_str("ffl"),
+        'ﬅ' | 'ﬆ' => out.push_str("st"),
+        _ => out.extend(c.to_lowercase()),
+    }
+}
+
+/// Case-folded copy of `s`, for caseless comparison.
+pub fn fold_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    s.chars().for_each(|c| fold_char(c, &mut out));
+    out
+}
+
+impl EzStr {
+    /// Folds every grapheme, returning the folded text and the folded byte offset
+    /// at which each grapheme starts (plus the total length at the end).
+    fn folded_with_offsets(&self) -> (String, Vec<usize>) {
+        let mut folded = String::with_capacity(self.as_str().len());
+        let mut starts = Vec::with_capacity(self.len() + 1);
+        for g in self.graphemes() {
+            starts.push(folded.len());
+            g.as_str().chars().for_each(|c| fold_char(c, &mut folded));
+        }
+        starts.push(folded.len());
+        (folded, starts)
+    }
+
+    /// Like `contains`, ignoring case (full Unicode case folding, so "STRASSE" is found in "Straße").
+    pub fn contains_fold<T: AsRef<str>>(&self, needle: T) -> bool {
+        self.find_fold(needle).is_some()
+    }
+
+    /// First caseless occurrence of the literal `needle`, as a span in the original text.
+    pub fn find_fold<T: AsRef<str>>(&self, needle: T) -> Option<GraphemeMatch> {
+        self.find_iter_fold(needle).into_iter().next()
+    }
+
+    /// All non-overlapping caseless occurrences of the literal `needle`. Matches must
+    /// cover whole graphemes of the original text; "s" alone does not match inside "ß".
+    pub fn find_iter_fold<T: AsRef<str>>(&self, needle: T) -> Vec<GraphemeMatch> {
+        let needle = fold_case(needle.as_ref());
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let (folded, starts) = self.folded_with_offsets();
+        let mut ret = Vec::new();
+        let mut from = 0;
+        while let Some(pos) = folded[from..].find(&needle) {
+            let (start, end) = (from + pos, from + pos + needle.len());
+            match (starts.binary_search(&start), starts.binary_search(&end)) {
+                (Ok(g_start), Ok(g_end)) => {
+                    ret.push(GraphemeMatch::from_source(self, g_start, g_end));
+                    from = end;
+                }
+                _ => from = start + folded[start..].chars().next().unwrap().len_utf8(),
+            }
+        }
+        ret
+    }
+}
+
+/// Language-specific casing rules used by `EzStr::to_title_case`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaseRules {
+    Root,
+    /// "ij" is a single letter: "ijsselmeer" becomes "IJsselmeer".
+    Dutch,
+    /// Dotted and dotless i: "i" uppercases to "İ" and "I" lowercases to "ı".
+    Turkic,
+}
+
+impl CaseRules {
+    fn for_locale(locale: &str) -> CaseRules {
+        match locale.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase().as_str() {
+            "nl" => CaseRules::Dutch,
+            "tr" | "az" => CaseRules::Turkic,
+            _ => CaseRules::Root,
+        }
+    }
+
+    fn push_lower(self, s: &str, out: &mut String) {
+        match self {
+            CaseRules::Turkic => out.push_str(&s.replace('I', "ı").replace('İ', "i").to_lowercase()),
+            _ => out.push_str(&s.to_lowercase()),
+        }
+    }
+
+    fn push_title(self, c: char, out: &mut String) {
+        match c {
+            'ǆ' | 'ǅ' | 'Ǆ' => out.push('ǅ'),
+            'ǉ' | 'ǈ' | 'Ǉ' => out.push('ǈ'),
+            'ǌ' | 'ǋ' | 'Ǌ' => out.push('ǋ'),
+            'ǳ' | 'ǲ' | 'Ǳ' => out.push('ǲ'),
+            'i' if self == CaseRules::Turkic => out.push('İ'),
+            _ => out.extend(c.to_uppercase()),
+        }
+    }
+
+    /// Uppercases the first letter of `word` and lowercases the rest, keeping the
+    /// marks of the first grapheme on it. Words not starting with a letter are only
+    /// lowercased.
+    fn push_capitalized(self, word: &str, out: &mut String) {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(c) if c.is_alphabetic() => {
+                let rest = chars.as_str();
+                let ij = self == CaseRules::Dutch && (c == 'i' || c == 'I') && rest.starts_with(['j', 'J']);
+                if ij {
+                    out.push_str("IJ");
+                    self.push_lower(&rest[1..], out);
+                } else {
+                    self.push_title(c, out);
+                    let marks = rest.len() - rest.trim_start_matches(crate::is_combining_mark).len();
+                    out.push_str(&rest[..marks]);
+                    self.push_lower(&rest[marks..], out);
+                }
+            }
+            _ => self.push_lower(word, out),
+        }
+    }
+}
+
+impl EzStr {
+    /// Capitalizes every word (UAX #29 word boundaries) and lowercases the rest of it.
+    /// Apostrophes stay inside a word ("don't" gives "Don't") while hyphens separate
+    /// words ("jean-luc" gives "Jean-Luc"). `locale` selects Dutch "IJ" and Turkish
+    /// dotted i; other tags use the default rules.
+    pub fn to_title_case(&self, locale: &str) -> EzStr {
+        let rules = CaseRules::for_locale(locale);
+        let mut out = String::with_capacity(self.as_str().len());
+        for word in self.as_str().split_word_bounds() {
+            rules.push_capitalized(word, &mut out);
+        }
+        EzStr::new(out)
+    }
+
+    /// Lowercases the text and capitalizes the first letter of each sentence. A sentence
+    /// starts at the beginning of the text and after `.`, `!` or `?` followed by
+    /// whitespace, whatever case the next word had.
+    pub fn to_sentence_case(&self) -> EzStr {
+        let data = self.as_str();
+        let mut out = String::with_capacity(data.len());
+        let mut start = 0;
+        let mut pending = true;
+        let mut after_terminal = false;
+        for (i, c) in data.char_indices() {
+            if pending && c.is_alphabetic() {
+                out.push_str(&data[start..i].to_lowercase());
+                CaseRules::Root.push_title(c, &mut out);
+                start = i + c.len_utf8();
+                pending = false;
+            }
+            if c.is_whitespace() && after_terminal {
+                pending = true;
+            }
+            after_terminal = matches!(c, '.' | '!' | '?') || after_terminal && c.is_whitespace();
+        }
+        out.push_str(&data[start..].to_lowercase());
+        EzStr::new(out)
+    }
+}
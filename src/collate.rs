@@ -0,0 +1,106 @@
+use std::cmp::Ordering;
+
+use crate::case::fold_char;
+use crate::normalize::{nfc, nfd_chars};
+use crate::{is_combining_mark, EzStr};
+
+/// Letters a locale sorts as separate letters, each placed after the given one.
+struct Tailoring {
+    letters: &'static [(char, char)],
+    /// Accent differences are compared from the end of the string (Canadian French).
+    backwards_secondary: bool,
+}
+
+const ROOT: Tailoring = Tailoring { letters: &[], backwards_secondary: false };
+const SWEDISH: Tailoring = Tailoring { letters: &[('å', 'z'), ('ä', 'z'), ('æ', 'z'), ('ö', 'z'), ('ø', 'z')], backwards_secondary: false };
+const DANISH: Tailoring = Tailoring { letters: &[('æ', 'z'), ('ä', 'z'), ('ø', 'z'), ('ö', 'z'), ('å', 'z')], backwards_secondary: false };
+const SPANISH: Tailoring = Tailoring { letters: &[('ñ', 'n')], backwards_secondary: false };
+const CANADIAN_FRENCH: Tailoring = Tailoring { letters: &[], backwards_secondary: true };
+
+/// Tailoring for a BCP 47 tag such as "fr", "sv-SE" or "fr_CA"; unknown languages get
+/// the root order.
+fn tailoring(locale: &str) -> &'static Tailoring {
+    let locale = locale.to_ascii_lowercase().replace('_', "-");
+    let language = locale.split('-').next().unwrap_or("");
+    match language {
+        "sv" | "fi" => &SWEDISH,
+        "da" | "nb" | "nn" | "no" => &DANISH,
+        "es" => &SPANISH,
+        "fr" if locale.split('-').any(|part| part == "ca") => &CANADIAN_FRENCH,
+        _ => &ROOT,
+    }
+}
+
+// Primary weights: whitespace, punctuation and symbols sort first by code point, then
+// digits by value, then letters by code point of their folded base letter. Letters are
+// spaced out so tailored letters fit right after the one they follow.
+const DIGIT_BASE: u32 = 0x0020_0000;
+const LETTER_BASE: u32 = 0x0100_0000;
+
+fn primary(c: char) -> u32 {
+    if let Some(d) = c.to_digit(10) {
+        DIGIT_BASE + d
+    } else if c.is_alphanumeric() {
+        LETTER_BASE + ((c as u32) << 4)
+    } else {
+        c as u32 + 1
+    }
+}
+
+/// Sort key for `EzStr::collation_key`: byte-for-byte comparison of two keys gives the
+/// collated order of their strings, so keys can be computed once and sorted many times.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CollationKey(Vec<u32>);
+
+impl EzStr {
+    /// Multi-level sort key in the style of the Unicode Collation Algorithm: base letters
+    /// first, then accents, then case, so "é" sorts next to "e" rather than after "z".
+    /// `locale` tailors the order for Swedish and Finnish, Danish and Norwegian (å ä ö
+    /// after z), Spanish (ñ after n) and Canadian French (accents compared backwards).
+    pub fn collation_key(&self, locale: &str) -> CollationKey {
+        let tailoring = tailoring(locale);
+        let (mut primaries, mut secondaries, mut tertiaries) = (Vec::new(), Vec::new(), Vec::new());
+        let mut folded = String::new();
+        for c in nfc(self.as_str()).chars() {
+            let case = if c.is_uppercase() { 2 } else { 1 };
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            if let Some(pos) = tailoring.letters.iter().position(|&(letter, _)| letter == lower) {
+                primaries.push(primary(tailoring.letters[pos].1) + 1 + pos as u32);
+                secondaries.push(1);
+                tertiaries.push(case);
+                continue;
+            }
+            for d in nfd_chars(c.encode_utf8(&mut [0; 4])) {
+                if is_combining_mark(d) {
+                    secondaries.push(d as u32);
+                    continue;
+                }
+                folded.clear();
+                fold_char(d, &mut folded);
+                for f in folded.chars() {
+                    primaries.push(primary(f));
+                    secondaries.push(1);
+                    tertiaries.push(case);
+                }
+            }
+        }
+        if tailoring.backwards_secondary {
+            secondaries.reverse();
+        }
+        let mut key = primaries;
+        key.push(0);
+        key.extend(secondaries);
+        key.push(0);
+        key.extend(tertiaries);
+        CollationKey(key)
+    }
+
+    /// Compares by `collation_key` for `locale`, falling back to code point order so
+    /// that only identical strings compare equal.
+    pub fn cmp_collated<T: AsRef<str>>(&self, other: T, locale: &str) -> Ordering {
+        let other = EzStr::new(other.as_ref());
+        self.collation_key(locale)
+            .cmp(&other.collation_key(locale))
+            .then_with(|| self.as_str().cmp(other.as_str()))
+    }
+}
@@ -0,0 +1,133 @@
+//! A column of strings in Arrow's large-string layout: one contiguous UTF-8 buffer and
+//! `len + 1` i64 offsets. Grapheme boundaries are computed per element on first use.
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{EzStr, GIndex};
+
+/// Why `EzStrArray::from_parts` rejected its buffers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnarError {
+    /// Offsets are empty, decreasing, negative or past the end of the values.
+    InvalidOffsets,
+    /// An element does not start or end on a UTF-8 char boundary.
+    InvalidUtf8 { element: usize },
+}
+
+impl std::fmt::Display for ColumnarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnarError::InvalidOffsets => write!(f, "offsets are not a valid string array layout"),
+            ColumnarError::InvalidUtf8 { element } => write!(f, "element {} is not valid UTF-8", element),
+        }
+    }
+}
+
+impl std::error::Error for ColumnarError {}
+
+#[derive(Debug, Clone)]
+pub struct EzStrArray {
+    values: String,
+    offsets: Vec<i64>,
+    // grapheme start offsets of each element, relative to the element
+    boundaries: Vec<OnceCell<Vec<usize>>>,
+}
+
+impl EzStrArray {
+    /// Takes ownership of Arrow-style buffers, e.g. those of a `LargeStringArray`.
+    pub fn from_parts(values: Vec<u8>, offsets: Vec<i64>) -> Result<EzStrArray, ColumnarError> {
+        let sorted = offsets.windows(2).all(|w| w[0] <= w[1]);
+        let in_range = offsets.first().map_or(false, |&o| o >= 0)
+            && offsets.last().map_or(false, |&o| o as usize <= values.len());
+        if !sorted || !in_range {
+            return Err(ColumnarError::InvalidOffsets);
+        }
+        let values = String::from_utf8(values).map_err(|e| {
+            let bad = e.utf8_error().valid_up_to() as i64;
+            ColumnarError::InvalidUtf8 { element: offsets.partition_point(|&o| o <= bad).saturating_sub(1) }
+        })?;
+        if let Some(element) = offsets.iter().position(|&o| !values.is_char_boundary(o as usize)) {
+            return Err(ColumnarError::InvalidUtf8 { element: element.saturating_sub(1) });
+        }
+        let boundaries = (1..offsets.len()).map(|_| OnceCell::new()).collect();
+        Ok(EzStrArray { values, offsets, boundaries })
+    }
+
+    /// Gives the buffers back in the layout accepted by `from_parts`.
+    pub fn into_parts(self) -> (Vec<u8>, Vec<i64>) {
+        (self.values.into_bytes(), self.offsets)
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The text of element `i`, borrowed from the shared buffer.
+    pub fn value(&self, i: usize) -> &str {
+        &self.values[self.offsets[i] as usize..self.offsets[i + 1] as usize]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).map(move |i| self.value(i))
+    }
+
+    fn boundaries(&self, i: usize) -> &Vec<usize> {
+        self.boundaries[i].get_or_init(|| self.value(i).grapheme_indices(true).map(|(b, _)| b).collect())
+    }
+
+    /// Number of graphemes in element `i`.
+    pub fn grapheme_len(&self, i: usize) -> usize {
+        self.boundaries(i).len()
+    }
+
+    /// Graphemes `start..end` of element `i`, with the same clamping as `EzStr::slice_py`.
+    pub fn slice<S: Into<GIndex>, E: Into<GIndex>>(&self, i: usize, start: S, end: E) -> &str {
+        let value = self.value(i);
+        let boundaries = self.boundaries(i);
+        let len = boundaries.len();
+        let (start, end) = (start.into().resolve(len), end.into().resolve(len));
+        if start >= end {
+            return "";
+        }
+        let byte = |g: usize| boundaries.get(g).copied().unwrap_or(value.len());
+        &value[byte(start)..byte(end)]
+    }
+
+    /// Grapheme span of the first match of `regex` in element `i`.
+    pub fn find(&self, i: usize, regex: &Regex) -> Option<std::ops::Range<usize>> {
+        let boundaries = self.boundaries(i);
+        regex.find(self.value(i)).map(|m| {
+            let start = boundaries.partition_point(|&b| b < m.start());
+            let end = boundaries.partition_point(|&b| b < m.end());
+            start..end
+        })
+    }
+
+    /// Applies `slice` to every element, building a new column without per-row allocations.
+    pub fn slice_all<S: Into<GIndex> + Copy, E: Into<GIndex> + Copy>(&self, start: S, end: E) -> EzStrArray {
+        (0..self.len()).map(|i| self.slice(i, start, end)).collect()
+    }
+
+    /// Copies element `i` into an owned `EzStr`.
+    pub fn to_ezstr(&self, i: usize) -> EzStr {
+        EzStr::new(self.value(i))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for EzStrArray {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut values = String::new();
+        let mut offsets = vec![0];
+        for s in iter {
+            values += s.as_ref();
+            offsets.push(values.len() as i64);
+        }
+        let boundaries = (1..offsets.len()).map(|_| OnceCell::new()).collect();
+        EzStrArray { values, offsets, boundaries }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::diff::{myers_ops, DiffKind};
+use crate::{EzStr, GraphemeMatch};
+
+impl EzStr {
+    /// Longest run of graphemes found in both strings, as the matching spans in
+    /// `self` and in `other`. The earliest one in `self` wins ties.
+    pub fn longest_common_substring(&self, other: &EzStr) -> Option<(GraphemeMatch, GraphemeMatch)> {
+        let (a, b) = (self.graphemes(), other.graphemes());
+        let mut prev = vec![0usize; b.len() + 1];
+        let mut cur = vec![0usize; b.len() + 1];
+        let (mut best_len, mut best_a_end, mut best_b_end) = (0, 0, 0);
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                cur[j] = if a[i - 1] == b[j - 1] { prev[j - 1] + 1 } else { 0 };
+                if cur[j] > best_len {
+                    best_len = cur[j];
+                    best_a_end = i;
+                    best_b_end = j;
+                }
+            }
+            std::mem::swap(&mut prev, &mut cur);
+        }
+        if best_len == 0 {
+            return None;
+        }
+        Some((
+            GraphemeMatch::from_source(self, best_a_end - best_len, best_a_end),
+            GraphemeMatch::from_source(other, best_b_end - best_len, best_b_end),
+        ))
+    }
+
+    /// A longest common subsequence of graphemes, as aligned pairs of spans: each
+    /// pair is a run that is contiguous in both `self` and `other`.
+    pub fn longest_common_subsequence(&self, other: &EzStr) -> Vec<(GraphemeMatch, GraphemeMatch)> {
+        let mut runs: Vec<(usize, usize, usize)> = Vec::new(); // (start in self, start in other, len)
+        let (mut i, mut j) = (0, 0);
+        for op in myers_ops(self.graphemes(), other.graphemes()) {
+            match op {
+                DiffKind::Equal => {
+                    match runs.last_mut() {
+                        Some(run) if run.0 + run.2 == i && run.1 + run.2 == j => run.2 += 1,
+                        _ => runs.push((i, j, 1)),
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                DiffKind::Delete => i += 1,
+                DiffKind::Insert => j += 1,
+            }
+        }
+        runs.into_iter()
+            .map(|(i, j, len)| {
+                (
+                    GraphemeMatch::from_source(self, i, i + len),
+                    GraphemeMatch::from_source(other, j, j + len),
+                )
+            })
+            .collect()
+    }
+}
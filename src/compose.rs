@@ -0,0 +1,76 @@
+use crate::EzStr;
+
+/// Text being typed through an input method. The IME's preedit (a Hangul syllable
+/// still being built, kana before conversion) is kept apart from the committed text
+/// and is neither segmented nor counted until `commit_composition`, so lengths and
+/// carets computed from `committed` don't jump around while the user composes.
+///
+/// Appending goes through `EzStr::with_replaced`, so only the end of the committed
+/// text is segmented again on each commit.
+#[derive(Debug, Clone, Default)]
+pub struct ComposingEzStr {
+    committed: EzStr,
+    preedit: String,
+}
+
+impl ComposingEzStr {
+    pub fn new<T: Into<EzStr>>(text: T) -> Self {
+        ComposingEzStr { committed: text.into(), preedit: String::new() }
+    }
+
+    /// The text without the preedit.
+    pub fn committed(&self) -> &EzStr {
+        &self.committed
+    }
+
+    /// The uncommitted preedit, empty when not composing.
+    pub fn preedit(&self) -> &str {
+        &self.preedit
+    }
+
+    pub fn is_composing(&self) -> bool {
+        !self.preedit.is_empty()
+    }
+
+    /// Graphemes in the committed text; the preedit is not counted.
+    pub fn len(&self) -> usize {
+        self.committed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.committed.is_empty()
+    }
+
+    /// Appends already-final text. A pending preedit stays pending after it.
+    pub fn push_str<T: AsRef<str>>(&mut self, text: T) {
+        let end = self.committed.len();
+        self.committed.replace_range(end..end, text);
+    }
+
+    /// Sets the preedit to `partial`, replacing the previous one as IMEs send the whole
+    /// preedit on each update. An empty `partial` ends the composition without text.
+    pub fn push_composing<T: AsRef<str>>(&mut self, partial: T) {
+        self.preedit.clear();
+        self.preedit += partial.as_ref();
+    }
+
+    /// Appends the preedit to the committed text and returns how many graphemes the
+    /// committed text grew by, which is less than the preedit's own count when its
+    /// start joins the last committed grapheme.
+    pub fn commit_composition(&mut self) -> usize {
+        let before = self.committed.len();
+        let preedit = std::mem::take(&mut self.preedit);
+        self.push_str(preedit);
+        self.committed.len() - before
+    }
+
+    /// Drops the preedit, as when the user presses Escape in the IME.
+    pub fn cancel_composition(&mut self) {
+        self.preedit.clear();
+    }
+
+    /// Committed text followed by the preedit, for drawing the input field.
+    pub fn display(&self) -> String {
+        format!("{}{}", self.committed.as_str(), self.preedit)
+    }
+}
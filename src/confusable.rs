@@ -0,0 +1,193 @@
+use crate::emoji::is_emoji;
+use crate::script::{char_script, Script};
+use crate::{EzStr, GSpan};
+
+/// Why `EzStr::detect_suspicious` flagged a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SuspiciousKind {
+    /// Bidi embedding, override or isolate controls (U+202A..U+202E, U+2066..U+2069),
+    /// which can make text display in a different order than it is stored.
+    BidiControl,
+    /// Zero-width and other invisible chars: spaces, joiners outside emoji sequences,
+    /// direction marks, soft hyphens, word joiners and stray byte order marks.
+    Invisible,
+    /// Lookalike letters of `script` inside a word written mostly in another script,
+    /// such as a Cyrillic "а" in "pаypal".
+    MixedScript(Script),
+}
+
+/// A run of graphemes flagged by `EzStr::detect_suspicious`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Suspicious {
+    pub kind: SuspiciousKind,
+    pub span: GSpan,
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{AD}' | '\u{34F}' | '\u{61C}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    )
+}
+
+/// Prototype of `c` from the UTS #39 confusables data, for the Latin lookalikes that
+/// come up in practice: Cyrillic and Greek homoglyphs, digits and pipes that pass for
+/// letters, typographic hyphens and apostrophes, and invisible format chars (which map
+/// to nothing).
+fn prototype(c: char) -> Option<&'static str> {
+    Some(match c {
+        // Cyrillic
+        'а' => "a",
+        'в' => "B",
+        'е' | 'ё' => "e",
+        'о' => "o",
+        'р' => "p",
+        'с' => "c",
+        'у' | 'ү' => "y",
+        'х' => "x",
+        'ѕ' => "s",
+        'і' | 'ӏ' => "l",
+        'ј' => "j",
+        'ԁ' => "d",
+        'һ' => "h",
+        'ԛ' => "q",
+        'ԝ' => "w",
+        'б' => "6",
+        'А' => "A",
+        'В' => "B",
+        'Е' => "E",
+        'З' => "3",
+        'К' => "K",
+        'М' => "M",
+        'Н' => "H",
+        'О' => "O",
+        'Р' => "P",
+        'С' => "C",
+        'Т' => "T",
+        'У' | 'Ү' => "Y",
+        'Х' => "X",
+        'Ѕ' => "S",
+        'І' | 'Ӏ' => "l",
+        'Ј' => "J",
+        'Ԛ' => "Q",
+        'Ԝ' => "W",
+        // Greek
+        'α' => "a",
+        'ο' => "o",
+        'ν' => "v",
+        'ρ' => "p",
+        'ι' => "i",
+        'Α' => "A",
+        'Β' => "B",
+        'Ε' => "E",
+        'Ζ' => "Z",
+        'Η' => "H",
+        'Ι' => "l",
+        'Κ' => "K",
+        'Μ' => "M",
+        'Ν' => "N",
+        'Ο' => "O",
+        'Ρ' => "P",
+        'Τ' => "T",
+        'Υ' => "Y",
+        'Χ' => "X",
+        // ASCII and punctuation
+        '0' => "O",
+        '1' | 'I' | '|' => "l",
+        '‐' | '‑' | '‒' | '−' => "-",
+        '‘' | '’' | 'ʼ' | '`' | '´' => "'",
+        '\u{AD}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => "",
+        _ => return None,
+    })
+}
+
+impl EzStr {
+    /// UTS #39 skeleton: each char replaced by its confusable prototype, so strings
+    /// that look alike get the same skeleton ("раураl" in Cyrillic and "paypal" both
+    /// become "paypal"). Only meant for comparison, never for display.
+    pub fn skeleton(&self) -> EzStr {
+        let data = self.as_str();
+        let mut out = String::with_capacity(data.len());
+        for c in data.chars() {
+            // Fullwidth ASCII looks like ASCII.
+            let c = match c {
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap(),
+                _ => c,
+            };
+            match prototype(c) {
+                Some(p) => out += p,
+                None => out.push(c),
+            }
+        }
+        EzStr::new(out)
+    }
+
+    /// True if the two strings have the same skeleton, i.e. could be mistaken for each
+    /// other. Identical strings are confusable too.
+    pub fn is_confusable_with<T: AsRef<str>>(&self, other: T) -> bool {
+        self.skeleton() == EzStr::new(other.as_ref()).skeleton()
+    }
+
+    /// Flags what could make text look different from what it is, for vetting
+    /// user-submitted titles and names before showing them: bidi controls (as used in
+    /// Trojan Source attacks), invisible chars, and confusable letters from another
+    /// script hidden in a word. Adjacent graphemes flagged for the same reason are
+    /// reported as one span, in text order.
+    pub fn detect_suspicious(&self) -> Vec<Suspicious> {
+        let mut flags: Vec<Option<SuspiciousKind>> = self
+            .iter()
+            .map(|g| {
+                let s = g.as_str();
+                if s.chars().any(is_bidi_control) {
+                    Some(SuspiciousKind::BidiControl)
+                } else if s.chars().any(|c| is_invisible(c) && !(c == '\u{200D}' && is_emoji(s))) {
+                    Some(SuspiciousKind::Invisible)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let graphemes = self.graphemes();
+        for word in self.words() {
+            // scripts in order of first appearance, with their grapheme counts
+            let mut counts: Vec<(Script, usize)> = Vec::new();
+            for g in &graphemes[word.start..word.end] {
+                let script = g.script();
+                if matches!(script, Script::Common | Script::Inherited) {
+                    continue;
+                }
+                match counts.iter_mut().find(|(s, _)| *s == script) {
+                    Some((_, n)) => *n += 1,
+                    None => counts.push((script, 1)),
+                }
+            }
+            if counts.len() < 2 {
+                continue;
+            }
+            let main = counts.iter().rev().max_by_key(|(_, n)| *n).unwrap().0;
+            for i in word.start..word.end {
+                let g = &graphemes[i];
+                let script = g.script();
+                let lookalike = g.as_str().chars().next().is_some_and(|c| prototype(c).is_some());
+                if flags[i].is_none() && lookalike && script != main && !matches!(script, Script::Common | Script::Inherited) {
+                    flags[i] = Some(SuspiciousKind::MixedScript(script));
+                }
+            }
+        }
+
+        let mut found: Vec<Suspicious> = Vec::new();
+        for (i, flag) in flags.into_iter().enumerate() {
+            let Some(kind) = flag else { continue };
+            match found.last_mut() {
+                Some(last) if last.kind == kind && last.span.end == i => last.span.end = i + 1,
+                _ => found.push(Suspicious { kind, span: GSpan::new(i, i + 1) }),
+            }
+        }
+        found
+    }
+}
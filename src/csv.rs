@@ -0,0 +1,136 @@
+use crate::{EzStr, GraphemeMatch};
+
+/// Field delimiter and quote char for `EzStr::parse_csv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dialect {
+    pub delimiter: char,
+    pub quote: char,
+}
+
+impl Dialect {
+    /// Comma separated, `"` quoted.
+    pub const CSV: Dialect = Dialect { delimiter: ',', quote: '"' };
+    /// Tab separated, `"` quoted.
+    pub const TSV: Dialect = Dialect { delimiter: '\t', quote: '"' };
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: char) -> Self {
+        self.quote = quote;
+        self
+    }
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect::CSV
+    }
+}
+
+/// One field: its value with quotes removed and doubled quotes collapsed, and the span
+/// of the field as written, quotes included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvCell {
+    pub value: EzStr,
+    pub raw: GraphemeMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRow {
+    pub cells: Vec<CsvCell>,
+    /// The record without its line break.
+    pub raw: GraphemeMatch,
+}
+
+impl CsvRow {
+    /// Cell values, in order.
+    pub fn values(&self) -> impl Iterator<Item = &EzStr> {
+        self.cells.iter().map(|cell| &cell.value)
+    }
+}
+
+/// Why the text is not valid CSV; `at` is a grapheme index in the parsed text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    UnclosedQuote { at: usize },
+    /// Something other than a delimiter or line break follows a closing quote.
+    TextAfterQuote { at: usize },
+}
+
+impl std::fmt::Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::UnclosedQuote { at } => write!(f, "quoted field opened at {} is never closed", at),
+            CsvError::TextAfterQuote { at } => write!(f, "unexpected text after closing quote at {}", at),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl EzStr {
+    /// Parses CSV or TSV text into rows of cells with grapheme spans. Records end at
+    /// "\n" or "\r\n" outside quotes, a quoted field may span lines, and a final empty
+    /// line is not a row. A quote inside an unquoted field is kept literally.
+    pub fn parse_csv(&self, dialect: Dialect) -> Result<Vec<CsvRow>, CsvError> {
+        let data = self.as_str();
+        let span = |start: usize, end: usize| {
+            let (start, end) = self.byte_range_to_grapheme_indices(start, end);
+            GraphemeMatch::from_source(self, start, end)
+        };
+        let at = |byte: usize| self.byte_range_to_grapheme_indices(byte, byte).0;
+        let quote_len = dialect.quote.len_utf8();
+
+        let mut rows = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let row_start = pos;
+            let mut cells = Vec::new();
+            let row_end = loop {
+                let field_start = pos;
+                let (value, field_end) = if data[pos..].starts_with(dialect.quote) {
+                    let mut value = String::new();
+                    let mut i = pos + quote_len;
+                    loop {
+                        let close = data[i..].find(dialect.quote).ok_or(CsvError::UnclosedQuote { at: at(pos) })?;
+                        value += &data[i..i + close];
+                        i += close + quote_len;
+                        if data[i..].starts_with(dialect.quote) {
+                            value.push(dialect.quote);
+                            i += quote_len;
+                        } else {
+                            break;
+                        }
+                    }
+                    let rest = &data[i..];
+                    if !(rest.is_empty() || rest.starts_with(dialect.delimiter) || rest.starts_with(['\n', '\r'])) {
+                        return Err(CsvError::TextAfterQuote { at: at(i) });
+                    }
+                    (EzStr::new(value), i)
+                } else {
+                    let len = data[pos..].find(|c| c == dialect.delimiter || c == '\n' || c == '\r').unwrap_or(data.len() - pos);
+                    (EzStr::new(&data[pos..pos + len]), pos + len)
+                };
+                cells.push(CsvCell { value, raw: span(field_start, field_end) });
+                pos = field_end;
+                match data[pos..].chars().next() {
+                    Some(c) if c == dialect.delimiter => pos += c.len_utf8(),
+                    Some('\r') if data[pos..].starts_with("\r\n") => {
+                        pos += 2;
+                        break pos - 2;
+                    }
+                    Some(c @ ('\n' | '\r')) => {
+                        pos += c.len_utf8();
+                        break pos - 1;
+                    }
+                    _ => break pos,
+                }
+            };
+            rows.push(CsvRow { cells, raw: span(row_start, row_end) });
+        }
+        Ok(rows)
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::Range;
+
+use crate::{EzStr, GSpan};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of graphemes that is unchanged, inserted or deleted between two strings.
+///
+/// `old` and `new` are grapheme spans in each string; the span on the side a hunk
+/// does not touch is empty and marks where it applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub kind: DiffKind,
+    pub old: GSpan,
+    pub new: GSpan,
+    pub text: EzStr,
+}
+
+/// Shortest edit script between `a` and `b` (Myers' O(ND) algorithm), one op per element.
+pub(crate) fn myers_ops<T: PartialEq>(a: &[T], b: &[T]) -> Vec<DiffKind> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (n + m) as usize;
+    let offset = max as isize;
+    let mut v = vec![0isize; 2 * max + 2];
+    // before step d only diagonals -d..=d can have been reached, so that window of v
+    // is all the backtrack needs, keeping the trace at O(D^2) instead of O((N+M)D)
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+
+    let mut ops = Vec::with_capacity(max);
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let at = |k: isize| v[(k + d) as usize];
+        // step 0 starts from the origin, off the end of its one-diagonal window
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+            (at(prev_k), at(prev_k) - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            ops.push(DiffKind::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(DiffKind::Insert);
+                y -= 1;
+            } else {
+                ops.push(DiffKind::Delete);
+                x -= 1;
+            }
+        }
+    }
+    ops.reverse();
+    ops
+}
+
+/// Grapheme-level diff from `old` to `new`, as consecutive hunks covering both strings.
+pub fn diff(old: &EzStr, new: &EzStr) -> Vec<DiffHunk> {
+    let ops = myers_ops(old.graphemes(), new.graphemes());
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut o, mut n) = (0, 0);
+    for op in ops {
+        let (next_o, next_n) = match op {
+            DiffKind::Equal => (o + 1, n + 1),
+            DiffKind::Delete => (o + 1, n),
+            DiffKind::Insert => (o, n + 1),
+        };
+        match hunks.last_mut() {
+            Some(last) if last.kind == op => {
+                last.old.end = next_o;
+                last.new.end = next_n;
+            }
+            _ => hunks.push(DiffHunk { kind: op, old: GSpan::new(o, next_o), new: GSpan::new(n, next_n), text: EzStr::default() }),
+        }
+        o = next_o;
+        n = next_n;
+    }
+    for hunk in hunks.iter_mut() {
+        hunk.text = match hunk.kind {
+            DiffKind::Insert => new.slice_strict(hunk.new.start, hunk.new.end),
+            _ => old.slice_strict(hunk.old.start, hunk.old.end),
+        };
+    }
+    hunks
+}
+
+/// One change in a compact patch from `make_patch`: `deleted` at grapheme `at` of the
+/// old text is replaced by `inserted`. Unchanged text is not stored, only up to
+/// `context` graphemes of it on each side of the change for `EzStr::apply_hunks` to
+/// find the spot again after other edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchHunk {
+    pub at: usize,
+    pub before: EzStr,
+    pub deleted: EzStr,
+    pub inserted: EzStr,
+    pub after: EzStr,
+}
+
+/// Patch turning `old` into `new`, with `context` graphemes of anchoring text around
+/// each change. Its size grows with the changes rather than with the document.
+pub fn make_patch(old: &EzStr, new: &EzStr, context: usize) -> Vec<PatchHunk> {
+    // (old span, new span) of each run of adjacent deletes and inserts
+    let mut changes: Vec<(GSpan, GSpan)> = Vec::new();
+    let mut joined = false;
+    for hunk in diff(old, new) {
+        match (hunk.kind, changes.last_mut()) {
+            (DiffKind::Equal, _) => joined = false,
+            (_, Some(last)) if joined => {
+                last.0.end = hunk.old.end;
+                last.1.end = hunk.new.end;
+            }
+            _ => {
+                changes.push((hunk.old, hunk.new));
+                joined = true;
+            }
+        }
+    }
+    (0..changes.len())
+        .map(|i| {
+            let (old_span, new_span) = changes[i];
+            // context stops at the neighbouring changes
+            let floor = if i == 0 { 0 } else { changes[i - 1].0.end };
+            let ceil = changes.get(i + 1).map_or(old.len(), |next| next.0.start);
+            let before_start = old_span.start.saturating_sub(context).max(floor);
+            let after_end = (old_span.end + context).min(ceil);
+            PatchHunk {
+                at: old_span.start,
+                before: old.slice_strict(before_start, old_span.start),
+                deleted: old.slice_strict(old_span.start, old_span.end),
+                inserted: new.slice_strict(new_span.start, new_span.end),
+                after: old.slice_strict(old_span.end, after_end),
+            }
+        })
+        .collect()
+}
+
+/// Renders a line-based unified diff with `context` unchanged lines around each change.
+pub fn unified_diff(old: &EzStr, new: &EzStr, context: usize) -> String {
+    let old_lines: Vec<&str> = old.as_str().split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.as_str().split_inclusive('\n').collect();
+    let ops = myers_ops(&old_lines, &new_lines);
+
+    // (op, old line, new line) for every line of the script
+    let mut rows = Vec::with_capacity(ops.len());
+    let (mut o, mut n) = (0, 0);
+    for op in ops {
+        rows.push((op, o, n));
+        match op {
+            DiffKind::Equal => {
+                o += 1;
+                n += 1;
+            }
+            DiffKind::Delete => o += 1,
+            DiffKind::Insert => n += 1,
+        }
+    }
+
+    let mut out = String::from("--- old\n+++ new\n");
+    let mut i = 0;
+    while i < rows.len() {
+        if rows[i].0 == DiffKind::Equal {
+            i += 1;
+            continue;
+        }
+        // extend the hunk while the next change is within 2 * context equal lines
+        let start = i.saturating_sub(context);
+        let mut end = i;
+        loop {
+            while end < rows.len() && rows[end].0 != DiffKind::Equal {
+                end += 1;
+            }
+            let next_change = (end..rows.len()).find(|&j| rows[j].0 != DiffKind::Equal);
+            match next_change {
+                Some(j) if j - end <= 2 * context => end = j,
+                _ => break,
+            }
+        }
+        let end = (end + context).min(rows.len());
+
+        let hunk = &rows[start..end];
+        let old_count = hunk.iter().filter(|r| r.0 != DiffKind::Insert).count();
+        let new_count = hunk.iter().filter(|r| r.0 != DiffKind::Delete).count();
+        let old_start = if old_count == 0 { hunk[0].1 } else { hunk[0].1 + 1 };
+        let new_start = if new_count == 0 { hunk[0].2 } else { hunk[0].2 + 1 };
+        out += &format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count);
+        for &(op, o, n) in hunk {
+            let (prefix, line) = match op {
+                DiffKind::Equal => (' ', old_lines[o]),
+                DiffKind::Delete => ('-', old_lines[o]),
+                DiffKind::Insert => ('+', new_lines[n]),
+            };
+            out.push(prefix);
+            out += line.trim_end_matches('\n');
+            out.push('\n');
+        }
+        i = end;
+    }
+    out
+}
+
+/// Why `EzStr::apply_patch` could not apply a hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The text a Delete hunk removes is not in the string at or after the previous hunk.
+    DeletedTextNotFound { hunk: usize, text: EzStr },
+    /// An Insert hunk points past the end of the string.
+    OutOfBounds { hunk: usize, position: usize, len: usize },
+}
+
+impl std::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::DeletedTextNotFound { hunk, text } => {
+                write!(f, "hunk {}: deleted text {:?} not found", hunk, text)
+            }
+            PatchError::OutOfBounds { hunk, position, len } => {
+                write!(f, "hunk {}: position {} is past the end of a string of length {}", hunk, position, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Occurrence of `needle` in `haystack[from..]` whose start is closest to `near`.
+fn nearest_occurrence(haystack: &[crate::Grapheme], needle: &[crate::Grapheme], from: usize, near: usize) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len())
+        .filter(|&i| haystack[i..i + needle.len()] == *needle)
+        .min_by_key(|&i| i.abs_diff(near))
+}
+
+impl EzStr {
+    /// Applies hunks produced by `diff` against an older version of this string.
+    ///
+    /// Equal and Delete hunks are located at their recorded position, or at the nearest
+    /// occurrence of their text when earlier edits moved it; an Equal hunk whose text is
+    /// gone is skipped, so patches still apply when only their context changed. Diff
+    /// hunks carry all the unchanged text; use `make_patch` and `apply_hunks` to send
+    /// only the changes.
+    pub fn apply_patch(&self, hunks: &[DiffHunk]) -> Result<EzStr, PatchError> {
+        let source = self.graphemes();
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut cursor = 0;
+        let mut drift: isize = 0;
+        let copy = |out: &mut String, range: Range<usize>| {
+            source[range].iter().for_each(|g| out.push_str(g.as_str()));
+        };
+
+        for (i, hunk) in hunks.iter().enumerate() {
+            let expected = (hunk.old.start as isize + drift).max(cursor as isize) as usize;
+            match hunk.kind {
+                DiffKind::Insert => {
+                    if expected > source.len() {
+                        return Err(PatchError::OutOfBounds { hunk: i, position: expected, len: source.len() });
+                    }
+                    copy(&mut out, cursor..expected);
+                    out += hunk.text.as_str();
+                    cursor = expected;
+                }
+                DiffKind::Equal | DiffKind::Delete => {
+                    let needle = hunk.text.graphemes();
+                    let found = match nearest_occurrence(source, needle, cursor, expected) {
+                        Some(found) => found,
+                        None if hunk.kind == DiffKind::Equal => continue,
+                        None => return Err(PatchError::DeletedTextNotFound { hunk: i, text: hunk.text.clone() }),
+                    };
+                    let end = found + needle.len();
+                    if hunk.kind == DiffKind::Equal {
+                        copy(&mut out, cursor..end);
+                    } else {
+                        copy(&mut out, cursor..found);
+                    }
+                    drift = end as isize - hunk.old.end as isize;
+                    cursor = end;
+                }
+            }
+        }
+        copy(&mut out, cursor..source.len());
+        Ok(EzStr::new(out))
+    }
+
+    /// Applies a patch from `make_patch` made against an older version of this string.
+    ///
+    /// Each change is looked for near its recorded position, shifted by earlier changes,
+    /// together with its context; if the context was edited too, with less of it, down
+    /// to the deleted text alone. A pure insertion whose context is gone goes at its
+    /// recorded position.
+    pub fn apply_hunks(&self, hunks: &[PatchHunk]) -> Result<EzStr, PatchError> {
+        let source = self.graphemes();
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut cursor: usize = 0;
+        let mut drift: isize = 0;
+
+        for (i, hunk) in hunks.iter().enumerate() {
+            let expected = (hunk.at as isize + drift).max(cursor as isize) as usize;
+            let (before, deleted, after) =
+                (&hunk.before.graphemes()[..], &hunk.deleted.graphemes()[..], &hunk.after.graphemes()[..]);
+            let none: &[crate::Grapheme] = &[];
+            let candidates = [(before, after), (before, none), (none, after), (none, none)];
+            let found = candidates.iter().find_map(|&(before, after)| {
+                let needle: Vec<crate::Grapheme> = [before, deleted, after].concat();
+                let near = expected.saturating_sub(before.len());
+                let from = cursor.saturating_sub(before.len());
+                let found = nearest_occurrence(source, &needle, from, near)? + before.len();
+                (found >= cursor).then_some(found)
+            });
+            let at = match found {
+                Some(at) if !deleted.is_empty() || expected <= source.len() => at,
+                _ if !deleted.is_empty() => {
+                    return Err(PatchError::DeletedTextNotFound { hunk: i, text: hunk.deleted.clone() });
+                }
+                _ => return Err(PatchError::OutOfBounds { hunk: i, position: expected, len: source.len() }),
+            };
+            source[cursor..at].iter().for_each(|g| out.push_str(g.as_str()));
+            out += hunk.inserted.as_str();
+            cursor = at + deleted.len();
+            drift = at as isize - hunk.at as isize;
+        }
+        source[cursor..].iter().for_each(|g| out.push_str(g.as_str()));
+        Ok(EzStr::new(out))
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::EzStr;
+
+/// Minimum number of grapheme insertions, deletions and substitutions turning `a` into `b`.
+pub fn levenshtein(a: &EzStr, b: &EzStr) -> usize {
+    let (a, b) = (a.graphemes(), b.graphemes());
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Levenshtein distance that also counts a swap of two graphemes as one edit,
+/// including swaps separated by other edits (unrestricted Damerau-Levenshtein).
+pub fn damerau_levenshtein(a: &EzStr, b: &EzStr) -> usize {
+    let (a, b) = (a.graphemes(), b.graphemes());
+    let max = a.len() + b.len();
+    let width = b.len() + 2;
+    let mut d = vec![0; (a.len() + 2) * width];
+    d[0] = max;
+    for i in 0..=a.len() {
+        d[(i + 1) * width] = max;
+        d[(i + 1) * width + 1] = i;
+    }
+    for j in 0..=b.len() {
+        d[j + 1] = max;
+        d[width + j + 1] = j;
+    }
+
+    // row of the last occurrence of each grapheme of `a`
+    let mut last_row: HashMap<&str, usize> = HashMap::new();
+    for i in 1..=a.len() {
+        let mut last_match_col = 0;
+        for j in 1..=b.len() {
+            let k = *last_row.get(b[j - 1].as_str()).unwrap_or(&0);
+            let l = last_match_col;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_match_col = j;
+                0
+            } else {
+                1
+            };
+            let substitution = d[i * width + j] + cost;
+            let insertion = d[(i + 1) * width + j] + 1;
+            let deletion = d[i * width + j + 1] + 1;
+            let transposition = d[k * width + l] + (i - k - 1) + 1 + (j - l - 1);
+            d[(i + 1) * width + j + 1] = substitution.min(insertion).min(deletion).min(transposition);
+        }
+        last_row.insert(a[i - 1].as_str(), i);
+    }
+    d[(a.len() + 1) * width + b.len() + 1]
+}
+
+/// Jaro similarity in `0.0..=1.0` over graphemes.
+pub fn jaro(a: &EzStr, b: &EzStr) -> f64 {
+    let (a, b) = (a.graphemes(), b.graphemes());
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+    for i in 0..a.len() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && a[i] == b[j] {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(g, _)| g);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(g, _)| g);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// Jaro-Winkler similarity in `0.0..=1.0`, boosting strings with a common prefix of up to 4 graphemes.
+pub fn jaro_winkler(a: &EzStr, b: &EzStr) -> f64 {
+    let sim = jaro(a, b);
+    let prefix = a
+        .graphemes()
+        .iter()
+        .zip(b.graphemes())
+        .take(4)
+        .take_while(|(x, y)| x == y)
+        .count();
+    sim + prefix as f64 * 0.1 * (1.0 - sim)
+}
+
+/// Normalized Levenshtein similarity: 1.0 for equal strings, 0.0 when nothing is shared.
+pub fn similarity_ratio(a: &EzStr, b: &EzStr) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
@@ -0,0 +1,71 @@
+use unicode_segmentation::GraphemeCursor;
+
+use crate::segment::next_boundary;
+use crate::{EzStr, GSpan, GraphemeIndex};
+
+impl EzStr {
+    /// Copy with the graphemes in `range` replaced by `replacement`, like
+    /// `String::replace_range`. Panics if `range` is out of bounds.
+    ///
+    /// If this string is already indexed, the new one gets its index without being
+    /// segmented again: offsets before the edit are kept, offsets after it are shifted,
+    /// and only the text from the grapheme before the edit up to the first boundary
+    /// that lines up with an old one is re-segmented.
+    pub fn with_replaced<S: Into<GSpan>, T: AsRef<str>>(&self, range: S, replacement: T) -> EzStr {
+        let range = range.into();
+        let replacement = replacement.as_ref();
+        // the index alone, never the `Grapheme` cache, so each edit stays cheap
+        let len = self.grapheme_index().len();
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "grapheme range {}..{} out of bounds for length {}",
+            range.start,
+            range.end,
+            len
+        );
+        let data = self.as_str();
+        let (byte_start, byte_end) = (self.grapheme_to_byte(range.start), self.grapheme_to_byte(range.end));
+        let mut text = String::with_capacity(data.len() - (byte_end - byte_start) + replacement.len());
+        text += &data[..byte_start];
+        text += replacement;
+        text += &data[byte_end..];
+
+        let Some(old) = self.grapheme_index_data.get() else {
+            return EzStr::new(text);
+        };
+        let new_end = byte_start + replacement.len();
+        let shift = |byte: usize| byte - byte_end + new_end;
+
+        // The grapheme before the edit may absorb the start of the replacement.
+        let keep = range.start.saturating_sub(1);
+        let mut offsets: Vec<usize> = old.iter().take(keep).collect();
+        let mut cursor = GraphemeCursor::new(0, text.len(), true);
+        let mut pos = old.byte_offset(keep).unwrap_or(0).min(byte_start);
+        let mut resync = range.end;
+        while pos < text.len() {
+            if pos >= new_end {
+                // Skip old graphemes that now start before `pos`; stop if one starts there.
+                while resync < old.len() && shift(old.byte_offset(resync).unwrap()) < pos {
+                    resync += 1;
+                }
+                if resync < old.len() && shift(old.byte_offset(resync).unwrap()) == pos {
+                    break;
+                }
+            }
+            offsets.push(pos);
+            pos = next_boundary(&text, &mut cursor, pos);
+        }
+        if pos < text.len() {
+            offsets.extend((resync..old.len()).map(|i| shift(old.byte_offset(i).unwrap())));
+        }
+
+        let out = EzStr::new(text);
+        let _ = out.grapheme_index_data.set(GraphemeIndex::from_offsets(offsets));
+        out
+    }
+
+    /// In-place `with_replaced`.
+    pub fn replace_range<S: Into<GSpan>, T: AsRef<str>>(&mut self, range: S, replacement: T) {
+        *self = self.with_replaced(range, replacement);
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+
+#[cfg(feature = "archive")]
+use crate::archive::ArchiveError;
+#[cfg(feature = "bench")]
+use crate::bench::Regression;
+#[cfg(feature = "columnar")]
+use crate::columnar::ColumnarError;
+#[cfg(feature = "csv")]
+use crate::csv::CsvError;
+use crate::diff::PatchError;
+use crate::template::TemplateError;
+use crate::MatchValidationError;
+
+/// Errors returned by the fallible `EzStr` APIs and conversions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    ParseInt(ParseIntError),
+    ParseFloat(ParseFloatError),
+    /// Any other `FromStr` failure, by message.
+    Parse(String),
+    /// A grapheme range that does not fit a string of `len` graphemes.
+    OutOfBounds { start: usize, end: usize, len: usize },
+    /// A regex that failed to compile, with the compiler's message.
+    InvalidPattern(String),
+    /// A malformed escape sequence starting at grapheme `at`.
+    InvalidEscape { at: usize },
+    /// An I/O failure, kept as its kind and message so `Error` stays `Clone` and `Eq`.
+    Io { kind: io::ErrorKind, message: String },
+    // The module-specific errors below convert with `?` for callers that use `Error`
+    // throughout.
+    Patch(PatchError),
+    Template(TemplateError),
+    Validation(MatchValidationError),
+    #[cfg(feature = "archive")]
+    Archive(ArchiveError),
+    #[cfg(feature = "bench")]
+    Regression(Regression),
+    #[cfg(feature = "columnar")]
+    Columnar(ColumnarError),
+    #[cfg(feature = "csv")]
+    Csv(CsvError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ParseInt(e) => write!(f, "invalid integer: {}", e),
+            Error::ParseFloat(e) => write!(f, "invalid number: {}", e),
+            Error::Parse(msg) => write!(f, "parse error: {}", msg),
+            Error::OutOfBounds { start, end, len } => {
+                write!(f, "grapheme range {}..{} out of bounds for length {}", start, end, len)
+            }
+            Error::InvalidPattern(msg) => write!(f, "invalid pattern: {}", msg),
+            Error::InvalidEscape { at } => write!(f, "invalid escape sequence at {}", at),
+            Error::Io { message, .. } => write!(f, "I/O error: {}", message),
+            Error::Patch(e) => write!(f, "{}", e),
+            Error::Template(e) => write!(f, "{}", e),
+            Error::Validation(e) => write!(f, "{}", e),
+            #[cfg(feature = "archive")]
+            Error::Archive(e) => write!(f, "{}", e),
+            #[cfg(feature = "bench")]
+            Error::Regression(e) => write!(f, "{}", e),
+            #[cfg(feature = "columnar")]
+            Error::Columnar(e) => write!(f, "{}", e),
+            #[cfg(feature = "csv")]
+            Error::Csv(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ParseInt(e) => Some(e),
+            Error::ParseFloat(e) => Some(e),
+            Error::Patch(e) => Some(e),
+            Error::Template(e) => Some(e),
+            Error::Validation(e) => Some(e),
+            #[cfg(feature = "archive")]
+            Error::Archive(e) => Some(e),
+            #[cfg(feature = "bench")]
+            Error::Regression(e) => Some(e),
+            #[cfg(feature = "columnar")]
+            Error::Columnar(e) => Some(e),
+            #[cfg(feature = "csv")]
+            Error::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Error::ParseInt(e)
+    }
+}
+
+impl From<ParseFloatError> for Error {
+    fn from(e: ParseFloatError) -> Self {
+        Error::ParseFloat(e)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(e: regex::Error) -> Self {
+        Error::InvalidPattern(e.to_string())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io { kind: e.kind(), message: e.to_string() }
+    }
+}
+
+impl From<PatchError> for Error {
+    fn from(e: PatchError) -> Self {
+        Error::Patch(e)
+    }
+}
+
+impl From<TemplateError> for Error {
+    fn from(e: TemplateError) -> Self {
+        Error::Template(e)
+    }
+}
+
+impl From<MatchValidationError> for Error {
+    fn from(e: MatchValidationError) -> Self {
+        Error::Validation(e)
+    }
+}
+
+#[cfg(feature = "archive")]
+impl From<ArchiveError> for Error {
+    fn from(e: ArchiveError) -> Self {
+        Error::Archive(e)
+    }
+}
+
+#[cfg(feature = "bench")]
+impl From<Regression> for Error {
+    fn from(e: Regression) -> Self {
+        Error::Regression(e)
+    }
+}
+
+#[cfg(feature = "columnar")]
+impl From<ColumnarError> for Error {
+    fn from(e: ColumnarError) -> Self {
+        Error::Columnar(e)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<CsvError> for Error {
+    fn from(e: CsvError) -> Self {
+        Error::Csv(e)
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write;
+
+use crate::{Error, EzStr, SpanMap};
+
+/// Target syntax for `EzStr::escape`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EscapeStyle {
+    /// Contents of a JSON string literal, without the surrounding quotes.
+    Json,
+    /// Text content or a quoted attribute value in HTML.
+    Html,
+    /// A single POSIX shell word: special chars get a backslash, line breaks are quoted.
+    Shell,
+    /// A regex matching the text literally.
+    Regex,
+}
+
+fn escape_char(c: char, style: EscapeStyle, out: &mut String) {
+    match style {
+        EscapeStyle::Json => match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        },
+        EscapeStyle::Html => match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        },
+        EscapeStyle::Shell => match c {
+            '\n' | '\r' => {
+                out.push('\'');
+                out.push(c);
+                out.push('\'');
+            }
+            c if c.is_ascii() && !c.is_ascii_alphanumeric() && !"_-./,:=@+%".contains(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        },
+        EscapeStyle::Regex => {
+            let mut buf = [0; 4];
+            out.push_str(&regex::escape(c.encode_utf8(&mut buf)));
+        }
+    }
+}
+
+impl EzStr {
+    /// Escapes the text for `style`. The SpanMap links each escaped grapheme back to the
+    /// grapheme it came from. An empty string gives `''` for the shell and is otherwise
+    /// left empty.
+    pub fn escape(&self, style: EscapeStyle) -> (EzStr, SpanMap) {
+        if self.is_empty() && style == EscapeStyle::Shell {
+            let mut map = SpanMap::new();
+            map.push(0..2, 0..0);
+            return (EzStr::new("''"), map);
+        }
+        self.map_graphemes(|g| {
+            let mut piece = String::with_capacity(g.as_str().len());
+            g.as_str().chars().for_each(|c| escape_char(c, style, &mut piece));
+            Some(EzStr::new(piece))
+        })
+    }
+
+    pub fn escape_json(&self) -> (EzStr, SpanMap) {
+        self.escape(EscapeStyle::Json)
+    }
+
+    /// Decodes the escapes of a JSON string literal body (`\n`, `\"`, `é`, surrogate
+    /// pairs...). The SpanMap links each decoded grapheme to the escape sequence it came
+    /// from. Fails on an unknown or truncated escape, or an unpaired surrogate.
+    pub fn unescape_json(&self) -> Result<(EzStr, SpanMap), Error> {
+        let data = self.as_str();
+        let invalid = |byte: usize| Error::InvalidEscape { at: self.byte_range_to_grapheme_indices(byte, byte).0 };
+        let hex = |at: usize| -> Option<u32> {
+            let digits = data.get(at..at + 4)?;
+            digits.bytes().all(|b| b.is_ascii_hexdigit()).then(|| u32::from_str_radix(digits, 16).ok())?
+        };
+
+        // Decoded text, and for each decoded char its output byte offset and source byte range.
+        let mut out = String::with_capacity(data.len());
+        let mut sources: Vec<(usize, usize, usize)> = Vec::new();
+        let mut i = 0;
+        while i < data.len() {
+            let c = data[i..].chars().next().unwrap();
+            let (decoded, len) = if c != '\\' {
+                (c, c.len_utf8())
+            } else {
+                match data.as_bytes().get(i + 1) {
+                    Some(b'"') => ('"', 2),
+                    Some(b'\\') => ('\\', 2),
+                    Some(b'/') => ('/', 2),
+                    Some(b'b') => ('\u{8}', 2),
+                    Some(b'f') => ('\u{c}', 2),
+                    Some(b'n') => ('\n', 2),
+                    Some(b'r') => ('\r', 2),
+                    Some(b't') => ('\t', 2),
+                    Some(b'u') => {
+                        let high = hex(i + 2).ok_or_else(|| invalid(i))?;
+                        if (0xD800..0xDC00).contains(&high) {
+                            let low = data[i + 6..]
+                                .starts_with("\\u")
+                                .then(|| hex(i + 8))
+                                .flatten()
+                                .filter(|low| (0xDC00..0xE000).contains(low))
+                                .ok_or_else(|| invalid(i))?;
+                            let c = char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).unwrap();
+                            (c, 12)
+                        } else {
+                            (char::from_u32(high).ok_or_else(|| invalid(i))?, 6)
+                        }
+                    }
+                    _ => return Err(invalid(i)),
+                }
+            };
+            sources.push((out.len(), i, i + len));
+            out.push(decoded);
+            i += len;
+        }
+
+        let text = EzStr::new(out);
+        let mut map = SpanMap::new();
+        let mut next = 0;
+        for (n, g) in text.iter_indices() {
+            let start = sources[next].0;
+            let first = next;
+            while next < sources.len() && sources[next].0 < start + g.as_str().len() {
+                next += 1;
+            }
+            let (src_start, src_end) = self.byte_range_to_grapheme_indices(sources[first].1, sources[next - 1].2);
+            map.push(n..n + 1, src_start..src_end);
+        }
+        Ok((text, map))
+    }
+}
@@ -0,0 +1,143 @@
+//! C ABI for embedding. Strings are passed as UTF-8 pointer/length pairs and every call
+//! returns an `EzStrStatus`; results are written through out-pointers. The C declarations
+//! are in `include/ezstr.h`, and `examples/c/smoke.c` shows how to link against the crate.
+use std::slice;
+
+use regex::Regex;
+
+use crate::EzStr;
+
+/// Status code returned by every `ezstr_*` function. Values are stable.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EzStrStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    OutOfBounds = 3,
+    InvalidRegex = 4,
+    NotFound = 5,
+}
+
+unsafe fn str_from_raw<'a>(data: *const u8, len: usize) -> Result<&'a str, EzStrStatus> {
+    if data.is_null() {
+        return if len == 0 { Ok("") } else { Err(EzStrStatus::NullPointer) };
+    }
+    std::str::from_utf8(unsafe { slice::from_raw_parts(data, len) }).map_err(|_| EzStrStatus::InvalidUtf8)
+}
+
+/// Creates an `EzStr` from `len` bytes of UTF-8 at `data`. Free it with `ezstr_free`.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes and `out` must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_new(data: *const u8, len: usize, out: *mut *mut EzStr) -> EzStrStatus {
+    if out.is_null() {
+        return EzStrStatus::NullPointer;
+    }
+    match unsafe { str_from_raw(data, len) } {
+        Ok(s) => {
+            unsafe { *out = Box::into_raw(Box::new(EzStr::new(s))) };
+            EzStrStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// Frees an `EzStr` returned by this API. Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a pointer from `ezstr_new`/`ezstr_slice` that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_free(s: *mut EzStr) {
+    if !s.is_null() {
+        drop(unsafe { Box::from_raw(s) });
+    }
+}
+
+/// Writes the number of graphemes in `s` to `out`.
+///
+/// # Safety
+/// `s` must be a live `EzStr` from this API and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_len(s: *const EzStr, out: *mut usize) -> EzStrStatus {
+    let (Some(s), false) = (unsafe { s.as_ref() }, out.is_null()) else {
+        return EzStrStatus::NullPointer;
+    };
+    unsafe { *out = s.len() };
+    EzStrStatus::Ok
+}
+
+/// Points `out_data`/`out_len` at the UTF-8 text of `s`; valid until `s` is freed.
+///
+/// # Safety
+/// `s` must be a live `EzStr` from this API and the out-pointers valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_as_utf8(s: *const EzStr, out_data: *mut *const u8, out_len: *mut usize) -> EzStrStatus {
+    let Some(s) = (unsafe { s.as_ref() }) else {
+        return EzStrStatus::NullPointer;
+    };
+    if out_data.is_null() || out_len.is_null() {
+        return EzStrStatus::NullPointer;
+    }
+    unsafe {
+        *out_data = s.as_str().as_ptr();
+        *out_len = s.as_str().len();
+    }
+    EzStrStatus::Ok
+}
+
+/// Copies graphemes `start..end` of `s` into a new `EzStr`.
+///
+/// # Safety
+/// `s` must be a live `EzStr` from this API and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_slice(s: *const EzStr, start: usize, end: usize, out: *mut *mut EzStr) -> EzStrStatus {
+    let (Some(s), false) = (unsafe { s.as_ref() }, out.is_null()) else {
+        return EzStrStatus::NullPointer;
+    };
+    if start > end || end > s.len() {
+        return EzStrStatus::OutOfBounds;
+    }
+    unsafe { *out = Box::into_raw(Box::new(s.slice_strict(start, end))) };
+    EzStrStatus::Ok
+}
+
+/// Finds the first match of the regex `pattern` and writes its grapheme span to
+/// `out_start`/`out_end`. Returns `NotFound` when there is no match.
+///
+/// # Safety
+/// `s` must be a live `EzStr` from this API, `pattern` must point to `pattern_len`
+/// readable bytes and the out-pointers must be valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ezstr_find(
+    s: *const EzStr,
+    pattern: *const u8,
+    pattern_len: usize,
+    out_start: *mut usize,
+    out_end: *mut usize,
+) -> EzStrStatus {
+    let Some(s) = (unsafe { s.as_ref() }) else {
+        return EzStrStatus::NullPointer;
+    };
+    if out_start.is_null() || out_end.is_null() {
+        return EzStrStatus::NullPointer;
+    }
+    let pattern = match unsafe { str_from_raw(pattern, pattern_len) } {
+        Ok(p) => p,
+        Err(status) => return status,
+    };
+    let Ok(regex) = Regex::new(pattern) else {
+        return EzStrStatus::InvalidRegex;
+    };
+    match s.find(&regex) {
+        Some(m) => {
+            unsafe {
+                *out_start = m.start;
+                *out_end = m.end;
+            }
+            EzStrStatus::Ok
+        }
+        None => EzStrStatus::NotFound,
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{EzStr, GraphemeRef, SpanMap};
+
+impl EzStr {
+    /// Replaces each grapheme by what `f` returns for it; `None` removes it. The
+    /// SpanMap links every output span to its source grapheme, with an empty output
+    /// span for each removed one.
+    pub fn map_graphemes<F>(&self, mut f: F) -> (EzStr, SpanMap)
+    where
+        F: FnMut(GraphemeRef<'_>) -> Option<EzStr>,
+    {
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut map = SpanMap::new();
+        let mut out_len = 0;
+        for (i, g) in self.iter_indices() {
+            let piece_len = match f(g) {
+                Some(piece) => {
+                    out += piece.as_str();
+                    piece.len()
+                }
+                None => 0,
+            };
+            map.push(out_len..out_len + piece_len, i..i + 1);
+            out_len += piece_len;
+        }
+        (EzStr::new(out), map)
+    }
+
+    /// Copy keeping only the graphemes for which `keep` returns true, e.g.
+    /// `filter_graphemes(|g| !g.as_str().chars().all(char::is_control))`.
+    pub fn filter_graphemes<F>(&self, mut keep: F) -> (EzStr, SpanMap)
+    where
+        F: FnMut(GraphemeRef<'_>) -> bool,
+    {
+        self.map_graphemes(|g| keep(g).then(|| EzStr::new(g.as_str())))
+    }
+
+    /// In-place `filter_graphemes`, like `Vec::retain`. Returns the map from the new
+    /// text back to the old one.
+    pub fn retain<F>(&mut self, keep: F) -> SpanMap
+    where
+        F: FnMut(GraphemeRef<'_>) -> bool,
+    {
+        let (kept, map) = self.filter_graphemes(keep);
+        *self = kept;
+        map
+    }
+}
@@ -0,0 +1,52 @@
+//! Entry points for fuzzers: each takes raw bytes, builds an `EzStr` from them and panics
+//! if an invariant from `testing` breaks. The cargo-fuzz targets in `fuzz/` call these,
+//! and they can be run from any other harness or on saved crash inputs.
+use regex::Regex;
+
+use crate::testing::{check_byte_index_round_trip, check_matches, check_slice_round_trip};
+use crate::{EzStr, GSpan};
+
+/// Splits fuzz input into two numbers and the text, decoded lossily so any bytes do.
+fn split_input(data: &[u8]) -> (usize, usize, EzStr) {
+    let byte = |i: usize| data.get(i).copied().unwrap_or(0) as usize;
+    let rest = data.get(2..).unwrap_or(&[]);
+    (byte(0), byte(1), EzStr::new(String::from_utf8_lossy(rest).as_ref()))
+}
+
+/// Byte to grapheme conversion of arbitrary byte ranges, including ones that start or
+/// end inside a char or a grapheme.
+pub fn byte_ranges(data: &[u8]) {
+    let (a, b, text) = split_input(data);
+    check_byte_index_round_trip(&text).unwrap();
+    let len = text.as_str().len();
+    let (start, end) = ((a * len) / 255, (b * len) / 255);
+    let span = GSpan::from_byte_range(&text, start.min(end)..start.max(end));
+    assert!(span.start <= span.end && span.end <= text.len(), "{:?} out of bounds for {:?}", span, text);
+}
+
+/// Slicing by grapheme at arbitrary indices, in and out of bounds.
+pub fn slicing(data: &[u8]) {
+    let (a, b, text) = split_input(data);
+    check_slice_round_trip(&text).unwrap();
+    let len = text.len();
+    match text.try_slice(a, b) {
+        Ok(_) => assert!(a <= b && b <= len, "slice {}..{} of {:?} accepted", a, b, text),
+        Err(_) => assert!(a > b || b > len, "slice {}..{} of {:?} refused", a, b, text),
+    }
+    let (start, end) = (a.min(len), b.min(len));
+    let end = end.max(start);
+    let rebuilt = [text.slice(0, start), text.slice(start, end), text.slice(end, len)].map(|s| s.as_str().to_string()).concat();
+    assert_eq!(rebuilt, text.as_str());
+}
+
+/// Matches found in the text are valid, with the needle taken from the input so that it
+/// often starts or ends inside a grapheme.
+pub fn find_round_trip(data: &[u8]) {
+    let (a, b, text) = split_input(data);
+    let chars: Vec<char> = text.as_str().chars().collect();
+    let from = a.min(chars.len());
+    let needle: String = chars[from..].iter().take(b % 4 + 1).collect();
+    let regex = Regex::new(&regex::escape(&needle)).unwrap();
+    check_matches(&text, &regex).unwrap();
+    assert!(needle.is_empty() || text.find(&regex).is_some(), "{:?} not found in {:?}", needle, text);
+}
@@ -0,0 +1,69 @@
+use crate::{EzStr, GraphemeMatch};
+
+/// An approximate occurrence found by `EzStr::fuzzy_find`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub matched: GraphemeMatch,
+    /// Grapheme edits (insertions, deletions, substitutions) between the pattern and the matched text.
+    pub edits: usize,
+}
+
+impl EzStr {
+    /// Finds non-overlapping substrings within `max_edits` grapheme edits of `pattern`,
+    /// preferring the closest candidates where they overlap.
+    ///
+    /// Uses Sellers' dynamic programming, one column of `pattern.len() + 1` cells per
+    /// grapheme of `self`, with the start of each alignment carried along.
+    pub fn fuzzy_find<T: Into<EzStr>>(&self, pattern: T, max_edits: usize) -> Vec<FuzzyMatch> {
+        let pattern = pattern.into();
+        let p = pattern.graphemes();
+        let text = self.graphemes();
+        let m = p.len();
+
+        // cost[i] / start[i]: best alignment of p[..i] ending at the current text position
+        let mut cost: Vec<usize> = (0..=m).collect();
+        let mut start: Vec<usize> = vec![0; m + 1];
+        let mut ends: Vec<(usize, usize, usize)> = Vec::new(); // (start, end, edits)
+        for j in 1..=text.len() {
+            let (mut diag_cost, mut diag_start) = (cost[0], start[0]);
+            cost[0] = 0;
+            start[0] = j;
+            for i in 1..=m {
+                let (up_cost, up_start) = (cost[i], start[i]);
+                let substitution = diag_cost + if p[i - 1] == text[j - 1] { 0 } else { 1 };
+                // ties go to the earliest start, i.e. the longest alignment
+                let best = [
+                    (substitution, diag_start),
+                    (cost[i - 1] + 1, start[i - 1]),
+                    (up_cost + 1, up_start),
+                ]
+                .into_iter()
+                .min()
+                .unwrap();
+                cost[i] = best.0;
+                start[i] = best.1;
+                diag_cost = up_cost;
+                diag_start = up_start;
+            }
+            if cost[m] <= max_edits && start[m] < j {
+                ends.push((start[m], j, cost[m]));
+            }
+        }
+
+        // closest candidates first, then longest and earliest; keep those not overlapping a kept one
+        ends.sort_by_key(|&(start, end, edits)| (edits, std::cmp::Reverse(end - start), start));
+        let mut kept: Vec<(usize, usize, usize)> = Vec::new();
+        for candidate in ends {
+            if kept.iter().all(|k| candidate.1 <= k.0 || candidate.0 >= k.1) {
+                kept.push(candidate);
+            }
+        }
+        kept.sort();
+        kept.into_iter()
+            .map(|(start, end, edits)| FuzzyMatch {
+                matched: GraphemeMatch::from_source(self, start, end),
+                edits,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,144 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::EzStr;
+
+/// One element of a parsed glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GlobToken {
+    /// `*`: any run of graphemes, possibly empty.
+    Star,
+    /// `?`: exactly one grapheme.
+    One,
+    /// `[...]` or `[!...]`: one grapheme that is (or with `negated`, is not) in the class.
+    Class { negated: bool, items: Vec<ClassItem> },
+    /// A grapheme matched as is.
+    Literal(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ClassItem {
+    Grapheme(String),
+    /// `a-z`: graphemes made of a single char in the range.
+    Range(char, char),
+}
+
+impl GlobToken {
+    /// Whether this token, other than `Star`, matches the grapheme `g`.
+    fn matches(&self, g: &str) -> bool {
+        match self {
+            GlobToken::Star | GlobToken::One => true,
+            GlobToken::Literal(l) => l == g,
+            GlobToken::Class { negated, items } => items.iter().any(|item| item.matches(g)) != *negated,
+        }
+    }
+}
+
+impl ClassItem {
+    fn matches(&self, g: &str) -> bool {
+        match self {
+            ClassItem::Grapheme(item) => item == g,
+            ClassItem::Range(lo, hi) => {
+                let mut chars = g.chars();
+                matches!((chars.next(), chars.next()), (Some(c), None) if (*lo..=*hi).contains(&c))
+            }
+        }
+    }
+}
+
+/// Splits a glob into tokens, grapheme by grapheme. `\` makes the next grapheme
+/// literal; a `[` without a closing `]` is a literal, and a `]` right after `[` or
+/// `[!` belongs to the class.
+pub(crate) fn parse_glob(pattern: &str) -> Vec<GlobToken> {
+    let graphemes: Vec<&str> = pattern.graphemes(true).collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < graphemes.len() {
+        let g = graphemes[i];
+        i += 1;
+        match g {
+            "*" => {
+                if tokens.last() != Some(&GlobToken::Star) {
+                    tokens.push(GlobToken::Star);
+                }
+            }
+            "?" => tokens.push(GlobToken::One),
+            "\\" if i < graphemes.len() => {
+                tokens.push(GlobToken::Literal(graphemes[i].to_string()));
+                i += 1;
+            }
+            "[" => match parse_class(&graphemes[i..]) {
+                Some((token, used)) => {
+                    tokens.push(token);
+                    i += used;
+                }
+                None => tokens.push(GlobToken::Literal(g.to_string())),
+            },
+            _ => tokens.push(GlobToken::Literal(g.to_string())),
+        }
+    }
+    tokens
+}
+
+/// Parses a class after its `[`, returning it with the number of graphemes used
+/// including the closing `]`.
+fn parse_class(graphemes: &[&str]) -> Option<(GlobToken, usize)> {
+    let negated = matches!(graphemes.first(), Some(&"!") | Some(&"^"));
+    let mut i = negated as usize;
+    let first = i;
+    let mut items = Vec::new();
+    loop {
+        let g = *graphemes.get(i)?;
+        if g == "]" && i > first {
+            return Some((GlobToken::Class { negated, items }, i + 1));
+        }
+        let single = |g: &str| {
+            let mut chars = g.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        };
+        match (single(g), graphemes.get(i + 1), graphemes.get(i + 2).copied().and_then(single)) {
+            (Some(lo), Some(&"-"), Some(hi)) if graphemes[i + 2] != "]" => {
+                items.push(ClassItem::Range(lo, hi));
+                i += 3;
+            }
+            _ => {
+                items.push(ClassItem::Grapheme(g.to_string()));
+                i += 1;
+            }
+        }
+    }
+}
+
+impl EzStr {
+    /// Shell-style wildcard match of the whole string: `*` matches any run of
+    /// graphemes, `?` exactly one grapheme (a whole emoji or accented letter), and
+    /// `[abc]`, `[a-z]` or `[!abc]` one grapheme in or out of a class. `\` escapes the
+    /// next grapheme. "Th? *Dreams" matches "Thé Nicotine Dreams".
+    pub fn matches_glob<T: AsRef<str>>(&self, pattern: T) -> bool {
+        let tokens = parse_glob(pattern.as_ref());
+        let text: Vec<&str> = self.iter().map(|g| g.0).collect();
+        // greedy matching, going back to the last `*` on a mismatch
+        let (mut t, mut p) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while t < text.len() {
+            match tokens.get(p) {
+                Some(GlobToken::Star) => {
+                    backtrack = Some((p, t));
+                    p += 1;
+                }
+                Some(token) if token.matches(text[t]) => {
+                    t += 1;
+                    p += 1;
+                }
+                _ => match backtrack {
+                    Some((star, from)) => {
+                        backtrack = Some((star, from + 1));
+                        p = star + 1;
+                        t = from + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+        tokens[p..].iter().all(|token| *token == GlobToken::Star)
+    }
+}
@@ -0,0 +1,134 @@
+use once_cell::sync::OnceCell;
+
+const BLOCK: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Block {
+    /// Byte offset of the block's first grapheme.
+    base: usize,
+    /// Where the block's relative offsets start in `narrow` or `wide`.
+    at: usize,
+    wide: bool,
+}
+
+/// Byte offset of every grapheme, stored as a two-level index: an absolute offset per
+/// block of 256 graphemes, then offsets relative to it. Relative offsets take 2 bytes,
+/// or 4 in the rare blocks spanning more than 64 KiB.
+#[derive(Debug, Clone, Default)]
+pub struct GraphemeIndex {
+    len: usize,
+    blocks: Vec<Block>,
+    narrow: Vec<u16>,
+    wide: Vec<u32>,
+    // char offset of each block's first grapheme, counted on first use
+    block_chars: OnceCell<Vec<usize>>,
+}
+
+impl PartialEq for GraphemeIndex {
+    fn eq(&self, other: &Self) -> bool {
+        (self.len, &self.blocks, &self.narrow, &self.wide) == (other.len, &other.blocks, &other.narrow, &other.wide)
+    }
+}
+
+impl Eq for GraphemeIndex {}
+
+impl GraphemeIndex {
+    /// Builds the index from the ascending start offsets of each grapheme.
+    pub fn from_offsets<I: IntoIterator<Item = usize>>(offsets: I) -> Self {
+        let offsets: Vec<usize> = offsets.into_iter().collect();
+        let mut index = GraphemeIndex { len: offsets.len(), ..Default::default() };
+        index.blocks.reserve(offsets.len().div_ceil(BLOCK));
+        index.narrow.reserve(offsets.len());
+        for chunk in offsets.chunks(BLOCK) {
+            let base = chunk[0];
+            let wide = chunk.last().unwrap() - base > u16::MAX as usize;
+            if wide {
+                index.blocks.push(Block { base, at: index.wide.len(), wide });
+                index.wide.extend(chunk.iter().map(|&o| (o - base) as u32));
+            } else {
+                index.blocks.push(Block { base, at: index.narrow.len(), wide });
+                index.narrow.extend(chunk.iter().map(|&o| (o - base) as u16));
+            }
+        }
+        index.narrow.shrink_to_fit();
+        index
+    }
+
+    /// Number of graphemes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn relative(&self, block: &Block, i: usize) -> usize {
+        if block.wide { self.wide[block.at + i] as usize } else { self.narrow[block.at + i] as usize }
+    }
+
+    fn block_len(&self, b: usize) -> usize {
+        if b + 1 < self.blocks.len() { BLOCK } else { self.len - b * BLOCK }
+    }
+
+    /// Byte offset at which grapheme `index` starts.
+    pub fn byte_offset(&self, index: usize) -> Option<usize> {
+        if index >= self.len {
+            return None;
+        }
+        let block = &self.blocks[index / BLOCK];
+        Some(block.base + self.relative(block, index % BLOCK))
+    }
+
+    /// Like `binary_search` on the offsets: `Ok(g)` if grapheme `g` starts at `byte`,
+    /// otherwise `Err(g)` with the first grapheme starting after it.
+    pub fn search(&self, byte: usize) -> Result<usize, usize> {
+        let b = self.blocks.partition_point(|block| block.base <= byte);
+        if b == 0 {
+            return Err(0);
+        }
+        let block = &self.blocks[b - 1];
+        let target = byte - block.base;
+        let (mut lo, mut hi) = (0, self.block_len(b - 1));
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.relative(block, mid) < target { lo = mid + 1 } else { hi = mid }
+        }
+        let g = (b - 1) * BLOCK + lo;
+        if lo < self.block_len(b - 1) && self.relative(block, lo) == target { Ok(g) } else { Err(g) }
+    }
+
+    /// Char offset at which grapheme `index` starts in `text`, the string this index
+    /// was built from; `len()` maps to the char count of `text`. Only the chars within
+    /// one block are counted once per-block offsets are known.
+    pub(crate) fn char_offset(&self, text: &str, index: usize) -> usize {
+        if self.blocks.is_empty() {
+            return 0;
+        }
+        let block_chars = self.block_chars.get_or_init(|| {
+            let mut chars = 0;
+            let mut prev = 0;
+            self.blocks
+                .iter()
+                .map(|block| {
+                    chars += text[prev..block.base].chars().count();
+                    prev = block.base;
+                    chars
+                })
+                .collect()
+        });
+        let b = (index / BLOCK).min(self.blocks.len() - 1);
+        let byte = self.byte_offset(index).unwrap_or(text.len());
+        block_chars[b] + text[self.blocks[b].base..byte].chars().count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).map(move |i| self.byte_offset(i).unwrap())
+    }
+
+    /// Heap bytes used by the index.
+    pub fn heap_size(&self) -> usize {
+        self.blocks.capacity() * std::mem::size_of::<Block>() + self.narrow.capacity() * 2 + self.wide.capacity() * 4
+            + self.block_chars.get().map_or(0, |c| c.capacity() * std::mem::size_of::<usize>())
+    }
+}
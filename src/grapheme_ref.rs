@@ -0,0 +1,73 @@
+use crate::{emoji, is_combining_mark, Grapheme};
+
+/// A grapheme cluster borrowed from its string, as yielded by `EzStr::iter`. It has the
+/// same predicates as `Grapheme` without owning a copy of the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GraphemeRef<'a>(pub &'a str);
+
+impl<'a> GraphemeRef<'a> {
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    pub fn to_grapheme(&self) -> Grapheme {
+        Grapheme::new(self.0)
+    }
+
+    /// True if every char of the cluster is whitespace, e.g. `"\r\n"`.
+    pub fn is_whitespace(&self) -> bool {
+        !self.0.is_empty() && self.0.chars().all(char::is_whitespace)
+    }
+
+    /// True for a letter cluster: an alphabetic base followed only by letters,
+    /// combining marks or joiners. Emoji ZWJ sequences are not alphabetic.
+    pub fn is_alphabetic(&self) -> bool {
+        let mut chars = self.0.chars();
+        match chars.next() {
+            Some(c) if c.is_alphabetic() => {
+                chars.all(|c| c.is_alphabetic() || is_combining_mark(c) || c == '\u{200C}' || c == '\u{200D}')
+            }
+            _ => false,
+        }
+    }
+
+    /// True for a digit or other numeric char, optionally with combining marks.
+    /// Keycap emoji such as "1️⃣" are not numeric.
+    pub fn is_numeric(&self) -> bool {
+        let mut chars = self.0.chars();
+        match chars.next() {
+            Some(c) if c.is_numeric() => chars.all(is_combining_mark) && !emoji::is_emoji(self),
+            _ => false,
+        }
+    }
+
+    /// True if the cluster is made only of combining marks, as happens with a
+    /// mark at the start of a string or after a control char.
+    pub fn is_combining(&self) -> bool {
+        !self.0.is_empty() && self.0.chars().all(is_combining_mark)
+    }
+}
+
+impl AsRef<str> for GraphemeRef<'_> {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl PartialEq<&str> for GraphemeRef<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Grapheme> for GraphemeRef<'_> {
+    fn eq(&self, other: &Grapheme) -> bool {
+        self.0 == other.as_str()
+    }
+}
+
+impl std::fmt::Display for GraphemeRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
@@ -0,0 +1,141 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+const INLINE_CAP: usize = 22;
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, buf: [u8; INLINE_CAP] },
+    Heap(Box<str>),
+}
+
+/// Immutable storage for one grapheme cluster. Clusters of up to 22 bytes (everything but
+/// long ZWJ sequences and heavily stacked marks) are stored inline without allocating.
+#[derive(Clone)]
+pub struct GraphemeStr(Repr);
+
+impl GraphemeStr {
+    pub fn new(s: &str) -> Self {
+        if s.len() <= INLINE_CAP {
+            let mut buf = [0; INLINE_CAP];
+            buf[..s.len()].copy_from_slice(s.as_bytes());
+            GraphemeStr(Repr::Inline { len: s.len() as u8, buf })
+        } else {
+            GraphemeStr(Repr::Heap(s.into()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // the buffer is only ever filled from a &str
+            Repr::Inline { len, buf } => unsafe { std::str::from_utf8_unchecked(&buf[..*len as usize]) },
+            Repr::Heap(s) => s,
+        }
+    }
+
+    /// Whether the text is stored on the heap rather than inline.
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.0, Repr::Heap(_))
+    }
+}
+
+impl Default for GraphemeStr {
+    fn default() -> Self {
+        GraphemeStr::new("")
+    }
+}
+
+impl Deref for GraphemeStr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for GraphemeStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for GraphemeStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for GraphemeStr {
+    fn from(item: &str) -> Self {
+        GraphemeStr::new(item)
+    }
+}
+
+impl From<String> for GraphemeStr {
+    fn from(item: String) -> Self {
+        GraphemeStr::new(&item)
+    }
+}
+
+impl From<GraphemeStr> for String {
+    fn from(item: GraphemeStr) -> Self {
+        item.as_str().to_string()
+    }
+}
+
+impl PartialEq for GraphemeStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for GraphemeStr {}
+
+impl PartialEq<str> for GraphemeStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for GraphemeStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for GraphemeStr {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialOrd for GraphemeStr {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GraphemeStr {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for GraphemeStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for GraphemeStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for GraphemeStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
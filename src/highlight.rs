@@ -0,0 +1,201 @@
+use regex::Regex;
+
+use crate::annotated::AnnotatedEzStr;
+use crate::{EzStr, GSpan, GraphemeMatch};
+
+/// How a `Highlighter` picks between rules matching at the same position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conflict {
+    /// The longest match wins, and the earlier rule among equally long ones.
+    #[default]
+    Longest,
+    /// The earliest rule wins whatever the lengths.
+    First,
+}
+
+/// Labels text with regex rules, lexer style: scanning left to right, the leftmost
+/// match of any rule is taken, ties at the same position are settled by `Conflict`,
+/// and scanning resumes after it. The resulting annotations never overlap; empty
+/// matches are ignored.
+#[derive(Debug, Clone)]
+pub struct Highlighter<L> {
+    rules: Vec<(Regex, L)>,
+    conflict: Conflict,
+}
+
+impl<L> Default for Highlighter<L> {
+    fn default() -> Self {
+        Highlighter { rules: Vec::new(), conflict: Conflict::default() }
+    }
+}
+
+impl<L: Clone> Highlighter<L> {
+    pub fn new() -> Self {
+        Highlighter::default()
+    }
+
+    /// Adds a rule; earlier rules take precedence on ties.
+    pub fn rule(mut self, regex: Regex, label: L) -> Self {
+        self.rules.push((regex, label));
+        self
+    }
+
+    pub fn conflict(mut self, conflict: Conflict) -> Self {
+        self.conflict = conflict;
+        self
+    }
+
+    pub fn highlight(&self, text: &EzStr) -> AnnotatedEzStr<L> {
+        let data = text.as_str();
+        let mut out = AnnotatedEzStr::new(text.clone());
+        // next non-empty match of each rule at or after `pos`, as byte spans
+        let mut next: Vec<Option<(usize, usize)>> = vec![None; self.rules.len()];
+        let mut pos = 0;
+        while pos < data.len() {
+            for (i, (regex, _)) in self.rules.iter().enumerate() {
+                if next[i].is_none_or(|(start, _)| start < pos) {
+                    next[i] = find_non_empty(regex, data, pos);
+                }
+            }
+            let mut best: Option<(usize, (usize, usize))> = None;
+            for (i, &m) in next.iter().enumerate() {
+                let Some((start, end)) = m else { continue };
+                let better = match best {
+                    None => true,
+                    Some((_, (b_start, b_end))) => {
+                        start < b_start || (start == b_start && self.conflict == Conflict::Longest && end > b_end)
+                    }
+                };
+                if better {
+                    best = Some((i, (start, end)));
+                }
+            }
+            let Some((rule, (start, end))) = best else { break };
+            let (g_start, g_end) = text.byte_range_to_grapheme_indices(start, end);
+            out.annotate_exclusive(GSpan::new(g_start, g_end), self.rules[rule].1.clone());
+            pos = text.grapheme_to_byte(g_end).max(end);
+        }
+        out
+    }
+}
+
+fn find_non_empty(regex: &Regex, haystack: &str, mut pos: usize) -> Option<(usize, usize)> {
+    while pos <= haystack.len() {
+        let m = regex.find_at(haystack, pos)?;
+        if !m.is_empty() {
+            return Some((m.start(), m.end()));
+        }
+        pos = m.end() + haystack[m.end()..].chars().next()?.len_utf8();
+    }
+    None
+}
+
+/// A caret line under part of a source line: (line, first grapheme, end grapheme,
+/// label shown on this line).
+type Mark<'a> = (usize, usize, usize, Option<&'a str>);
+
+/// Splits each labelled span into one mark per line it covers, sorted by position.
+fn marks<'a, S: AsRef<str>>(source: &EzStr, line_starts: &[usize], labels: &'a [(GraphemeMatch, S)]) -> Vec<Mark<'a>> {
+    let len = source.len();
+    let line_of = |idx: usize| line_starts.partition_point(|&start| start <= idx) - 1;
+    let mut marks = Vec::new();
+    for (m, label) in labels {
+        let start = m.start.min(len);
+        let end = m.end.clamp(start, len);
+        let first = line_of(start);
+        let last = line_of(end.saturating_sub(1).max(start));
+        for line in first..=last {
+            let from = start.max(line_starts[line]);
+            let to = end.min(line_end(source, line_starts, line));
+            marks.push((line, from, to, if line == last { Some(label.as_ref()) } else { None }));
+        }
+    }
+    marks.sort_by_key(|&(line, from, _, _)| (line, from));
+    marks
+}
+
+/// End of a line's content, before its terminator.
+fn line_end(source: &EzStr, line_starts: &[usize], line: usize) -> usize {
+    match line_starts.get(line + 1) {
+        Some(&next) => next - 1,
+        None => source.len(),
+    }
+}
+
+/// Appends a source line with its number in the gutter.
+fn push_line(out: &mut String, source: &EzStr, line_starts: &[usize], line: usize, number: usize, gutter: usize) {
+    let graphemes = source.graphemes();
+    *out += &format!("{:>width$} | ", number, width = gutter);
+    graphemes[line_starts[line]..line_end(source, line_starts, line)].iter().for_each(|g| *out += g.as_str());
+    out.push('\n');
+}
+
+/// Appends the caret line for `mark`, aligned by display width under its source line.
+fn push_carets(out: &mut String, source: &EzStr, line_starts: &[usize], mark: Mark<'_>, gutter: usize) {
+    let graphemes = source.graphemes();
+    let (line, from, to, label) = mark;
+    *out += &format!("{:>width$} | ", "", width = gutter);
+    for g in &graphemes[line_starts[line]..from] {
+        if g.value == "\t" {
+            out.push('\t');
+        } else {
+            out.extend(std::iter::repeat(' ').take(g.display_width()));
+        }
+    }
+    let carets: usize = graphemes[from..to].iter().map(|g| g.display_width()).sum();
+    out.extend(std::iter::repeat('^').take(carets.max(1)));
+    if let Some(label) = label.filter(|l| !l.is_empty()) {
+        out.push(' ');
+        *out += label;
+    }
+    out.push('\n');
+}
+
+/// Renders the lines of `source` touched by `labels`, codespan style: a line-number
+/// gutter, carets under each labelled span and the label text after the last caret.
+///
+/// Spans over several lines are underlined on every line they cover; empty spans get a
+/// single caret. Carets are aligned by display width and tabs are repeated from the source.
+pub fn render<S: AsRef<str>>(source: &EzStr, labels: &[(GraphemeMatch, S)]) -> String {
+    let line_starts = source.line_starts();
+    let marks = marks(source, &line_starts, labels);
+    let gutter = marks.last().map_or(1, |m| (m.0 + 1).to_string().len());
+    let mut out = String::new();
+    let mut i = 0;
+    while i < marks.len() {
+        let line = marks[i].0;
+        push_line(&mut out, source, &line_starts, line, line + 1, gutter);
+        while i < marks.len() && marks[i].0 == line {
+            push_carets(&mut out, source, &line_starts, marks[i], gutter);
+            i += 1;
+        }
+    }
+    out
+}
+
+impl EzStr {
+    /// Every line with its number in a gutter, numbered from `start_line` (so an excerpt
+    /// can keep its numbers from the whole chart) and right-aligned in `gutter_width`
+    /// columns, widened if a number needs more. Same layout as `highlight::render`.
+    pub fn render_numbered(&self, start_line: usize, gutter_width: usize) -> String {
+        self.render_numbered_with_labels::<&str>(start_line, gutter_width, &[])
+    }
+
+    /// `render_numbered` with carets and labels under the spans of `labels`, as in
+    /// `highlight::render`.
+    pub fn render_numbered_with_labels<S: AsRef<str>>(&self, start_line: usize, gutter_width: usize, labels: &[(GraphemeMatch, S)]) -> String {
+        let line_starts = self.line_starts();
+        let marks = marks(self, &line_starts, labels);
+        let last_number = start_line + line_starts.len() - 1;
+        let gutter = gutter_width.max(last_number.to_string().len());
+        let mut out = String::new();
+        let mut marks = marks.into_iter().peekable();
+        for line in 0..line_starts.len() {
+            push_line(&mut out, self, &line_starts, line, start_line + line, gutter);
+            while let Some(mark) = marks.next_if(|m| m.0 == line) {
+                push_carets(&mut out, self, &line_starts, mark, gutter);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,109 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::EzStr;
+
+/// Indentation unit found by `EzStr::detect_indent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Indent {
+    Tabs,
+    Spaces(usize),
+}
+
+impl Indent {
+    /// One level of this indentation, e.g. four spaces.
+    pub fn unit(self) -> String {
+        match self {
+            Indent::Tabs => "\t".to_string(),
+            Indent::Spaces(n) => " ".repeat(n),
+        }
+    }
+}
+
+/// Leading whitespace of `line` in bytes, stopping before any grapheme that is not
+/// entirely whitespace (a space carrying a combining mark is content) or that ends the line.
+fn leading_whitespace(line: &str) -> usize {
+    line.graphemes(true)
+        .take_while(|g| g.chars().all(|c| c.is_whitespace() && c != '\n' && c != '\r'))
+        .map(str::len)
+        .sum()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim_end_matches(['\n', '\r']).len() == leading_whitespace(line)
+}
+
+impl EzStr {
+    /// Indentation unit of the text: tabs if more lines are indented with tabs than
+    /// with spaces, otherwise the most common increase in spaces from one non-blank line
+    /// to the next. `None` when no line is indented.
+    pub fn detect_indent(&self) -> Option<Indent> {
+        let (mut tabs, mut spaces) = (0, 0);
+        let mut deltas: Vec<(usize, usize)> = Vec::new();
+        let mut previous = 0;
+        for line in self.as_str().lines().filter(|line| !is_blank(line)) {
+            let indent = &line[..leading_whitespace(line)];
+            if indent.starts_with('\t') {
+                tabs += 1;
+                continue;
+            }
+            let width = indent.chars().count();
+            if width > 0 {
+                spaces += 1;
+            }
+            if width > previous {
+                match deltas.iter_mut().find(|(delta, _)| *delta == width - previous) {
+                    Some((_, count)) => *count += 1,
+                    None => deltas.push((width - previous, 1)),
+                }
+            }
+            previous = width;
+        }
+        if tabs == 0 && spaces == 0 {
+            return None;
+        }
+        if tabs > spaces {
+            return Some(Indent::Tabs);
+        }
+        // Ties go to the smaller step.
+        deltas.sort_by_key(|&(delta, count)| (std::cmp::Reverse(count), delta));
+        deltas.first().map(|&(delta, _)| Indent::Spaces(delta))
+    }
+
+    /// Prefixes every non-blank line with `prefix`.
+    pub fn indent<T: AsRef<str>>(&self, prefix: T) -> EzStr {
+        let prefix = prefix.as_ref();
+        let mut out = String::with_capacity(self.as_str().len());
+        for line in self.as_str().split_inclusive('\n') {
+            if !is_blank(line) {
+                out += prefix;
+            }
+            out += line;
+        }
+        EzStr::new(out)
+    }
+
+    /// Removes the leading whitespace common to all non-blank lines, like Python's
+    /// `textwrap.dedent`. Tabs and spaces are not interchangeable, and whitespace-only
+    /// lines are emptied up to their line break.
+    pub fn dedent(&self) -> EzStr {
+        let data = self.as_str();
+        let common = data
+            .split_inclusive('\n')
+            .filter(|line| !is_blank(line))
+            .map(|line| &line[..leading_whitespace(line)])
+            .reduce(|a, b| {
+                let shared: usize = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).map(|(x, _)| x.len_utf8()).sum();
+                &a[..shared]
+            })
+            .unwrap_or("");
+        let mut out = String::with_capacity(data.len());
+        for line in data.split_inclusive('\n') {
+            if is_blank(line) {
+                out += &line[leading_whitespace(line)..];
+            } else {
+                out += &line[common.len()..];
+            }
+        }
+        EzStr::new(out)
+    }
+}
@@ -0,0 +1,98 @@
+//! Suffix-array index for answering many substring queries over the same text.
+use crate::{EzStr, GSpan};
+
+/// Suffix array over an `EzStr`, for repeated substring searches over a large text
+/// that does not change. Building is `O(n log² n)` and a query costs `O(m log n)`
+/// plus the number of hits; only suffixes starting on a grapheme boundary are kept.
+///
+/// Texts over 4 GiB are not supported.
+#[derive(Debug, Clone)]
+pub struct EzStrIndex {
+    text: EzStr,
+    /// Byte offsets of the grapheme-aligned suffixes, in lexicographic order.
+    suffixes: Vec<u32>,
+}
+
+impl EzStrIndex {
+    pub fn build(text: &EzStr) -> Self {
+        let data = text.as_str().as_bytes();
+        assert!(data.len() <= u32::MAX as usize, "text of {} bytes is too large to index", data.len());
+        let index = text.grapheme_index();
+        let suffixes = suffix_array(data).into_iter().filter(|&i| index.search(i as usize).is_ok()).collect();
+        EzStrIndex { text: text.clone(), suffixes }
+    }
+
+    pub fn text(&self) -> &EzStr {
+        &self.text
+    }
+
+    /// Grapheme spans of every occurrence of `pattern`, in text order. Occurrences
+    /// that start or end inside a grapheme are skipped, and an empty pattern matches
+    /// nothing. Unlike `str::matches`, overlapping occurrences are all returned.
+    pub fn find_all(&self, pattern: &str) -> Vec<GSpan> {
+        let index = self.text.grapheme_index();
+        let len = self.text.as_str().len();
+        let mut spans: Vec<GSpan> = self
+            .candidates(pattern)
+            .iter()
+            .filter_map(|&start| {
+                let (start, end) = (start as usize, start as usize + pattern.len());
+                let g_start = index.search(start).ok()?;
+                let g_end = if end == len { index.len() } else { index.search(end).ok()? };
+                Some(GSpan::new(g_start, g_end))
+            })
+            .collect();
+        spans.sort_unstable();
+        spans
+    }
+
+    /// Number of occurrences, as counted by `find_all`.
+    pub fn count(&self, pattern: &str) -> usize {
+        self.find_all(pattern).len()
+    }
+
+    pub fn contains(&self, pattern: &str) -> bool {
+        !self.find_all(pattern).is_empty()
+    }
+
+    /// Suffixes starting with `pattern`, in suffix order.
+    fn candidates(&self, pattern: &str) -> &[u32] {
+        if pattern.is_empty() {
+            return &[];
+        }
+        let data = self.text.as_str().as_bytes();
+        let pattern = pattern.as_bytes();
+        let prefix = |i: u32| &data[i as usize..(i as usize + pattern.len()).min(data.len())];
+        let lo = self.suffixes.partition_point(|&i| prefix(i) < pattern);
+        let hi = self.suffixes.partition_point(|&i| prefix(i) <= pattern);
+        &self.suffixes[lo..hi]
+    }
+}
+
+/// Suffix array of `s` by prefix doubling: suffixes are sorted on their first `2k`
+/// bytes using the ranks from the previous round, until all ranks are distinct.
+fn suffix_array(s: &[u8]) -> Vec<u32> {
+    let n = s.len();
+    let mut sa: Vec<u32> = (0..n as u32).collect();
+    let mut rank: Vec<u32> = s.iter().map(|&b| b as u32).collect();
+    let mut next = vec![0u32; n];
+    if n < 2 {
+        return sa;
+    }
+    let mut k = 1;
+    loop {
+        // a suffix shorter than `k` sorts before any longer one with the same start
+        let key = |i: u32| (rank[i as usize], rank.get(i as usize + k).map_or(0, |&r| r + 1));
+        sa.sort_unstable_by_key(|&i| key(i));
+        next[sa[0] as usize] = 0;
+        for w in 1..n {
+            next[sa[w] as usize] = next[sa[w - 1] as usize] + (key(sa[w - 1]) < key(sa[w])) as u32;
+        }
+        std::mem::swap(&mut rank, &mut next);
+        if rank[sa[n - 1] as usize] as usize == n - 1 {
+            break;
+        }
+        k *= 2;
+    }
+    sa
+}
@@ -0,0 +1,121 @@
+use crate::split::Delimiter;
+use crate::{EzStr, GraphemeMatch};
+
+/// One `key: value` pair from `EzStr::parse_kv_lines`, with the spans of both sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvEntry {
+    pub key: GraphemeMatch,
+    pub value: GraphemeMatch,
+}
+
+/// Entries in the order they appear in the text. Repeated keys are all kept;
+/// `get` returns the first.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct KvLines {
+    entries: Vec<KvEntry>,
+}
+
+impl KvLines {
+    pub fn get<T: AsRef<str>>(&self, key: T) -> Option<&EzStr> {
+        self.entry(key).map(|e| &e.value.text)
+    }
+
+    pub fn entry<T: AsRef<str>>(&self, key: T) -> Option<&KvEntry> {
+        self.entries.iter().find(|e| e.key.text.as_str() == key.as_ref())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &EzStr> {
+        self.entries.iter().map(|e| &e.key.text)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, KvEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl IntoIterator for KvLines {
+    type Item = KvEntry;
+    type IntoIter = std::vec::IntoIter<KvEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a KvLines {
+    type Item = &'a KvEntry;
+    type IntoIter = std::slice::Iter<'a, KvEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Byte range of `s[start..end]` without surrounding whitespace or `*` frame markers.
+fn trim_range(s: &str, start: usize, end: usize) -> (usize, usize) {
+    let frame = |c: char| c.is_whitespace() || c == '*';
+    let part = &s[start..end];
+    let trimmed_start = part.trim_start_matches(frame);
+    let trimmed = trimmed_start.trim_end_matches(frame);
+    let from = start + part.len() - trimmed_start.len();
+    (from, from + trimmed.len())
+}
+
+/// Byte span of a trailing `©2024` (or `© 2020-2024`) notice in `s[start..end]`,
+/// as the span of the `©` and the span of the years.
+fn copyright(s: &str, start: usize, end: usize) -> Option<((usize, usize), (usize, usize))> {
+    let at = start + s[start..end].rfind('©')?;
+    let (years_start, years_end) = trim_range(s, at + '©'.len_utf8(), end);
+    let years = &s[years_start..years_end];
+    let is_year = |c: char| c.is_ascii_digit() || c == '-' || c == '–';
+    if years.is_empty() || !years.starts_with(|c: char| c.is_ascii_digit()) || !years.chars().all(is_year) {
+        return None;
+    }
+    Some(((at, at + '©'.len_utf8()), (years_start, years_end)))
+}
+
+impl EzStr {
+    /// Reads `key<separator>value` lines such as the "By: …" line of a chart header.
+    /// Keys and values are trimmed of whitespace and `*` frame markers, lines without
+    /// the separator are skipped, and a trailing `©2024` notice becomes its own entry
+    /// with key "©", so "By: Alin Rogoz  ©2024" gives `By` and `©`.
+    pub fn parse_kv_lines<D: Delimiter>(&self, separator: D) -> KvLines {
+        let data = self.as_str();
+        let mut entries = Vec::new();
+        let mut push = |key: (usize, usize), value: (usize, usize)| {
+            let (k_start, k_end) = self.byte_range_to_grapheme_indices(key.0, key.1);
+            let (v_start, v_end) = self.byte_range_to_grapheme_indices(value.0, value.1);
+            entries.push(KvEntry {
+                key: GraphemeMatch::from_source(self, k_start, k_end),
+                value: GraphemeMatch::from_source(self, v_start, v_end),
+            });
+        };
+
+        let mut line_start = 0;
+        for line in data.split_inclusive('\n') {
+            let line_end = line_start + line.trim_end_matches(['\n', '\r']).len();
+            let notice = copyright(data, line_start, line_end);
+            let body_end = notice.map_or(line_end, |(mark, _)| mark.0);
+            let split = separator.byte_spans(&data[line_start..body_end]).into_iter().next();
+            if let Some((sep_start, sep_end)) = split {
+                let key = trim_range(data, line_start, line_start + sep_start);
+                if key.0 < key.1 {
+                    push(key, trim_range(data, line_start + sep_end, body_end));
+                }
+            }
+            if let Some((mark, years)) = notice {
+                push(mark, years);
+            }
+            line_start += line.len();
+        }
+        KvLines { entries }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{EzStr, GSpan};
+
+/// One column of a `Layout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// The column's lines joined by "\n", without padding.
+    pub text: EzStr,
+    /// Source span of each line, in order; wrapped-away whitespace is not included.
+    pub lines: Vec<GSpan>,
+}
+
+impl Column {
+    /// Source span from the start of the first line to the end of the last one.
+    pub fn span(&self) -> Option<GSpan> {
+        Some(GSpan::new(self.lines.first()?.start, self.lines.last()?.end))
+    }
+}
+
+/// Text flowed into columns by `EzStr::layout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// Filled left to right, each up to the layout height.
+    pub columns: Vec<Column>,
+    /// Display width of a column.
+    pub width: usize,
+    /// Grapheme index where the text that did not fit starts, e.g. for the next page.
+    pub overflow: Option<usize>,
+}
+
+impl Layout {
+    /// The columns side by side, `gap` spaces apart, each padded to the column width.
+    /// Trailing spaces are trimmed from every row.
+    pub fn render(&self, gap: usize) -> EzStr {
+        let rows = self.columns.iter().map(|c| c.lines.len()).max().unwrap_or(0);
+        let columns: Vec<Vec<EzStr>> = self
+            .columns
+            .iter()
+            .map(|c| c.text.as_str().split('\n').map(EzStr::new).collect())
+            .collect();
+        let mut out = String::new();
+        for row in 0..rows {
+            let mut line = String::new();
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    line.extend(std::iter::repeat(' ').take(gap));
+                }
+                let cell = column.get(row).filter(|_| row < self.columns[i].lines.len());
+                let cell_width = cell.map_or(0, |c| c.display_width());
+                line += cell.map_or("", |c| c.as_str());
+                line.extend(std::iter::repeat(' ').take(self.width.saturating_sub(cell_width)));
+            }
+            out += line.trim_end_matches(' ');
+            out.push('\n');
+        }
+        EzStr::new(out)
+    }
+}
+
+impl EzStr {
+    /// Wraps the text to `width` display columns (see `wrap`) and flows the lines into
+    /// up to `columns` columns of `height` lines each, like a two-column printed chart.
+    /// Whatever does not fit is reported in `overflow` so it can go on the next page.
+    pub fn layout(&self, columns: usize, width: usize, height: usize) -> Layout {
+        let lines = self.wrap(width);
+        let capacity = columns.saturating_mul(height);
+        let placed = &lines[..lines.len().min(capacity)];
+        let columns = placed
+            .chunks(height.max(1))
+            .map(|chunk| Column {
+                text: EzStr::new(chunk.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n")),
+                lines: chunk.iter().map(|l| l.span()).collect(),
+            })
+            .collect();
+        Layout { columns, width, overflow: lines.get(capacity).map(|l| l.start) }
+    }
+}
@@ -3,6 +3,7 @@
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::fmt::Display;
+use std::fmt::Write as _;
 use std::ops::Index;
 use std::ops::Add;
 use std::slice::SliceIndex;
@@ -10,31 +11,260 @@ use std::hash::Hash;
 use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation;
 use once_cell::sync::OnceCell;
+use std::sync::Mutex;
+use prefix_index::PrefixIndex;
+use repr::Repr;
+
+pub mod annotated;
+pub mod ansi;
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod builder;
+pub mod case;
+#[cfg(feature = "collate")]
+pub mod collate;
+#[cfg(feature = "columnar")]
+pub mod columnar;
+pub mod compose;
+pub mod confusable;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod diff;
+pub mod distance;
+pub mod edit;
+pub mod emoji;
+pub mod escape;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+#[cfg(feature = "testing")]
+pub mod fuzz_helpers;
+pub mod fuzzy;
+pub mod glob;
+pub mod highlight;
+pub mod indent;
+#[cfg(feature = "index")]
+pub mod index;
+pub mod kv;
+pub mod layout;
+pub mod lines;
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
+#[cfg(feature = "music")]
+pub mod music;
+pub mod natural;
+pub mod ngram;
+pub mod normalize;
+pub mod numbers;
+pub mod paste;
+pub mod pattern;
+pub mod persistent;
+pub mod rect;
+pub mod redact;
+pub mod runs;
+pub mod script;
+pub mod search;
+pub mod selection;
+#[cfg(feature = "bidi")]
+pub mod bidi;
+#[cfg(feature = "translit")]
+pub mod translit;
+pub mod slug;
+pub mod span;
+pub mod split;
+pub mod table;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trie;
+pub mod view;
+pub mod whitespace;
+pub mod width;
+pub mod words;
+pub mod wrap;
+mod common;
+mod error;
+mod grapheme_index;
+mod grapheme_ref;
+mod grapheme_str;
+mod macros;
+mod prefix_index;
+mod repr;
+mod segment;
+mod span_map;
+
+pub use error::Error;
+pub use grapheme_index::GraphemeIndex;
+pub use grapheme_ref::GraphemeRef;
+pub use grapheme_str::GraphemeStr;
+pub use segment::{ExtendedGraphemes, LegacyGraphemes, Segmenter};
+pub use span::{Bias, GSpan, SpanSet};
+pub use span_map::SpanMap;
+pub use view::EzSlice;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Grapheme {
-    pub value: String,
+    pub value: GraphemeStr,
 }
 
 impl Grapheme {
     pub fn new(value: &str) -> Grapheme {
         Grapheme {
-            value: String::from(value),
+            value: GraphemeStr::new(value),
         }
     }
 }
 
+impl Grapheme {
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// Borrows the cluster as a `GraphemeRef`.
+    pub fn as_grapheme_ref(&self) -> GraphemeRef<'_> {
+        GraphemeRef(self.value.as_str())
+    }
+
+    /// True if every char of the cluster is whitespace, e.g. `"\r\n"`.
+    pub fn is_whitespace(&self) -> bool {
+        self.as_grapheme_ref().is_whitespace()
+    }
+
+    /// True for a letter cluster; see `GraphemeRef::is_alphabetic`.
+    pub fn is_alphabetic(&self) -> bool {
+        self.as_grapheme_ref().is_alphabetic()
+    }
+
+    /// True for a digit or other numeric char, optionally with combining marks.
+    pub fn is_numeric(&self) -> bool {
+        self.as_grapheme_ref().is_numeric()
+    }
+
+    /// True if the cluster is made only of combining marks.
+    pub fn is_combining(&self) -> bool {
+        self.as_grapheme_ref().is_combining()
+    }
+}
+
+// Nonspacing, spacing and enclosing marks (Mn, Mc, Me), from Unicode 14.0.0.
+const COMBINING_MARKS: &[(u32, u32)] = &[
+    (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2), (0x05C4, 0x05C5), (0x05C7, 0x05C7), (0x0610, 0x061A),
+    (0x064B, 0x065F), (0x0670, 0x0670), (0x06D6, 0x06DC), (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8), (0x06EA, 0x06ED), (0x0711, 0x0711), (0x0730, 0x074A),
+    (0x07A6, 0x07B0), (0x07EB, 0x07F3), (0x07FD, 0x07FD), (0x0816, 0x0819),
+    (0x081B, 0x0823), (0x0825, 0x0827), (0x0829, 0x082D), (0x0859, 0x085B),
+    (0x0898, 0x089F), (0x08CA, 0x08E1), (0x08E3, 0x0903), (0x093A, 0x093C),
+    (0x093E, 0x094F), (0x0951, 0x0957), (0x0962, 0x0963), (0x0981, 0x0983),
+    (0x09BC, 0x09BC), (0x09BE, 0x09C4), (0x09C7, 0x09C8), (0x09CB, 0x09CD),
+    (0x09D7, 0x09D7), (0x09E2, 0x09E3), (0x09FE, 0x09FE), (0x0A01, 0x0A03),
+    (0x0A3C, 0x0A3C), (0x0A3E, 0x0A42), (0x0A47, 0x0A48), (0x0A4B, 0x0A4D),
+    (0x0A51, 0x0A51), (0x0A70, 0x0A71), (0x0A75, 0x0A75), (0x0A81, 0x0A83),
+    (0x0ABC, 0x0ABC), (0x0ABE, 0x0AC5), (0x0AC7, 0x0AC9), (0x0ACB, 0x0ACD),
+    (0x0AE2, 0x0AE3), (0x0AFA, 0x0AFF), (0x0B01, 0x0B03), (0x0B3C, 0x0B3C),
+    (0x0B3E, 0x0B44), (0x0B47, 0x0B48), (0x0B4B, 0x0B4D), (0x0B55, 0x0B57),
+    (0x0B62, 0x0B63), (0x0B82, 0x0B82), (0x0BBE, 0x0BC2), (0x0BC6, 0x0BC8),
+    (0x0BCA, 0x0BCD), (0x0BD7, 0x0BD7), (0x0C00, 0x0C04), (0x0C3C, 0x0C3C),
+    (0x0C3E, 0x0C44), (0x0C46, 0x0C48), (0x0C4A, 0x0C4D), (0x0C55, 0x0C56),
+    (0x0C62, 0x0C63), (0x0C81, 0x0C83), (0x0CBC, 0x0CBC), (0x0CBE, 0x0CC4),
+    (0x0CC6, 0x0CC8), (0x0CCA, 0x0CCD), (0x0CD5, 0x0CD6), (0x0CE2, 0x0CE3),
+    (0x0D00, 0x0D03), (0x0D3B, 0x0D3C), (0x0D3E, 0x0D44), (0x0D46, 0x0D48),
+    (0x0D4A, 0x0D4D), (0x0D57, 0x0D57), (0x0D62, 0x0D63), (0x0D81, 0x0D83),
+    (0x0DCA, 0x0DCA), (0x0DCF, 0x0DD4), (0x0DD6, 0x0DD6), (0x0DD8, 0x0DDF),
+    (0x0DF2, 0x0DF3), (0x0E31, 0x0E31), (0x0E34, 0x0E3A), (0x0E47, 0x0E4E),
+    (0x0EB1, 0x0EB1), (0x0EB4, 0x0EBC), (0x0EC8, 0x0ECD), (0x0F18, 0x0F19),
+    (0x0F35, 0x0F35), (0x0F37, 0x0F37), (0x0F39, 0x0F39), (0x0F3E, 0x0F3F),
+    (0x0F71, 0x0F84), (0x0F86, 0x0F87), (0x0F8D, 0x0F97), (0x0F99, 0x0FBC),
+    (0x0FC6, 0x0FC6), (0x102B, 0x103E), (0x1056, 0x1059), (0x105E, 0x1060),
+    (0x1062, 0x1064), (0x1067, 0x106D), (0x1071, 0x1074), (0x1082, 0x108D),
+    (0x108F, 0x108F), (0x109A, 0x109D), (0x135D, 0x135F), (0x1712, 0x1715),
+    (0x1732, 0x1734), (0x1752, 0x1753), (0x1772, 0x1773), (0x17B4, 0x17D3),
+    (0x17DD, 0x17DD), (0x180B, 0x180D), (0x180F, 0x180F), (0x1885, 0x1886),
+    (0x18A9, 0x18A9), (0x1920, 0x192B), (0x1930, 0x193B), (0x1A17, 0x1A1B),
+    (0x1A55, 0x1A5E), (0x1A60, 0x1A7C), (0x1A7F, 0x1A7F), (0x1AB0, 0x1ACE),
+    (0x1B00, 0x1B04), (0x1B34, 0x1B44), (0x1B6B, 0x1B73), (0x1B80, 0x1B82),
+    (0x1BA1, 0x1BAD), (0x1BE6, 0x1BF3), (0x1C24, 0x1C37), (0x1CD0, 0x1CD2),
+    (0x1CD4, 0x1CE8), (0x1CED, 0x1CED), (0x1CF4, 0x1CF4), (0x1CF7, 0x1CF9),
+    (0x1DC0, 0x1DFF), (0x20D0, 0x20F0), (0x2CEF, 0x2CF1), (0x2D7F, 0x2D7F),
+    (0x2DE0, 0x2DFF), (0x302A, 0x302F), (0x3099, 0x309A), (0xA66F, 0xA672),
+    (0xA674, 0xA67D), (0xA69E, 0xA69F), (0xA6F0, 0xA6F1), (0xA802, 0xA802),
+    (0xA806, 0xA806), (0xA80B, 0xA80B), (0xA823, 0xA827), (0xA82C, 0xA82C),
+    (0xA880, 0xA881), (0xA8B4, 0xA8C5), (0xA8E0, 0xA8F1), (0xA8FF, 0xA8FF),
+    (0xA926, 0xA92D), (0xA947, 0xA953), (0xA980, 0xA983), (0xA9B3, 0xA9C0),
+    (0xA9E5, 0xA9E5), (0xAA29, 0xAA36), (0xAA43, 0xAA43), (0xAA4C, 0xAA4D),
+    (0xAA7B, 0xAA7D), (0xAAB0, 0xAAB0), (0xAAB2, 0xAAB4), (0xAAB7, 0xAAB8),
+    (0xAABE, 0xAABF), (0xAAC1, 0xAAC1), (0xAAEB, 0xAAEF), (0xAAF5, 0xAAF6),
+    (0xABE3, 0xABEA), (0xABEC, 0xABED), (0xFB1E, 0xFB1E), (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F), (0x101FD, 0x101FD), (0x102E0, 0x102E0), (0x10376, 0x1037A),
+    (0x10A01, 0x10A03), (0x10A05, 0x10A06), (0x10A0C, 0x10A0F), (0x10A38, 0x10A3A),
+    (0x10A3F, 0x10A3F), (0x10AE5, 0x10AE6), (0x10D24, 0x10D27), (0x10EAB, 0x10EAC),
+    (0x10F46, 0x10F50), (0x10F82, 0x10F85), (0x11000, 0x11002), (0x11038, 0x11046),
+    (0x11070, 0x11070), (0x11073, 0x11074), (0x1107F, 0x11082), (0x110B0, 0x110BA),
+    (0x110C2, 0x110C2), (0x11100, 0x11102), (0x11127, 0x11134), (0x11145, 0x11146),
+    (0x11173, 0x11173), (0x11180, 0x11182), (0x111B3, 0x111C0), (0x111C9, 0x111CC),
+    (0x111CE, 0x111CF), (0x1122C, 0x11237), (0x1123E, 0x1123E), (0x112DF, 0x112EA),
+    (0x11300, 0x11303), (0x1133B, 0x1133C), (0x1133E, 0x11344), (0x11347, 0x11348),
+    (0x1134B, 0x1134D), (0x11357, 0x11357), (0x11362, 0x11363), (0x11366, 0x1136C),
+    (0x11370, 0x11374), (0x11435, 0x11446), (0x1145E, 0x1145E), (0x114B0, 0x114C3),
+    (0x115AF, 0x115B5), (0x115B8, 0x115C0), (0x115DC, 0x115DD), (0x11630, 0x11640),
+    (0x116AB, 0x116B7), (0x1171D, 0x1172B), (0x1182C, 0x1183A), (0x11930, 0x11935),
+    (0x11937, 0x11938), (0x1193B, 0x1193E), (0x11940, 0x11940), (0x11942, 0x11943),
+    (0x119D1, 0x119D7), (0x119DA, 0x119E0), (0x119E4, 0x119E4), (0x11A01, 0x11A0A),
+    (0x11A33, 0x11A39), (0x11A3B, 0x11A3E), (0x11A47, 0x11A47), (0x11A51, 0x11A5B),
+    (0x11A8A, 0x11A99), (0x11C2F, 0x11C36), (0x11C38, 0x11C3F), (0x11C92, 0x11CA7),
+    (0x11CA9, 0x11CB6), (0x11D31, 0x11D36), (0x11D3A, 0x11D3A), (0x11D3C, 0x11D3D),
+    (0x11D3F, 0x11D45), (0x11D47, 0x11D47), (0x11D8A, 0x11D8E), (0x11D90, 0x11D91),
+    (0x11D93, 0x11D97), (0x11EF3, 0x11EF6), (0x16AF0, 0x16AF4), (0x16B30, 0x16B36),
+    (0x16F4F, 0x16F4F), (0x16F51, 0x16F87), (0x16F8F, 0x16F92), (0x16FE4, 0x16FE4),
+    (0x16FF0, 0x16FF1), (0x1BC9D, 0x1BC9E), (0x1CF00, 0x1CF2D), (0x1CF30, 0x1CF46),
+    (0x1D165, 0x1D169), (0x1D16D, 0x1D172), (0x1D17B, 0x1D182), (0x1D185, 0x1D18B),
+    (0x1D1AA, 0x1D1AD), (0x1D242, 0x1D244), (0x1DA00, 0x1DA36), (0x1DA3B, 0x1DA6C),
+    (0x1DA75, 0x1DA75), (0x1DA84, 0x1DA84), (0x1DA9B, 0x1DA9F), (0x1DAA1, 0x1DAAF),
+    (0x1E000, 0x1E006), (0x1E008, 0x1E018), (0x1E01B, 0x1E021), (0x1E023, 0x1E024),
+    (0x1E026, 0x1E02A), (0x1E130, 0x1E136), (0x1E2AE, 0x1E2AE), (0x1E2EC, 0x1E2EF),
+    (0x1E8D0, 0x1E8D6), (0x1E944, 0x1E94A), (0xE0100, 0xE01EF),
+];
+
+pub(crate) fn is_combining_mark(c: char) -> bool {
+    emoji::in_table(c, COMBINING_MARKS)
+}
+
 impl std::fmt::Display for Grapheme {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}", self.value)
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Default, Hash)]
+/// Matches compare and hash by `start`, `end` and `text` only: the byte and char
+/// offsets follow from those for matches of the same source, and are left at 0 by
+/// `GraphemeMatch::new`.
+#[derive(Clone, Default)]
 pub struct GraphemeMatch {
     pub start: usize,
     pub end: usize,
     pub text: EzStr,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+impl PartialEq for GraphemeMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end && self.text == other.text
+    }
+}
+
+impl Eq for GraphemeMatch {}
+
+impl Hash for GraphemeMatch {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.start.hash(state);
+        self.end.hash(state);
+        self.text.hash(state);
+    }
 }
 
 impl GraphemeMatch {
@@ -42,12 +272,42 @@ impl GraphemeMatch {
     where
         T: Into<EzStr>,
     {
-        let it = GraphemeMatch { start, end, text:text.into()};
+        let it = GraphemeMatch { start, end, text:text.into(), ..Default::default()};
         it
 
 
     }
 
+    /// Builds a match for the grapheme span `start..end` of `source`, filling in byte and char offsets.
+    pub fn from_source(source: &EzStr, start: usize, end: usize) -> Self {
+        let char_start = source.char_offset(start);
+        GraphemeMatch::from_source_at(source, start, end, char_start)
+    }
+
+    /// `from_source` for callers that already know the char offset of `start`.
+    pub(crate) fn from_source_at(source: &EzStr, start: usize, end: usize, char_start: usize) -> Self {
+        let byte_start = source.grapheme_to_byte(start);
+        let byte_end = source.grapheme_to_byte(end);
+        let char_end = char_start + source.data[byte_start..byte_end].chars().count();
+        GraphemeMatch {
+            start,
+            end,
+            text: EzStr::from_repr(source.data.slice(byte_start, byte_end)),
+            byte_start,
+            byte_end,
+            char_start,
+            char_end,
+        }
+    }
+
+    pub fn byte_range(&self) -> std::ops::Range<usize> {
+        self.byte_start..self.byte_end
+    }
+
+    pub fn char_range(&self) -> std::ops::Range<usize> {
+        self.char_start..self.char_end
+    }
+
 
 
 
@@ -60,57 +320,71 @@ impl GraphemeMatch {
     }
 
     pub fn is_valid(&self, source:&EzStr) -> bool {
-        //let a = self.source.chars().collect::<Vec<_>>()[self.start..self.end].to_vec();
-        // let a = &self.source.graphemes(true).collect::<Vec<_>>()[self.start..self.end].join("");
+        self.validate(source).is_ok()
+    }
+
+    /// Checks that the match text is found at `start..end` in `source`.
+    pub fn validate(&self, source: &EzStr) -> Result<(), MatchValidationError> {
+        let end = self.end.min(source.len());
+        let start = self.start.min(end);
+        let actual = source.slice_strict(start, end);
+        if self.end <= source.len() && actual == self.text {
+            return Ok(());
+        }
 
-        let a = source.slice(self.start as i32,self.end as i32);
-        let b = EzStr::new(&self.text.data);
-        let ret = a == b;
-        // if !ret {
-        //     panic!("a:{a:?} b:{b:?}\n{self:?}")
-        // }
-        ret
+        let re = Regex::new(&regex::escape(self.text.as_str())).unwrap();
+        let found_at = source.find_iter(&re).map(|m| (m.start, m.end)).collect();
+        Err(MatchValidationError {
+            expected: self.text.clone(),
+            start: self.start,
+            end: self.end,
+            actual,
+            found_at,
+        })
     }
 
+    /// Like `validate`, but panics with the error description when the match is invalid.
     pub fn ensure_is_valid<S: Into<EzStr> + Clone>(&self, source:S) -> () {
         let source = source.into();
-        if !self.is_valid(&source) {
-
-            // let span = &self.source[self.start..self.end];
-            // let re = Regex::new(self.text.as_str()).unwrap();
-            // let text_matches: Vec<_> = regex_find_graphemes_iter(&re, self.source).collect();
-            let a = source.slice(self.start as i32,self.end as i32);
-            let b = EzStr::new(&self.text.data);
-            let re = Regex::new(b.data.as_str().replace("|",r"\|").as_str()).unwrap();
-            let source:EzStr = source.into();
-            let text_matches: Vec<_> = source.find_iter(&re).collect();
-
-            let mut panic_str = String::new();
-            if text_matches.len() > 0 {
-                panic_str += format!("\n, so not found at [{},{}] but was found at        ",self.start,self.end).as_str();
-                for re_match in text_matches{
-                    panic_str += format!("[{},{}]: {:?}", re_match.start, re_match.end, source.slice(re_match.start.try_into().unwrap(),
-                                                                                                     re_match.end.try_into().unwrap())).as_str();
-                }
-                panic_str += "\n\n";
-            }
-            //panic!("source:{:?}\nre: {:?}\ntext_matches:{:?}",source,re,text_matches);
-            panic_str += format!("substring: {b:?} not at source.slice({},{}): {a:?} \nInvalid {self:?}\n{:?}",
-                                        self.start, self.end,
-                                        &source).as_str();
+        if let Err(err) = self.validate(&source) {
+            panic!("{}\n{}", err, highlight::render(&source, &[(self.clone(), "expected here")]));
+        }
+    }
 
 
+}
 
-            panic!("{}",panic_str);
-        } else {
-            println!("Successful match found at: {:?}", self);
+/// Why a `GraphemeMatch` does not describe its source, as returned by `GraphemeMatch::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchValidationError {
+    pub expected: EzStr,
+    pub start: usize,
+    pub end: usize,
+    /// What the source actually holds at `start..end` (clamped to the source length).
+    pub actual: EzStr,
+    /// Grapheme spans where `expected` does occur in the source.
+    pub found_at: Vec<(usize, usize)>,
+}
 
+impl Display for MatchValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "substring: {:?} not at source.slice({},{}): {:?}",
+            self.expected, self.start, self.end, self.actual
+        )?;
+        if !self.found_at.is_empty() {
+            write!(f, ", but was found at")?;
+            for (start, end) in &self.found_at {
+                write!(f, " [{},{}]", start, end)?;
+            }
         }
+        Ok(())
     }
-
-
 }
 
+impl std::error::Error for MatchValidationError {}
+
 impl<'a> Display for GraphemeMatch{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -121,17 +395,77 @@ impl<'a> fmt::Debug for GraphemeMatch{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "GraphemeMatch {{ start: {:?}, end: {}, text: {:?} }}",
-            self.start, self.end, self.text
+            "GraphemeMatch {{ start: {:?}, end: {}, text: {:?}, bytes: {:?}, chars: {:?} }}",
+            self.start, self.end, self.text, self.byte_range(), self.char_range()
         )
     }
 }
 
 
+/// A grapheme position counted from the start (`Pos`) or back from the end (`Neg`).
+///
+/// `Neg(n)` follows Python: `Neg(1)` is the last grapheme. Plain integers convert
+/// with the same meaning, negative values becoming `Neg`.
+///
+/// This is the one place signed offsets are accepted: everything else in the crate
+/// takes and returns `usize` grapheme indices, and `EzStr::index_of_offset` turns a
+/// signed offset into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GIndex {
+    Pos(usize),
+    Neg(usize),
+}
+
+impl GIndex {
+    /// Turns the index into an absolute position for a string of `len` graphemes, clamped to `0..=len`.
+    pub fn resolve(self, len: usize) -> usize {
+        match self {
+            GIndex::Pos(n) => n.min(len),
+            GIndex::Neg(n) => len.saturating_sub(n),
+        }
+    }
+
+    /// Like `resolve`, but `None` instead of clamping when the index falls outside `0..=len`.
+    pub fn checked_resolve(self, len: usize) -> Option<usize> {
+        match self {
+            GIndex::Pos(n) => (n <= len).then_some(n),
+            GIndex::Neg(n) => len.checked_sub(n),
+        }
+    }
+}
+
+impl From<usize> for GIndex {
+    fn from(item: usize) -> Self {
+        GIndex::Pos(item)
+    }
+}
+
+impl From<i32> for GIndex {
+    fn from(item: i32) -> Self {
+        if item < 0 {
+            GIndex::Neg(item.unsigned_abs() as usize)
+        } else {
+            GIndex::Pos(item as usize)
+        }
+    }
+}
+
+impl From<isize> for GIndex {
+    fn from(item: isize) -> Self {
+        if item < 0 {
+            GIndex::Neg(item.unsigned_abs())
+        } else {
+            GIndex::Pos(item as usize)
+        }
+    }
+}
+
 pub struct EzStr {
-    pub data: String,
-    pub graphemes_data: OnceCell<Vec<Grapheme>>,
-    grapheme_byte_index_data: OnceCell<Vec<(usize, usize)>>, // (byte_offset, grapheme_index)
+    data: Repr,
+    graphemes_data: OnceCell<Vec<Grapheme>>,
+    grapheme_index_data: OnceCell<GraphemeIndex>,
+    // graphemes segmented so far, until the full index is built
+    prefix_index_data: Mutex<Option<Box<PrefixIndex>>>,
 }
 
 impl Hash for EzStr {
@@ -152,73 +486,246 @@ impl Eq for EzStr {}
 
 impl EzStr {
     pub fn new<S: Into<String>>(data: S) -> Self {
-        let data = data.into();
+        EzStr::from_repr(Repr::from_string(data.into()))
+    }
+
+    /// Wraps a `'static` string without copying it. Slices of the result borrow it too.
+    ///
+    /// This is a `const fn`, so it can initialize a `static`; the grapheme caches are
+    /// still built on first use.
+    pub const fn from_static(data: &'static str) -> Self {
+        EzStr::from_repr(Repr::Static(data))
+    }
+
+    const fn from_repr(data: Repr) -> Self {
         EzStr {
             data,
             graphemes_data: OnceCell::new(),
-            grapheme_byte_index_data: OnceCell::new(),
+            grapheme_index_data: OnceCell::new(),
+            prefix_index_data: Mutex::new(None),
         }
     }
 
+    /// Builds an EzStr from already segmented graphemes, with the grapheme cache prewarmed.
+    pub fn from_graphemes<I: IntoIterator<Item = Grapheme>>(graphemes: I) -> Self {
+        let graphemes: Vec<Grapheme> = graphemes.into_iter().collect();
+        let data: String = graphemes.iter().map(|g| g.value.as_str()).collect();
+        let offsets = graphemes.iter().scan(0, |at, g| {
+            let start = *at;
+            *at += g.value.len();
+            Some(start)
+        });
+        let it = EzStr::new(data);
+        let _ = it.grapheme_index_data.set(GraphemeIndex::from_offsets(offsets));
+        let _ = it.graphemes_data.set(graphemes);
+        it
+    }
+
+    /// Builds an EzStr whose graphemes come from `segmenter` instead of the default
+    /// extended clusters. Only this value is affected: slices and other derived strings
+    /// are segmented by the default rules again, so re-apply it with `resegment` where
+    /// needed. Panics if the segmenter's offsets are not ascending char boundaries
+    /// starting at 0.
+    pub fn with_segmenter<S: Into<String>, G: Segmenter + ?Sized>(data: S, segmenter: &G) -> Self {
+        EzStr::new(data).resegment(segmenter)
+    }
+
+    /// This text with its graphemes from `segmenter`; see `with_segmenter`.
+    pub fn resegment<G: Segmenter + ?Sized>(&self, segmenter: &G) -> Self {
+        let data = self.as_str();
+        let starts = segmenter.grapheme_starts(data);
+        let valid = starts.first().map_or(data.is_empty(), |&first| first == 0)
+            && starts.windows(2).all(|w| w[0] < w[1])
+            && starts.iter().all(|&s| s < data.len() && data.is_char_boundary(s));
+        assert!(valid, "segmenter returned invalid grapheme starts {:?} for {:?}", starts, data);
+        let it = EzStr::from_repr(self.data.clone());
+        let _ = it.grapheme_index_data.set(GraphemeIndex::from_offsets(starts));
+        it
+    }
+
+    /// Builds the grapheme caches up front instead of on first use.
+    pub fn indexed(self) -> Self {
+        self.index_all();
+        self
+    }
+
+    /// Builds the grapheme caches now. Until then, slicing with non-negative indices
+    /// only segments the text up to the end of the slice.
+    pub fn index_all(&self) {
+        self.graphemes();
+        self.grapheme_index();
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.data.as_str()
+    }
+
+    pub fn into_string(self) -> String {
+        self.data.into_string()
+    }
+
+    /// Whether the text is borrowed from a `'static` string rather than owned.
+    pub fn is_static(&self) -> bool {
+        matches!(self.data, Repr::Static(_))
+    }
+
+    /// Whether the text is short enough (22 bytes or less) to be stored without a heap allocation.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.data, Repr::Inline { .. })
+    }
+
     pub fn graphemes(&self) -> &Vec<Grapheme> {
         self.graphemes_data.get_or_init(|| {
-            UnicodeSegmentation::graphemes(self.data.as_str(), true)
-                .map(Grapheme::new)
-                .collect()
+            let index = self.grapheme_index();
+            let ends = index.iter().skip(1).chain(std::iter::once(self.data.len()));
+            index.iter().zip(ends).map(|(start, end)| Grapheme::new(&self.data[start..end])).collect()
         })
     }
 
-    pub fn graphemes_byte_index(&self) -> &Vec<(usize, usize)> {
-        self.grapheme_byte_index_data.get_or_init(|| {
-            self.data
-                .grapheme_indices(true)
-                .enumerate()
-                .map(|(gi, (bi, _))| (bi, gi))
-                .collect()
-        })
+    /// Byte offset of every grapheme, in a compact two-level index.
+    pub fn grapheme_index(&self) -> &GraphemeIndex {
+        self.grapheme_index_data
+            .get_or_init(|| GraphemeIndex::from_offsets(segment::grapheme_starts(&self.data)))
     }
 
-    fn byte_range_to_grapheme_indices(&self, start: usize, end: usize) -> (usize, usize) {
-        let idx = self.graphemes_byte_index();
+    /// `(byte_offset, grapheme_index)` pairs; prefer `grapheme_index`, which does not allocate.
+    pub fn graphemes_byte_index(&self) -> Vec<(usize, usize)> {
+        self.grapheme_index().iter().enumerate().map(|(gi, b)| (b, gi)).collect()
+    }
 
-        let g_start = match idx.binary_search_by_key(&start, |&(b, _)| b) {
-            Ok(i) => idx[i].1,
-            Err(i) => idx.get(i).map(|&(_, gi)| gi).unwrap_or(self.len()),
-        };
+    /// Byte offset at which grapheme `index` starts; `len()` maps to the end of the data.
+    fn grapheme_to_byte(&self, index: usize) -> usize {
+        self.try_grapheme_to_byte(index).unwrap_or(self.data.len())
+    }
 
-        let g_end = match idx.binary_search_by_key(&end, |&(b, _)| b) {
-            Ok(i) => idx[i].1,
-            Err(i) => idx.get(i).map(|&(_, gi)| gi).unwrap_or(self.len()),
-        };
+    /// Like `grapheme_to_byte` but `None` past `len()`. Without a full index only
+    /// the text up to `index` is segmented.
+    fn try_grapheme_to_byte(&self, index: usize) -> Option<usize> {
+        if let Some(idx) = self.grapheme_index_data.get() {
+            return idx.byte_offset(index).or((index == idx.len()).then_some(self.data.len()));
+        }
+        let mut guard = self.prefix_index_data.lock().unwrap();
+        let prefix = guard.get_or_insert_with(|| Box::new(PrefixIndex::new(self.data.len())));
+        let byte = prefix.byte_of(&self.data, index);
+        if prefix.is_complete() {
+            let _ = self.grapheme_index_data.set(GraphemeIndex::from_offsets(prefix.starts().iter().copied()));
+            *guard = None;
+        }
+        byte
+    }
 
-        (g_start, g_end)
+    /// Char offset at which grapheme `index` starts. With a full index only the chars
+    /// of one block of graphemes are counted, so building many matches stays linear.
+    pub(crate) fn char_offset(&self, index: usize) -> usize {
+        match self.grapheme_index_data.get() {
+            Some(idx) => idx.char_offset(self.as_str(), index.min(idx.len())),
+            None => self.data[..self.grapheme_to_byte(index)].chars().count(),
+        }
     }
 
-    pub fn slice(&self, start: i32, end: i32) -> EzStr {
-        let graphemes = self.graphemes();
-        let mut ret = String::new();
-        let mut start = start;
-        let mut end = end;
+    /// Grapheme index at which each line starts; the first entry is always 0.
+    pub(crate) fn line_starts(&self) -> Vec<usize> {
+        let mut starts = vec![0];
+        for (i, g) in self.graphemes().iter().enumerate() {
+            if g.value.contains('\n') {
+                starts.push(i + 1);
+            }
+        }
+        starts
+    }
 
-        if start < 0 {
-            start = graphemes.len() as i32 + start + 1;
+    fn byte_range_to_grapheme_indices(&self, start: usize, end: usize) -> (usize, usize) {
+        let idx = self.grapheme_index();
+        let at = |byte: usize| idx.search(byte).unwrap_or_else(|g| g);
+        (at(start), at(end))
+    }
+
+    /// Slices by grapheme index; `start >= end` gives an empty string. Panics if
+    /// `end > len()`. Use `slice_py` for offsets from the end.
+    pub fn slice(&self, start: usize, end: usize) -> EzStr {
+        if start >= end {
+            return EzStr::default();
         }
-        if end < 0 {
-            end = graphemes.len() as i32 + end + 1;
+        self.slice_strict(start, end)
+    }
+
+    /// The old signed `slice`, where negative indices count from one past the end, so
+    /// `-1` is `len()`. Panics if an index falls outside the string.
+    #[deprecated(since = "0.3.0", note = "use `slice` with `usize` indices, or `slice_py` for offsets from the end")]
+    pub fn slice_signed(&self, start: isize, end: isize) -> EzStr {
+        let resolve = |i: isize| match usize::try_from(i) {
+            Ok(i) => Some(i),
+            Err(_) => self.len().checked_sub(i.unsigned_abs() - 1),
+        };
+        let (Some(from), Some(to)) = (resolve(start), resolve(end)) else {
+            panic!("grapheme range {}..{} out of bounds for length {}", start, end, self.len());
+        };
+        self.slice(from, to)
+    }
+
+    /// Grapheme index for a Python-style signed offset (`-1` is the last grapheme), or
+    /// `None` if it falls outside `0..=len()`.
+    pub fn index_of_offset(&self, offset: isize) -> Option<usize> {
+        GIndex::from(offset).checked_resolve(self.len())
+    }
+
+    /// Slices with Python semantics: `Neg(1)` (or `-1`) is the last grapheme,
+    /// out-of-range indices are clamped and `start >= end` gives an empty string.
+    pub fn slice_py<S: Into<GIndex>, E: Into<GIndex>>(&self, start: S, end: E) -> EzStr {
+        let len = self.len();
+        let start = start.into().resolve(len);
+        let end = end.into().resolve(len);
+        if start >= end {
+            return EzStr::default();
         }
+        self.slice_strict(start, end)
+    }
 
-        for i in start..end {
-            ret += &graphemes[i as usize].value;
+    /// Like `slice_strict`, but returns `Error::OutOfBounds` instead of panicking.
+    pub fn try_slice(&self, start: usize, end: usize) -> Result<EzStr, Error> {
+        match (self.try_grapheme_to_byte(start), self.try_grapheme_to_byte(end)) {
+            (Some(from), Some(to)) if start <= end => Ok(EzStr::from_repr(self.data.slice(from, to))),
+            _ => Err(Error::OutOfBounds { start, end, len: self.len() }),
         }
-        EzStr::new(&ret)
+    }
+
+    /// Slices by unsigned grapheme indices, panicking if `start > end` or `end > len()`.
+    pub fn slice_strict(&self, start: usize, end: usize) -> EzStr {
+        self.try_slice(start, end).unwrap_or_else(|e| panic!("{}", e))
     }
 
     pub fn len(&self) -> usize {
-        self.graphemes().len()
+        self.grapheme_index().len()
+    }
+
+    /// The grapheme at `index`, or `None` past the end; `s[index]` panics instead.
+    pub fn get(&self, index: usize) -> Option<&Grapheme> {
+        self.graphemes().get(index)
+    }
+
+    pub fn first(&self) -> Option<&Grapheme> {
+        self.graphemes().first()
+    }
+
+    pub fn last(&self) -> Option<&Grapheme> {
+        self.graphemes().last()
+    }
+
+    /// Borrowed graphemes, read straight from the byte index without building the
+    /// `Grapheme` cache.
+    pub fn iter(&self) -> impl Iterator<Item = GraphemeRef<'_>> + '_ {
+        let index = self.grapheme_index();
+        let ends = index.iter().skip(1).chain(std::iter::once(self.data.len()));
+        index.iter().zip(ends).map(move |(start, end)| GraphemeRef(&self.data[start..end]))
+    }
+
+    /// Each grapheme with its index.
+    pub fn iter_indices(&self) -> impl Iterator<Item = (usize, GraphemeRef<'_>)> + '_ {
+        self.iter().enumerate()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len() == 0
+        self.data.is_empty()
     }
 
     pub fn contains<T: AsRef<str>>(&self, substring: T) -> bool {
@@ -230,11 +737,7 @@ impl EzStr {
         let data = &self.data;
         regex.find(data).map(|m| {
             let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
-            GraphemeMatch::new(
-                g_start,
-                g_end,
-                self.slice(g_start as i32, g_end as i32),
-            )
+            GraphemeMatch::from_source(self, g_start, g_end)
         })
     }
 
@@ -242,7 +745,7 @@ impl EzStr {
     pub fn findOLD(&self, regex: &Regex) -> Option<GraphemeMatch> {
         regex.find(&self.data).map( |m| {
             let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
-            GraphemeMatch::new(g_start, g_end, self.slice(g_start as i32, g_end as i32))
+            GraphemeMatch::new(g_start, g_end, self.slice_strict(g_start, g_end))
         })
     }
 
@@ -254,13 +757,77 @@ impl EzStr {
         regex: &Regex,
     ) -> impl Iterator<Item=GraphemeMatch> {
         let data = &self.data;
+        // chars are counted incrementally so long scans stay linear
+        let mut counted_bytes = 0;
+        let mut counted_chars = 0;
         regex.find_iter(data).map(move |m| {
             let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
-            GraphemeMatch::new(
-                g_start,
-                g_end,
-                self.slice(g_start as i32, g_end as i32),
-            )
+            let byte_start = self.grapheme_to_byte(g_start);
+            let byte_end = self.grapheme_to_byte(g_end);
+            counted_chars += data[counted_bytes..byte_start].chars().count();
+            counted_bytes = byte_start;
+            let char_start = counted_chars;
+            let char_end = char_start + data[byte_start..byte_end].chars().count();
+            GraphemeMatch {
+                start: g_start,
+                end: g_end,
+                text: EzStr::from_repr(self.data.slice(byte_start, byte_end)),
+                byte_start,
+                byte_end,
+                char_start,
+                char_end,
+            }
+        })
+    }
+
+    /// Like `find_iter` but yields only grapheme spans, without copying match text
+    /// or counting chars.
+    pub fn find_spans_iter<'a>(&'a self, regex: &'a Regex) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+        regex.find_iter(self.as_str()).map(move |m| {
+            let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+            g_start..g_end
+        })
+    }
+
+    /// Returns the last of the matches `find_iter` would yield, without building the others.
+    pub fn rfind(&self, regex: &Regex) -> Option<GraphemeMatch> {
+        regex.find_iter(self.as_str()).last().map(|m| {
+            let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+            GraphemeMatch::from_source(self, g_start, g_end)
+        })
+    }
+
+    /// The matches of `find_iter` in reverse order. Only byte spans are collected up front;
+    /// each match is built as it is reached.
+    pub fn rfind_iter<'a>(&'a self, regex: &Regex) -> impl Iterator<Item = GraphemeMatch> + 'a {
+        let spans: Vec<(usize, usize)> = regex.find_iter(self.as_str()).map(|m| (m.start(), m.end())).collect();
+        spans.into_iter().rev().map(move |(start, end)| {
+            let (g_start, g_end) = self.byte_range_to_grapheme_indices(start, end);
+            GraphemeMatch::from_source(self, g_start, g_end)
+        })
+    }
+
+    /// Grapheme index and text of each non-overlapping occurrence of the literal `pattern`,
+    /// like `str::match_indices`. Occurrences that start or end inside a grapheme are skipped.
+    pub fn match_indices<'a, T: AsRef<str>>(&'a self, pattern: T) -> impl Iterator<Item = (usize, EzStr)> + 'a {
+        let pattern = pattern.as_ref().to_string();
+        let index = self.grapheme_index();
+        let mut from = 0;
+        std::iter::from_fn(move || {
+            if pattern.is_empty() {
+                return None;
+            }
+            while let Some(pos) = self.data[from..].find(pattern.as_str()) {
+                let (start, end) = (from + pos, from + pos + pattern.len());
+                let at_boundary = |byte: usize| byte == self.data.len() || index.search(byte).is_ok();
+                if at_boundary(start) && at_boundary(end) {
+                    from = end;
+                    let g = index.search(start).unwrap();
+                    return Some((g, EzStr::from_repr(self.data.slice(start, end))));
+                }
+                from = start + self.data[start..].chars().next().unwrap().len_utf8();
+            }
+            None
         })
     }
 
@@ -281,11 +848,21 @@ impl Clone for EzStr {
     fn clone(&self) -> EzStr {
         Self {data:self.data.clone(),
             graphemes_data: self.graphemes_data.clone(),
-            grapheme_byte_index_data: self.grapheme_byte_index_data.clone(), }
+            grapheme_index_data: self.grapheme_index_data.clone(),
+            prefix_index_data: Mutex::new(self.prefix_index_data.lock().unwrap().clone()), }
     }
 }
 
 
+impl From<std::borrow::Cow<'static, str>> for EzStr {
+    fn from(item: std::borrow::Cow<'static, str>) -> Self {
+        match item {
+            std::borrow::Cow::Borrowed(s) => EzStr::from_static(s),
+            std::borrow::Cow::Owned(s) => EzStr::new(s),
+        }
+    }
+}
+
 impl From<String> for EzStr {
     fn from(item: String) -> Self {
         EzStr::new(item)
@@ -299,13 +876,13 @@ impl From<&String> for EzStr {
 }
 impl From<&str> for EzStr {
     fn from(item: &str) -> Self {
-        EzStr::new(item)
+        EzStr::from_repr(Repr::small(item))
     }
 }
 
 impl From<char> for EzStr {
     fn from(item: char) -> Self {
-        EzStr::new(item.to_string())
+        EzStr::from_repr(Repr::small(item.encode_utf8(&mut [0; 4])))
     }
 }
 
@@ -328,25 +905,27 @@ impl From<&GraphemeMatch> for EzStr {
 }
 impl Into<String> for EzStr {
     fn into(self) -> String {
-        self.data
+        self.data.into_string()
     }
 }
 
 impl Into<String> for &EzStr {
     fn into(self) -> String {
-        self.data.clone()
+        self.data.to_string()
     }
 }
 
-impl Into<i32> for &EzStr {
-    fn into(self) -> i32 {
-        self.data.parse::<i32>().unwrap()
+impl TryFrom<&EzStr> for i32 {
+    type Error = Error;
+    fn try_from(item: &EzStr) -> Result<i32, Error> {
+        Ok(item.data.parse::<i32>()?)
     }
 }
 
-impl Into<i32> for EzStr {
-    fn into(self) -> i32 {
-        self.data.parse::<i32>().unwrap()
+impl TryFrom<EzStr> for i32 {
+    type Error = Error;
+    fn try_from(item: EzStr) -> Result<i32, Error> {
+        i32::try_from(&item)
     }
 }
 
@@ -363,7 +942,8 @@ impl IntoIterator for EzStr {
     type IntoIter = std::vec::IntoIter<Grapheme>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.graphemes().clone().into_iter()
+        self.graphemes();
+        self.graphemes_data.into_inner().unwrap_or_default().into_iter()
     }
 }
 
@@ -394,7 +974,7 @@ impl Index<std::ops::Range<usize>> for EzStr {
 impl Add<&str> for EzStr {
     type Output = EzStr;
     fn add(self, other: &str) -> EzStr {
-        EzStr::new(&(self.data + other))
+        EzStr::new(self.data.into_string() + other)
     }
 }
 
@@ -402,7 +982,7 @@ impl Add<&str> for EzStr {
 impl Add<&EzStr> for &EzStr {
     type Output = EzStr;
     fn add(self, other: &EzStr) -> EzStr {
-        EzStr::new(&(self.data.clone() + &other.data))
+        EzStr::new(self.data.to_string() + other.as_str())
     }
 }
 
@@ -410,7 +990,7 @@ impl Add<&EzStr> for &EzStr {
 impl Add<&str> for &EzStr {
     type Output = EzStr;
     fn add(self, other: &str) -> EzStr {
-        EzStr::new(&(self.data.clone() + other))
+        EzStr::new(self.data.to_string() + other)
     }
 }
 
@@ -418,7 +998,7 @@ impl Add<&str> for &EzStr {
 impl Add<&String> for &EzStr {
     type Output = EzStr;
     fn add(self, other: &String) -> EzStr {
-        EzStr::new(&(self.data.clone() + other))
+        EzStr::new(self.data.to_string() + other)
     }
 }
 
@@ -428,9 +1008,34 @@ impl Default for EzStr {
     }
 }
 
+/// Precision counts graphemes: `{:.3}` keeps the first three. Width pads to terminal
+/// columns (`display_width`), so wide CJK and emoji line up; with the alternate flag,
+/// as in `{:<#8}`, width counts graphemes instead. Strings align left by default.
 impl fmt::Display for EzStr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.data)
+        if f.width().is_none() && f.precision().is_none() {
+            return f.write_str(self.as_str());
+        }
+        let shown = match f.precision() {
+            Some(p) if p < self.len() => self.slice_strict(0, p),
+            _ => self.clone(),
+        };
+        let measured = if f.alternate() { shown.len() } else { shown.display_width() };
+        let pad = f.width().unwrap_or(0).saturating_sub(measured);
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Right) => (pad, 0),
+            Some(fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+            _ => (0, pad),
+        };
+        let fill = f.fill();
+        for _ in 0..before {
+            f.write_char(fill)?;
+        }
+        f.write_str(shown.as_str())?;
+        for _ in 0..after {
+            f.write_char(fill)?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,142 @@
+use regex::Regex;
+
+use crate::whitespace::NewlineStyle;
+use crate::{EzStr, GSpan, GraphemeMatch};
+
+/// A line from `EzStr::lines_with_terminators`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The line without its terminator.
+    pub content: GraphemeMatch,
+    /// The line with its terminator, if any.
+    pub full: GraphemeMatch,
+    /// How the line ended; `None` for a last line without a terminator.
+    pub ending: Option<NewlineStyle>,
+}
+
+/// A section from `EzStr::split_sections`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub heading: GraphemeMatch,
+    /// From the `level` capture of the heading regex; 1 without one.
+    pub level: usize,
+    /// Text between the heading and the next heading of any level.
+    pub body: GSpan,
+    /// Headed by a higher-level heading (lower `level`) than the next ones, up to the
+    /// next heading of the same or a higher level.
+    pub children: Vec<Section>,
+}
+
+impl Section {
+    /// The whole section: its heading, body and children.
+    pub fn span(&self) -> GSpan {
+        let end = self.children.last().map_or(self.body.end, |child| child.span().end);
+        GSpan::new(self.heading.start, end)
+    }
+}
+
+impl EzStr {
+    /// Splits into lines ending in "\n", "\r\n" or "\r", keeping track of each
+    /// terminator so the text can be rebuilt exactly: concatenating every `full` gives
+    /// back `self`. As with `str::lines`, a terminator at the very end does not start
+    /// another, empty line.
+    pub fn lines_with_terminators(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for (i, g) in self.iter_indices() {
+            let ending = match g.as_str() {
+                "\n" => NewlineStyle::Lf,
+                "\r\n" => NewlineStyle::CrLf,
+                "\r" => NewlineStyle::Cr,
+                _ => continue,
+            };
+            lines.push(Line {
+                content: GraphemeMatch::from_source(self, start, i),
+                full: GraphemeMatch::from_source(self, start, i + 1),
+                ending: Some(ending),
+            });
+            start = i + 1;
+        }
+        if start < self.len() {
+            lines.push(Line {
+                content: GraphemeMatch::from_source(self, start, self.len()),
+                full: GraphemeMatch::from_source(self, start, self.len()),
+                ending: None,
+            });
+        }
+        lines
+    }
+
+    /// Blocks of lines separated by one or more blank (empty or whitespace-only) lines,
+    /// e.g. the stanzas of a song. Each block runs from the start of its first line to
+    /// the end of its last line, without the final terminator.
+    pub fn paragraphs(&self) -> Vec<GraphemeMatch> {
+        let mut paragraphs = Vec::new();
+        let mut current: Option<(usize, usize)> = None;
+        for line in self.lines_with_terminators() {
+            if line.content.text.iter().all(|g| g.is_whitespace()) {
+                if let Some((start, end)) = current.take() {
+                    paragraphs.push(GraphemeMatch::from_source(self, start, end));
+                }
+            } else {
+                let start = current.map_or(line.content.start, |(start, _)| start);
+                current = Some((start, line.content.end));
+            }
+        }
+        if let Some((start, end)) = current {
+            paragraphs.push(GraphemeMatch::from_source(self, start, end));
+        }
+        paragraphs
+    }
+
+    /// Splits the text at each match of `heading` into sections, e.g. with
+    /// `\[(Verse|Chorus)\]` for song markers. If the regex has a `level` capture, its
+    /// number ("h2" gives 2) or otherwise its length in graphemes ("##" gives 2) sets the
+    /// section level, and sections nest under the closest preceding section of a lower
+    /// level. Headings and bodies together cover the text from the first heading on;
+    /// the text before it is `0..sections[0].heading.start`.
+    pub fn split_sections(&self, heading: &Regex) -> Vec<Section> {
+        let mut flat: Vec<Section> = Vec::new();
+        for caps in heading.captures_iter(self.as_str()) {
+            let m = caps.get(0).unwrap();
+            if m.is_empty() {
+                continue;
+            }
+            let (start, end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+            let level = caps.name("level").map_or(1, |level| {
+                let digits: String = level.as_str().chars().filter(|c| c.is_ascii_digit()).collect();
+                digits.parse().unwrap_or_else(|_| EzStr::new(level.as_str()).len())
+            });
+            if let Some(previous) = flat.last_mut() {
+                previous.body.end = start;
+            }
+            flat.push(Section {
+                heading: GraphemeMatch::from_source(self, start, end),
+                level,
+                body: GSpan::new(end, self.len()),
+                children: Vec::new(),
+            });
+        }
+
+        // `open` holds the chain of sections that later ones may still nest under
+        let mut roots: Vec<Section> = Vec::new();
+        let mut open: Vec<Section> = Vec::new();
+        let close = |open: &mut Vec<Section>, roots: &mut Vec<Section>| {
+            let section = open.pop().unwrap();
+            match open.last_mut() {
+                Some(parent) => parent.children.push(section),
+                None => roots.push(section),
+            }
+        };
+        for section in flat {
+            while open.last().is_some_and(|last| last.level >= section.level) {
+                close(&mut open, &mut roots);
+            }
+            open.push(section);
+        }
+        while !open.is_empty() {
+            close(&mut open, &mut roots);
+        }
+        roots
+    }
+}
@@ -0,0 +1,14 @@
+/// Builds an `EzStr` from a string literal without copying it; see `EzStr::from_static`.
+///
+/// The argument must be a string literal, checked at compile time. With no argument
+/// the result is empty.
+#[macro_export]
+macro_rules! ezstr {
+    () => {
+        $crate::EzStr::default()
+    };
+    ($text:literal $(,)?) => {{
+        const TEXT: &'static str = $text;
+        $crate::EzStr::from_static(TEXT)
+    }};
+}
@@ -20,7 +20,7 @@ mod tests {
 [4/4 Pickup]          𝄽  𝄽 𝆔♪  ♪ 𝆔♪  ♪
                      |N.C   A1 C1 A1 G1|");
 
-        assert_eq!(sample.slice(0, -1), sample);
+        assert_eq!(sample.slice_signed(0, -1), sample);
     }
 
     #[test]
@@ -52,4 +52,1949 @@ mod tests {
 
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_accessors_and_constructors() {
+        let sample = EzStr::new("Thé 𝆔♪");
+        assert_eq!(sample.as_str(), "Thé 𝆔♪");
+
+        let rebuilt = EzStr::from_graphemes(sample.graphemes().clone());
+        assert_eq!(rebuilt, sample);
+        assert_eq!(rebuilt.len(), 6);
+
+        let indexed = EzStr::new("abc").indexed();
+        assert_eq!(indexed.into_string(), "abc");
+    }
+
+    #[test]
+    fn test_match_byte_and_char_offsets() {
+        let sample = EzStr::new("Thé ♩≈117BPM");
+        let re = Regex::new(r"\d+").unwrap();
+        let m = sample.find(&re).unwrap();
+        assert_eq!((m.start, m.end), (6, 9));
+        assert_eq!(m.byte_range(), 11..14);
+        assert_eq!(m.char_range(), 6..9);
+        assert_eq!(&sample.as_str()[m.byte_range()], "117");
+
+        let sample = EzStr::new("e\u{301}|é|");
+        let re = Regex::new(r"\|").unwrap();
+        let matches: Vec<_> = sample.find_iter(&re).collect();
+        assert_eq!(matches[1].start, 3);
+        assert_eq!(matches[1].char_start, 4);
+        assert_eq!(matches[1].byte_start, 6);
+        assert_eq!(matches[1], GraphemeMatch::from_source(&sample, 3, 4));
+    }
+
+    #[test]
+    fn test_match_validation() {
+        let sample = EzStr::new("|A|B|");
+        let good = GraphemeMatch::from_source(&sample, 1, 2);
+        assert!(good.validate(&sample).is_ok());
+
+        let bad = GraphemeMatch::new(0, 1, "B");
+        let err = bad.validate(&sample).unwrap_err();
+        assert_eq!(err.actual, EzStr::new("|"));
+        assert_eq!(err.found_at, vec![(3, 4)]);
+        assert!(!bad.is_valid(&sample));
+
+        let past_end = GraphemeMatch::new(4, 9, "|");
+        assert!(past_end.validate(&sample).is_err());
+    }
+
+    #[test]
+    fn test_slice_semantics() {
+        let sample = EzStr::new("Thé𝆔♪");
+        assert_eq!(sample.slice_signed(0, -1), sample);
+        assert_eq!(sample.slice_py(0, -1), EzStr::new("Thé𝆔"));
+        assert_eq!(sample.slice_py(GIndex::Neg(2), GIndex::Pos(5)), EzStr::new("𝆔♪"));
+        assert_eq!(sample.slice_py(-2, 100usize), EzStr::new("𝆔♪"));
+        assert_eq!(sample.slice_py(4, 2), EzStr::default());
+        assert_eq!(sample.slice_strict(1, 3), EzStr::new("hé"));
+        assert!(std::panic::catch_unwind(|| sample.slice_strict(2, 6)).is_err());
+    }
+
+    #[test]
+    fn test_emoji() {
+        let sample = EzStr::new("𝆔♪ 👩‍👩‍👧 👍🏽 🇨🇦 ❤️ ❤ 1️⃣ ©2024");
+        assert_eq!(sample.emoji_count(), 5);
+        let found: Vec<_> = sample.emoji_iter().map(|(i, _)| i).collect();
+        assert_eq!(found, vec![3, 5, 7, 9, 13]);
+        assert_eq!(sample.strip_emoji(), EzStr::new("𝆔♪     ❤  ©2024"));
+        assert!(!emoji::is_emoji(&Grapheme::new("♪")));
+        assert!(emoji::is_emoji(&Grapheme::new("🏳️‍🌈")));
+    }
+
+    #[test]
+    fn test_script_runs() {
+        use ezstr::script::Script;
+
+        assert_eq!(Grapheme::new("é").script(), Script::Latin);
+        assert_eq!(Grapheme::new("e\u{301}").script(), Script::Latin);
+        assert_eq!(Grapheme::new("♪").script(), Script::Common);
+
+        let sample = EzStr::new("« Thé », 夜のカフェ 2024");
+        let runs: Vec<_> = sample
+            .script_runs()
+            .into_iter()
+            .map(|run| (run.script, run.text.to_string()))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                (Script::Latin, "« Thé », ".to_string()),
+                (Script::Han, "夜".to_string()),
+                (Script::Hiragana, "の".to_string()),
+                (Script::Katakana, "カフェ 2024".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "bidi")]
+    #[test]
+    fn test_bidi_visual_order() {
+        let sample = EzStr::new("Chart: שלום (2024)");
+        let levels = sample.bidi_levels();
+        assert_eq!(&levels[..7], &[0; 7]);
+        assert_eq!(levels[7], 1);
+
+        let visual = sample.visual_order();
+        assert_eq!(visual.text, EzStr::new("Chart: (2024) םולש"));
+        assert_eq!(&visual.logical_indices[7..], &[17, 13, 14, 15, 16, 12, 11, 10, 9, 8, 7]);
+
+        let rtl = EzStr::new("שלום abc!");
+        assert_eq!(rtl.visual_order().text, EzStr::new("!abc םולש"));
+    }
+
+    #[test]
+    fn test_grapheme_properties() {
+        let sample = EzStr::new("e\u{301}1 \r\n👩‍👩‍👧1️⃣");
+        let g = sample.graphemes();
+        assert!(g[0].is_alphabetic() && !g[0].is_numeric());
+        assert!(g[1].is_numeric() && !g[1].is_alphabetic());
+        assert!(g[2].is_whitespace() && g[3].is_whitespace());
+        assert!(!g[4].is_alphabetic() && !g[4].is_whitespace());
+        assert!(!g[5].is_numeric());
+        assert!(EzStr::new("\u{301}a")[0].is_combining());
+        assert!(!g[0].is_combining());
+    }
+
+    #[cfg(feature = "translit")]
+    #[test]
+    fn test_transliterate() {
+        let sample = EzStr::new("Édrihan Lévesque ©2024");
+        assert_eq!(sample.to_ascii_lossy(), EzStr::new("Edrihan Levesque (c)2024"));
+
+        let (ascii, map) = EzStr::new("Thé ©♪").transliterate();
+        assert_eq!(ascii, EzStr::new("The (c)?"));
+        assert_eq!(map.source_span(5), Some(4..5));
+        assert_eq!(map.output_span(5), Some(7..8));
+        assert_eq!(map.to_source(2..6), Some(2..5));
+    }
+
+    #[test]
+    fn test_slugify() {
+        use ezstr::slug::SlugOptions;
+
+        let title = EzStr::new("*  Thé - Nicotine Dreams   ♩≈117BPM");
+        let unicode = SlugOptions { transliterate: false, ..Default::default() };
+        assert_eq!(title.slugify(unicode), EzStr::new("thé-nicotine-dreams-117bpm"));
+
+        let short = SlugOptions { max_len: Some(12), separator: "_".into(), transliterate: false, ..Default::default() };
+        assert_eq!(title.slugify(short), EzStr::new("thé_nicotine"));
+
+        let keep_dots = SlugOptions { allowed: ".".into(), lowercase: false, ..Default::default() };
+        assert_eq!(EzStr::new("N.C / A1").slugify(keep_dots), EzStr::new("N.C-A1"));
+    }
+
+    #[cfg(feature = "translit")]
+    #[test]
+    fn test_slugify_transliterated() {
+        let title = EzStr::new("Thé - Nicotine Dreams ©2024");
+        assert_eq!(title.slugify(Default::default()), EzStr::new("the-nicotine-dreams-c-2024"));
+    }
+
+    #[test]
+    fn test_distances() {
+        use ezstr::distance::*;
+
+        let a = EzStr::new("Thé");
+        let b = EzStr::new("The\u{301}");
+        let c = EzStr::new("The");
+        assert_eq!(levenshtein(&a, &c), 1);
+        assert_eq!(levenshtein(&b, &c), 1);
+        assert_eq!(levenshtein(&EzStr::new("kitten"), &EzStr::new("sitting")), 3);
+
+        assert_eq!(damerau_levenshtein(&EzStr::new("ca"), &EzStr::new("abc")), 2);
+        assert_eq!(damerau_levenshtein(&EzStr::new("Tél"), &EzStr::new("Tlé")), 1);
+
+        assert!((jaro_winkler(&EzStr::new("MARTHA"), &EzStr::new("MARHTA")) - 0.9611).abs() < 1e-3);
+        assert_eq!(jaro_winkler(&a, &a), 1.0);
+        assert!((similarity_ratio(&EzStr::new("Nicotine"), &EzStr::new("Nicotina")) - 0.875).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fuzzy_find() {
+        let sample = EzStr::new("|N.C   A1 C1 Am7 G1| Amm7 Am7");
+        let hits = sample.fuzzy_find("Am7", 1);
+        let found: Vec<_> = hits.iter().map(|h| (h.matched.text.to_string(), h.edits)).collect();
+        assert_eq!(
+            found,
+            vec![("Am7".to_string(), 0), ("Amm7".to_string(), 1), ("Am7".to_string(), 0)]
+        );
+        assert_eq!(hits[0].matched.start, 13);
+
+        assert_eq!(EzStr::new("Thé").fuzzy_find("The", 1)[0].edits, 1);
+        assert!(sample.fuzzy_find("Bb9", 1).is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        use ezstr::diff::{diff, unified_diff, DiffKind};
+
+        let old = EzStr::new("|A1 C1 A1 G1|");
+        let new = EzStr::new("|Ä1 C1 G1|");
+        let hunks = diff(&old, &new);
+        let kinds: Vec<_> = hunks.iter().map(|h| (h.kind, h.text.to_string())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffKind::Equal, "|".to_string()),
+                (DiffKind::Delete, "A".to_string()),
+                (DiffKind::Insert, "Ä".to_string()),
+                (DiffKind::Equal, "1 C1 ".to_string()),
+                (DiffKind::Delete, "A1 ".to_string()),
+                (DiffKind::Equal, "G1|".to_string()),
+            ]
+        );
+        assert_eq!(hunks[4].old, 7..10);
+        assert_eq!(hunks[4].new, 7..7);
+
+        let old = EzStr::new("[Verse]\nA1\nC1\nG1\n[Chorus]\nD1\n");
+        let new = EzStr::new("[Verse]\nA1\nC2\nG1\n[Chorus]\nD1\n");
+        assert_eq!(
+            unified_diff(&old, &new, 1),
+            "--- old\n+++ new\n@@ -2,3 +2,3 @@\n A1\n-C1\n+C2\n G1\n"
+        );
+        assert!(diff(&old, &old).iter().all(|h| h.kind == DiffKind::Equal));
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        use ezstr::diff::{diff, DiffKind, PatchError};
+
+        let old = EzStr::new("|A1 C1 A1 G1|");
+        let new = EzStr::new("|Ä1 C1 G1|");
+        let hunks = diff(&old, &new);
+        assert_eq!(old.apply_patch(&hunks), Ok(new.clone()));
+
+        // only the changes plus a little context, applied to a copy edited elsewhere
+        let small: Vec<_> = hunks.iter().filter(|h| h.kind != DiffKind::Equal || h.text.len() < 4).cloned().collect();
+        let edited = EzStr::new("[Verse] |A1 C1 A1 G1|");
+        assert_eq!(edited.apply_patch(&small), Ok(EzStr::new("[Verse] |Ä1 C1 G1|")));
+
+        let unrelated = EzStr::new("|B1|");
+        assert!(matches!(unrelated.apply_patch(&hunks), Err(PatchError::DeletedTextNotFound { hunk: 1, .. })));
+    }
+
+    #[test]
+    fn test_longest_common() {
+        let a = EzStr::new("Thé Nicotine Dreams");
+        let b = EzStr::new("Nicotine Dream (live)");
+        let (in_a, in_b) = a.longest_common_substring(&b).unwrap();
+        assert_eq!(in_a.text, EzStr::new("Nicotine Dream"));
+        assert_eq!((in_a.start, in_b.start), (4, 0));
+        assert!(a.longest_common_substring(&EzStr::new("xyz")).is_none());
+
+        let pairs = EzStr::new("A1 C1 G1").longest_common_subsequence(&EzStr::new("A1 Cm G1"));
+        let spans: Vec<_> = pairs.iter().map(|(x, y)| (x.start, x.end, y.start, y.text.to_string())).collect();
+        assert_eq!(spans, vec![(0, 4, 0, "A1 C".to_string()), (5, 8, 5, " G1".to_string())]);
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(EzStr::new("Thé").display_width(), 3);
+        assert_eq!(EzStr::new("e\u{301}").display_width(), 1);
+        assert_eq!(EzStr::new("夜のカフェ").display_width(), 10);
+        assert_eq!(EzStr::new("👩‍👩‍👧♪").display_width(), 3);
+    }
+
+    #[test]
+    fn test_text_grid() {
+        use ezstr::table::TextGrid;
+
+        let sample = EzStr::new("[4/4 Pickup]\n   |N.C | A1 C1|夜|\n|E|F|G|H|");
+        let grid = TextGrid::parse(&sample);
+        assert_eq!(grid.rows.len(), 2);
+        assert_eq!(grid.rows[0].line, 1);
+        let first = &grid.rows[0].cells;
+        assert_eq!(first[1].text, EzStr::new("A1 C1"));
+        assert_eq!(sample.slice_strict(first[1].start, first[1].end), first[1].text);
+        assert_eq!(grid.column_count(), 4);
+        assert_eq!(grid.column_widths(), vec![3, 5, 2, 1]);
+        assert_eq!(
+            grid.render(),
+            EzStr::new("| N.C | A1 C1 | 夜 |\n| E   | F     | G  | H |")
+        );
+    }
+
+    #[test]
+    fn test_highlight_render() {
+        let sample = EzStr::new("[4/4 Pickup]\n|N.C\tA1 C1 夜|\nlast");
+        let re = Regex::new("A1 C1").unwrap();
+        let chords = sample.find(&re).unwrap();
+        let night = GraphemeMatch::from_source(&sample, 24, 25);
+        let across = GraphemeMatch::from_source(&sample, 9, 16);
+        let rendered = ezstr::highlight::render(&sample, &[(chords, "chords"), (night, ""), (across, "spans lines")]);
+        assert_eq!(
+            rendered,
+            "1 | [4/4 Pickup]\n  |          ^^^\n\
+             2 | |N.C\tA1 C1 夜|\n  | ^^^ spans lines\n  |     \t^^^^^ chords\n  |     \t      ^^\n"
+        );
+    }
+
+    #[test]
+    fn test_ansi_styles() {
+        use ezstr::ansi::{Color, Style};
+
+        let sample = EzStr::new("|Am7 夜|");
+        let styled = sample.style_spans(&[(1..4, Style::new().fg(Color::Red).bold()), (5..6, Style::new().bg(Color::Fixed(8)))]);
+        assert_eq!(styled.as_str(), "|\x1b[1;31mAm7\x1b[0m \x1b[48;5;8m夜\x1b[0m|");
+        assert_eq!(styled.strip_ansi(), sample);
+        assert_eq!(styled.display_width_ansi(), 8);
+        assert_eq!(EzStr::new("\x1b]8;;http://x\x1b\\link\x1b]8;;\x07").display_width_ansi(), 4);
+        assert_eq!(EzStr::new("a\u{1b}é").strip_ansi(), EzStr::new("a"));
+        assert_eq!(EzStr::new("\u{1b}日本").display_width_ansi(), 2);
+    }
+
+    #[test]
+    fn test_template_render() {
+        use ezstr::template::TemplateError;
+        use std::collections::HashMap;
+
+        let template = EzStr::new("*  {title}   ♩≈{bpm}BPM  {{page}}");
+        let mut values = HashMap::new();
+        values.insert("title", EzStr::new("Thé - Nicotine Dreams"));
+        values.insert("bpm", EzStr::new("117"));
+        let rendered = template.render(&values).unwrap();
+        assert_eq!(rendered.text, EzStr::new("*  Thé - Nicotine Dreams   ♩≈117BPM  {page}"));
+        assert_eq!(rendered.substitutions, vec![("title".to_string(), GSpan::new(3, 24)), ("bpm".to_string(), GSpan::new(29, 32))]);
+        assert_eq!(rendered.text.slice_strict(29, 32), EzStr::new("117"));
+
+        assert_eq!(
+            EzStr::new("é {composer}").render(&values),
+            Err(TemplateError::MissingValue { name: "composer".to_string(), at: 2 })
+        );
+        assert_eq!(EzStr::new("{title").render(&values), Err(TemplateError::UnclosedPlaceholder { at: 0 }));
+    }
+
+    #[test]
+    fn test_case_folded_search() {
+        let sample = EzStr::new("Thé Straße · THÉ STRASSE");
+        assert!(sample.contains_fold("straSSe"));
+        assert!(!sample.contains_fold("strasses"));
+
+        let hits = sample.find_iter_fold("strasse");
+        let spans: Vec<_> = hits.iter().map(|m| (m.start, m.end, m.text.to_string())).collect();
+        assert_eq!(spans, vec![(4, 10, "Straße".to_string()), (17, 24, "STRASSE".to_string())]);
+
+        assert_eq!(sample.find_fold("thé").unwrap().end, 3);
+        assert!(EzStr::new("ß").find_fold("s").is_none());
+    }
+
+    #[test]
+    fn test_find_with_context() {
+        let sample = EzStr::new("[Verse]\nNicotine dreams\nof you\n[Chorus]\nThé");
+        let re = Regex::new("dreams").unwrap();
+
+        let hits = sample.find_with_context(&re, 1, 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].before.text, EzStr::new("[Verse]\nNicotine "));
+        assert_eq!(hits[0].after.text, EzStr::new("\nof you"));
+
+        let hits = sample.find_with_context(&re, 0, 0);
+        assert_eq!(hits[0].before.text, EzStr::new("Nicotine "));
+        assert_eq!(hits[0].after.text, EzStr::default());
+
+        let re = Regex::new("Thé").unwrap();
+        let hits = sample.find_with_grapheme_context(&re, 4, 4);
+        assert_eq!(hits[0].before.text, EzStr::new("us]\n"));
+        assert_eq!(hits[0].after.end, sample.len());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi_roundtrip() {
+        use ezstr::ffi::*;
+        use std::ptr;
+
+        let text = "N.C|🎸 Amm7|夜";
+        unsafe {
+            let mut s = ptr::null_mut();
+            assert_eq!(ezstr_new(text.as_ptr(), text.len(), &mut s), EzStrStatus::Ok);
+            let mut len = 0;
+            assert_eq!(ezstr_len(s, &mut len), EzStrStatus::Ok);
+            assert_eq!(len, 12);
+
+            let (mut start, mut end) = (0, 0);
+            let pattern = "A\\w+";
+            assert_eq!(ezstr_find(s, pattern.as_ptr(), pattern.len(), &mut start, &mut end), EzStrStatus::Ok);
+            assert_eq!((start, end), (6, 10));
+            assert_eq!(ezstr_find(s, "(".as_ptr(), 1, &mut start, &mut end), EzStrStatus::InvalidRegex);
+
+            let mut sliced = ptr::null_mut();
+            assert_eq!(ezstr_slice(s, start, end, &mut sliced), EzStrStatus::Ok);
+            let (mut data, mut data_len) = (ptr::null(), 0);
+            assert_eq!(ezstr_as_utf8(sliced, &mut data, &mut data_len), EzStrStatus::Ok);
+            assert_eq!(std::slice::from_raw_parts(data, data_len), b"Amm7");
+            assert_eq!(ezstr_slice(s, 3, 99, &mut sliced), EzStrStatus::OutOfBounds);
+
+            assert_eq!(ezstr_new([0xff].as_ptr(), 1, &mut sliced), EzStrStatus::InvalidUtf8);
+            ezstr_free(sliced);
+            ezstr_free(s);
+        }
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn test_archive_roundtrip() {
+        use ezstr::archive::ArchiveError;
+
+        let sample = EzStr::new("[Verse]\n🎸 Amm7 夜\nnoël");
+        let bytes = sample.to_archive();
+        let loaded = EzStr::from_archive(&bytes).unwrap();
+        assert_eq!(loaded, sample);
+        assert_eq!(loaded.len(), sample.len());
+        assert_eq!(loaded.graphemes(), sample.graphemes());
+        assert_eq!(loaded.slice_strict(8, 14), EzStr::new("🎸 Amm7"));
+
+        let archived = ezstr::archive::ArchivedEzStr::new(&bytes).unwrap();
+        assert_eq!(archived.len(), sample.len());
+        assert_eq!(archived.slice(8, 14), "🎸 Amm7");
+        assert_eq!(archived.get(8), Some("🎸"));
+        assert_eq!(archived.find(&Regex::new("夜").unwrap()).unwrap(), sample.find(&Regex::new("夜").unwrap()).unwrap());
+        assert!(std::ptr::eq(archived.as_str().as_ptr(), bytes[12..].as_ptr()));
+
+        assert_eq!(EzStr::from_archive(&bytes[..bytes.len() - 3]), Err(ArchiveError::Truncated));
+        assert_eq!(EzStr::from_archive(b"nope"), Err(ArchiveError::BadMagic));
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 8;
+        corrupt[last..].copy_from_slice(&1u64.to_le_bytes());
+        assert_eq!(EzStr::from_archive(&corrupt), Err(ArchiveError::InvalidIndex));
+    }
+
+    #[cfg(feature = "columnar")]
+    #[test]
+    fn test_ezstr_array() {
+        use ezstr::columnar::{ColumnarError, EzStrArray};
+
+        let column: EzStrArray = ["🎸 Amm7", "noël", "", "夜夜夜"].into_iter().collect();
+        assert_eq!(column.len(), 4);
+        assert_eq!(column.grapheme_len(0), 6);
+        assert_eq!(column.slice(1, -2, 4), "ël");
+        assert_eq!(column.find(0, &Regex::new("m+").unwrap()), Some(3..5));
+
+        let heads = column.slice_all(0, 2);
+        assert_eq!(heads.iter().collect::<Vec<_>>(), vec!["🎸 ", "no", "", "夜夜"]);
+
+        let (values, offsets) = heads.into_parts();
+        assert_eq!(offsets, vec![0, 5, 7, 7, 13]);
+        assert!(EzStrArray::from_parts(values.clone(), offsets).is_ok());
+        assert_eq!(EzStrArray::from_parts(values.clone(), vec![0, 2, 1]).unwrap_err(), ColumnarError::InvalidOffsets);
+        assert_eq!(EzStrArray::from_parts(values, vec![0, 5, 8]).unwrap_err(), ColumnarError::InvalidUtf8 { element: 1 });
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn test_from_mmap() {
+        let path = std::env::temp_dir().join(format!("ezstr-mmap-{}.txt", std::process::id()));
+        std::fs::write(&path, "[Verse]\n🇫🇷🇩🇪 Amm7 noël\n夜").unwrap();
+
+        // the file is ours and nothing else writes to it while mapped
+        let mapped = unsafe { EzStr::from_mmap(&path) }.unwrap();
+        assert_eq!(mapped.slice(8, 10), "🇫🇷🇩🇪");
+        let found = mapped.find(&Regex::new("no.l").unwrap()).unwrap();
+        assert_eq!((found.start, found.end), (16, 20));
+        assert_eq!(found.text, EzStr::new("noël"));
+        assert_eq!(mapped.len(), EzStr::new(mapped.as_str()).len());
+        drop(mapped);
+
+        std::fs::write(&path, [0x41, 0xff]).unwrap();
+        assert_eq!(unsafe { EzStr::from_mmap(&path) }.err().unwrap().kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_grapheme_inline_storage() {
+        assert_eq!(std::mem::size_of::<Grapheme>(), 24);
+        let sample = EzStr::new("A夜👨‍👩‍👧‍👦Z̤͔ͧ̑̓ä͖̭̈̇lͮ̒ͫǧ̗͚̚o̙̔ͮ̇͐̇");
+        let graphemes = sample.graphemes();
+        assert!(graphemes[..2].iter().all(|g| !g.value.is_spilled()));
+        assert_eq!(graphemes[2].value, "👨‍👩‍👧‍👦");
+        assert!(graphemes[2].value.is_spilled());
+        assert_eq!(graphemes.iter().map(|g| g.as_str()).collect::<String>(), sample.as_str());
+        assert_eq!(format!("{:?}", graphemes[1]), "Grapheme { value: \"夜\" }");
+    }
+
+    #[test]
+    fn test_compact_grapheme_index() {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let mut text = "🎸 Amm7 noël 夜\n".repeat(100);
+        text += &format!("e{}", "\u{301}".repeat(40_000));
+        text += &"x".repeat(300);
+        let sample = EzStr::new(text.as_str());
+        let expected: Vec<usize> = text.grapheme_indices(true).map(|(b, _)| b).collect();
+
+        let index = sample.grapheme_index();
+        assert_eq!(index.len(), expected.len());
+        assert_eq!(index.iter().collect::<Vec<_>>(), expected);
+        assert!(index.heap_size() < expected.len() * 4);
+        for (g, &b) in expected.iter().enumerate() {
+            assert_eq!(index.search(b), Ok(g));
+        }
+        assert_eq!(index.search(expected[0] + 1), Err(1));
+        assert_eq!(index.search(text.len()), Err(expected.len()));
+        assert_eq!(sample.slice_strict(1399, 1402).as_str(), format!("\ne{}x", "\u{301}".repeat(40_000)));
+    }
+
+    #[test]
+    fn test_lazy_prefix_indexing() {
+        let sample = EzStr::new("🇫🇷🇩🇪 Amm7 noël\n".repeat(10_000));
+        assert_eq!(sample.slice(0, 3), EzStr::new("🇫🇷🇩🇪 "));
+        assert_eq!(sample.slice_strict(3, 7), EzStr::new("Amm7"));
+        assert_eq!(sample.slice_signed(-3, -1), EzStr::new("l\n"));
+        assert_eq!(sample.slice(5, 2), EzStr::default());
+        assert_eq!(sample.clone().slice_strict(8, 12), EzStr::new("noël"));
+
+        sample.index_all();
+        assert_eq!(sample.len(), 130_000);
+        assert_eq!(sample.slice_strict(129_996, 130_000), EzStr::new("oël\n"));
+        assert!(std::panic::catch_unwind(|| EzStr::new("ab").slice_strict(1, 3)).is_err());
+    }
+
+    #[test]
+    fn test_ascii_fast_path_segmentation() {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let samples = [
+            "plain ascii text, long enough to cover several words at once",
+            "a\r\nb\r\r\n\n\r",
+            "noe\u{308}l cafe\u{301}",
+            "\u{600}1 and \u{600}",
+            "flags 🇫🇷🇩🇪🇺 end",
+            "family 👨‍👩‍👧 a\u{200d}b a\u{903}",
+            "夜 mixed 夜夜 ASCII 夜",
+            "ends in e\u{301}",
+            "",
+        ];
+        for text in samples {
+            let sample = EzStr::new(text);
+            let expected: Vec<usize> = text.grapheme_indices(true).map(|(b, _)| b).collect();
+            assert_eq!(sample.grapheme_index().iter().collect::<Vec<_>>(), expected, "{:?}", text);
+            let graphemes: Vec<&str> = sample.graphemes().iter().map(|g| g.as_str()).collect();
+            assert_eq!(graphemes, text.graphemes(true).collect::<Vec<_>>());
+            let lazy = EzStr::new(text);
+            let n = expected.len();
+            assert_eq!(lazy.slice_strict(n / 2, n), sample.slice_py(n / 2, n));
+        }
+    }
+
+    #[test]
+    fn test_static_storage() {
+        use std::borrow::Cow;
+
+        let sample = EzStr::from_static("[Verse] Amm7 noël");
+        assert!(sample.is_static());
+        let chord = sample.slice_strict(8, 12);
+        assert!(chord.is_static());
+        assert_eq!(chord, EzStr::new("Amm7"));
+        assert!(sample.find(&Regex::new("no.l").unwrap()).unwrap().text.is_static());
+        assert!(!EzStr::new("Amm7").is_static());
+
+        assert!(EzStr::from(Cow::Borrowed("夜")).is_static());
+        assert!(!EzStr::from(Cow::<'static, str>::Owned("夜".to_string())).is_static());
+        assert_eq!((sample.clone() + "!").as_str(), "[Verse] Amm7 noël!");
+        assert_eq!(sample.into_string(), "[Verse] Amm7 noël");
+    }
+
+    #[test]
+    fn test_small_string_storage() {
+        let sample = EzStr::new("Nicotine dreams of you, Amm7 noël");
+        assert!(!sample.is_inline());
+        let chord = sample.slice_strict(24, 28);
+        assert!(chord.is_inline());
+        assert_eq!(chord.as_str(), "Amm7");
+        assert!(EzStr::from("夜").is_inline());
+        assert!(EzStr::from('夜').is_inline());
+        assert!(EzStr::new("exactly twenty-two b!!").is_inline());
+        assert!(!EzStr::new("twenty-three bytes long").is_inline());
+        assert_eq!(chord.clone() + "!", EzStr::new("Amm7!"));
+    }
+
+    #[cfg(feature = "bench")]
+    #[test]
+    fn test_bench_corpus() {
+        use ezstr::bench::{check_regression, measure, Corpus};
+        use std::time::Duration;
+
+        let corpora = Corpus::all(4096);
+        assert_eq!(corpora.len(), 5);
+        for corpus in &corpora {
+            assert!(corpus.text.as_str().len() >= 4096, "{}", corpus.name);
+        }
+        assert!(Corpus::emoji(1).text.emoji_count() > 0);
+
+        let elapsed = measure(3, || corpora[0].text.clone().indexed().len());
+        assert!(elapsed < Duration::from_secs(5));
+        let ms = Duration::from_millis;
+        assert!(check_regression("index", ms(100), ms(105), 1.10).is_ok());
+        assert_eq!(check_regression("index", ms(100), ms(120), 1.10).unwrap_err().current, ms(120));
+
+        let path = std::env::temp_dir().join(format!("ezstr-baseline-{}.txt", std::process::id()));
+        let mut baseline = ezstr::bench::Baseline::new();
+        baseline.insert("english/index", ms(100));
+        baseline.save(&path).unwrap();
+        let loaded = ezstr::bench::Baseline::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, baseline);
+        assert!(loaded.check("english/index", ms(120), 1.10).is_err());
+        assert!(loaded.check("cjk/index", ms(120), 1.10).is_ok());
+        assert!(ezstr::bench::Baseline::load(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_spans_iter() {
+        let sample = EzStr::new("🎸 Amm7 | G1 | noël Amm7");
+        let re = Regex::new(r"Amm7|no.l").unwrap();
+        let spans: Vec<_> = sample.find_spans_iter(&re).collect();
+        assert_eq!(spans, vec![2..6, 14..18, 19..23]);
+        let full: Vec<_> = sample.find_iter(&re).map(|m| m.start..m.end).collect();
+        assert_eq!(spans, full);
+    }
+
+    #[test]
+    fn test_rfind() {
+        let sample = EzStr::new("| 🎸 Amm7 | G1 | 夜 |");
+        let bar = Regex::new(r"\|").unwrap();
+        let last = sample.rfind(&bar).unwrap();
+        assert_eq!((last.start, last.end), (18, 19));
+        assert_eq!(sample.rfind(&Regex::new("x").unwrap()), None);
+
+        let starts: Vec<usize> = sample.rfind_iter(&bar).map(|m| m.start).collect();
+        assert_eq!(starts, vec![18, 14, 9, 0]);
+        let mut forward: Vec<_> = sample.find_iter(&bar).collect();
+        forward.reverse();
+        assert_eq!(sample.rfind_iter(&bar).collect::<Vec<_>>(), forward);
+    }
+
+    #[test]
+    fn test_match_indices() {
+        let sample = EzStr::new("noe\u{308}l | 🎸 e | Amm7 | e");
+        let found: Vec<(usize, EzStr)> = sample.match_indices("e").collect();
+        assert_eq!(found, vec![(9, EzStr::new("e")), (20, EzStr::new("e"))]);
+        let bars: Vec<usize> = sample.match_indices(" | ").map(|(i, _)| i).collect();
+        assert_eq!(bars, vec![4, 10, 17]);
+        assert_eq!(sample.match_indices("").count(), 0);
+    }
+
+    #[test]
+    fn test_bounded_split() {
+        let header = EzStr::new("By: Édrihan Lévesque / Alin Rogoz");
+        let (key, value) = header.split_once(": ").unwrap();
+        assert_eq!(key.text, EzStr::new("By"));
+        assert_eq!((value.start, value.end), (4, 33));
+
+        let (authors, last) = header.rsplit_once(" / ").unwrap();
+        assert_eq!(authors.text, EzStr::new("By: Édrihan Lévesque"));
+        assert_eq!(last.text, EzStr::new("Alin Rogoz"));
+        assert!(header.split_once('|').is_none());
+
+        let row = EzStr::new("夜|🎸 Amm7|G1|D");
+        let bar = Regex::new(r"\|").unwrap();
+        let texts = |parts: Vec<GraphemeMatch>| parts.into_iter().map(|p| p.text.into_string()).collect::<Vec<_>>();
+        assert_eq!(texts(row.splitn(3, &bar)), vec!["夜", "🎸 Amm7", "G1|D"]);
+        assert_eq!(texts(row.rsplitn(2, '|')), vec!["D", "夜|🎸 Amm7|G1"]);
+        assert_eq!(texts(row.splitn(1, "|")), vec!["夜|🎸 Amm7|G1|D"]);
+        assert!(row.splitn(0, "|").is_empty());
+        // a delimiter inside a grapheme does not split it
+        assert!(EzStr::new("noe\u{308}l").split_once("e").is_none());
+    }
+
+    #[test]
+    fn test_chunks_and_windows() {
+        let sample = EzStr::new("noe\u{308}l🎸夜");
+        let chunks: Vec<GraphemeMatch> = sample.chunks(2).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].text, EzStr::new("e\u{308}l"));
+        assert_eq!((chunks[1].start, chunks[1].char_start, chunks[1].char_end), (2, 2, 5));
+        assert_eq!(chunks[2].text, EzStr::new("🎸夜"));
+
+        let windows: Vec<String> = sample.windows(3).map(|w| w.text.into_string()).collect();
+        assert_eq!(windows, vec!["noe\u{308}", "oe\u{308}l", "e\u{308}l🎸", "l🎸夜"]);
+        for w in sample.windows(2) {
+            assert_eq!(w, GraphemeMatch::from_source(&sample, w.start, w.end));
+        }
+        assert_eq!(sample.windows(7).count(), 0);
+    }
+
+    #[test]
+    fn test_ngrams_and_shingles() {
+        let sample = EzStr::new("🎸 夜 noël");
+        let grams: Vec<EzStr> = sample.ngrams(2).collect();
+        assert_eq!(grams.len(), sample.len() - 1);
+        assert_eq!(grams[2], EzStr::new("夜 "));
+
+        let hashes = sample.shingle_hashes(3);
+        assert_eq!(hashes.len(), sample.len() - 2);
+        assert_eq!(hashes, EzStr::new("🎸 夜 noël").shingle_hashes(3));
+        // pinned so the hashes stay stable across releases
+        assert_eq!(EzStr::new("a").shingle_hashes(1), vec![0x089b_c907_b544_c769u64]);
+        assert!(EzStr::new("ab").shingle_hashes(3).is_empty());
+    }
+
+    #[test]
+    fn test_ezstr_builder() {
+        use ezstr::builder::EzStrBuilder;
+        use std::fmt::Write;
+
+        let mut builder = EzStrBuilder::with_capacity(64, 8);
+        builder.push("[Verse] ").push(EzStr::new("🎸 Amm7")).push("");
+        builder.push(" noe").push("\u{308}").push("l");
+        write!(builder, " {}", 7).unwrap();
+        builder.push(&Grapheme::new("夜"));
+        let (text, spans) = builder.build_with_spans();
+
+        assert_eq!(text.as_str(), "[Verse] 🎸 Amm7 noe\u{308}l 7夜");
+        assert_eq!(spans[0], 0..8);
+        assert_eq!(text.slice_strict(spans[1].start, spans[1].end), EzStr::new("🎸 Amm7"));
+        assert_eq!(spans[2], 14..14);
+        // the pushed accent merged into the "e" grapheme
+        assert_eq!(spans[3], 14..18);
+        assert_eq!(spans[4], 17..18);
+        assert_eq!(spans[6], 19..21);
+        assert_eq!(spans[7], 21..22);
+        assert_eq!(text.len(), 22);
+    }
+
+    #[test]
+    fn test_ezstr_macro() {
+        let chord = ezstr::ezstr!("Amm7 🎸");
+        assert!(chord.is_static());
+        assert_eq!(chord, EzStr::new("Amm7 🎸"));
+        assert_eq!(chord.len(), 6);
+        assert!(ezstr::ezstr!().is_empty());
+    }
+
+    #[test]
+    fn test_static_globals() {
+        static BAR: EzStr = EzStr::from_static(" | ");
+        static HEADER: EzStr = ezstr::ezstr!("夜 | 🎸 Amm7 | noël");
+
+        let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(|| HEADER.len())).collect();
+        for h in handles {
+            assert_eq!(h.join().unwrap(), 17);
+        }
+        let parts: Vec<usize> = HEADER.match_indices(BAR.as_str()).map(|(i, _)| i).collect();
+        assert_eq!(parts, vec![1, 10]);
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let night = EzStr::new("夜");
+        let chord = EzStr::new("🎸ab");
+        let word = EzStr::new("noe\u{308}l");
+        assert_eq!(format!("{:>4}|", night), "  夜|");
+        assert_eq!(format!("{:^8}|", chord), "  🎸ab  |");
+        assert_eq!(format!("{:.3}|", word), "noe\u{308}|");
+        assert_eq!(format!("{:-<6.2}|", word), "no----|");
+        assert_eq!(format!("{:<#4}|", EzStr::new("夜夜")), "夜夜  |");
+        assert_eq!(format!("{:4}|", word), "noe\u{308}l|");
+        assert_eq!(format!("{}", chord), "🎸ab");
+    }
+
+    #[test]
+    fn test_slice_view() {
+        let sample = EzStr::new("夜 | 🎸 Amm7 | noël Amm7");
+        let view = sample.slice_view(4..10);
+        assert_eq!(view, "🎸 Amm7");
+        assert_eq!(view.len(), 6);
+        assert_eq!(view.display_width(), 7);
+
+        let m = view.find(&Regex::new("m+").unwrap()).unwrap();
+        assert_eq!((m.start, m.end, m.byte_start, m.char_start), (3, 5, 6, 3));
+        assert_eq!(m.text, EzStr::new("mm"));
+        assert_eq!(view.find_iter(&Regex::new("Amm7").unwrap()).count(), 1);
+
+        let inner = view.slice_view(2..4);
+        assert_eq!(inner.as_str(), "Am");
+        assert_eq!(inner.range(), 6..8);
+        assert_eq!(inner.to_ezstr(), EzStr::new("Am"));
+        assert!(std::panic::catch_unwind(|| sample.slice_view(3..99)).is_err());
+    }
+
+    #[test]
+    fn test_iteration() {
+        let sample = EzStr::new("🎸 noe\u{308}l");
+        assert_eq!(sample.iter().count(), 6);
+        let (i, g) = sample.iter_indices().find(|(_, g)| g.is_combining() || g.as_str().chars().count() > 1).unwrap();
+        assert_eq!((i, g.as_str()), (4, "e\u{308}"));
+
+        let owned: Vec<Grapheme> = sample.clone().into_iter().collect();
+        assert_eq!(&owned, sample.graphemes());
+        assert_eq!(EzStr::default().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_grapheme_ref() {
+        let sample = EzStr::new("夜 7🎸e\u{301}");
+        let refs: Vec<GraphemeRef> = sample.iter().collect();
+        assert_eq!(refs.len(), 5);
+        assert_eq!(refs[0].display_width(), 2);
+        assert!(refs[1].is_whitespace());
+        assert!(refs[2].is_numeric());
+        assert!(ezstr::emoji::is_emoji(refs[3]));
+        assert!(refs[4].is_alphabetic());
+        assert_eq!(refs[4].script(), ezstr::script::Script::Latin);
+        assert_eq!(refs[4], sample.graphemes()[4]);
+        assert_eq!(refs[4].to_grapheme().as_grapheme_ref(), refs[4]);
+    }
+
+    #[test]
+    fn test_checked_access() {
+        let sample = EzStr::new("🎸 Amm7 夜");
+        assert_eq!(sample.get(2).map(|g| g.as_str()), Some("A"));
+        assert_eq!(sample.get(8), None);
+        assert_eq!(sample.first().unwrap().as_str(), "🎸");
+        assert_eq!(sample.last().unwrap().as_str(), "夜");
+        assert!(EzStr::default().first().is_none());
+
+        assert_eq!(sample.get_range(2..6).unwrap(), "Amm7");
+        assert_eq!(sample.get_range(8..8).unwrap(), "");
+        assert!(sample.get_range(6..9).is_none());
+        let (start, end) = (5, 3);
+        assert!(sample.get_range(start..end).is_none());
+    }
+
+    #[test]
+    fn test_error_and_try_from() {
+        assert_eq!(i32::try_from(EzStr::new("117")), Ok(117));
+        assert_eq!(i32::try_from(&EzStr::new("-4")), Ok(-4));
+        assert!(matches!(i32::try_from(&EzStr::new(" -4 ")), Err(ezstr::Error::ParseInt(_))));
+        assert!(matches!(i32::try_from(EzStr::new("♩≈117")), Err(ezstr::Error::ParseInt(_))));
+
+        let sample = EzStr::new("🎸 Amm7");
+        assert_eq!(sample.try_slice(2, 6), Ok(EzStr::new("Amm7")));
+        let err = sample.try_slice(4, 9).unwrap_err();
+        assert_eq!(err, ezstr::Error::OutOfBounds { start: 4, end: 9, len: 6 });
+        assert_eq!(err.to_string(), "grapheme range 4..9 out of bounds for length 6");
+        let unclosed = String::from("(");
+        let err: ezstr::Error = Regex::new(&unclosed).unwrap_err().into();
+        assert!(matches!(err, ezstr::Error::InvalidPattern(_)));
+
+        let render = || -> Result<EzStr, ezstr::Error> {
+            let values = std::collections::HashMap::new();
+            Ok(EzStr::new("{title").render(&values)?.text)
+        };
+        assert!(matches!(render(), Err(ezstr::Error::Template(_))));
+
+        let check = || -> Result<(), ezstr::Error> {
+            GraphemeMatch::new(0, 1, "B").validate(&EzStr::new("A1"))?;
+            Ok(())
+        };
+        assert!(matches!(check(), Err(ezstr::Error::Validation(_))));
+        let read = || -> Result<String, ezstr::Error> { Ok(std::fs::read_to_string("/nonexistent/ezstr.txt")?) };
+        assert!(matches!(read(), Err(ezstr::Error::Io { kind: std::io::ErrorKind::NotFound, .. })));
+    }
+
+    #[cfg(all(feature = "bench", feature = "csv"))]
+    #[test]
+    fn test_error_from_feature_errors() {
+        use ezstr::bench::check_regression;
+        use ezstr::csv::Dialect;
+        use std::time::Duration;
+
+        let regressed = || -> Result<(), ezstr::Error> {
+            check_regression("slice", Duration::from_millis(10), Duration::from_millis(20), 1.25)?;
+            Ok(())
+        };
+        assert!(matches!(regressed(), Err(ezstr::Error::Regression(_))));
+        let parse = || -> Result<usize, ezstr::Error> { Ok(EzStr::new("a,\"b").parse_csv(Dialect::CSV)?.len()) };
+        assert!(matches!(parse(), Err(ezstr::Error::Csv(_))));
+    }
+
+    #[test]
+    fn test_parse_and_extract_numbers() {
+        assert_eq!(EzStr::new("117").parse::<u32>(), Ok(117));
+        assert_eq!(EzStr::new("1.5").parse::<f64>(), Ok(1.5));
+        assert!(matches!(EzStr::new("x").parse::<i64>(), Err(ezstr::Error::ParseInt(_))));
+        assert!(matches!(EzStr::new("x").parse::<f32>(), Err(ezstr::Error::ParseFloat(_))));
+        assert!(matches!(EzStr::new("xy").parse::<char>(), Err(ezstr::Error::Parse(_))));
+
+        let header = EzStr::new("Thé ♩≈117BPM page 1/2 -3 dB 1-2 ×0.75");
+        let numbers = header.extract_numbers();
+        let values: Vec<f64> = numbers.iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![117.0, 1.0, 2.0, -3.0, 1.0, 2.0, 0.75]);
+        assert_eq!((numbers[0].matched.start, numbers[0].matched.end), (6, 9));
+        assert_eq!(numbers[3].integer, Some(-3));
+        assert_eq!(numbers[6].integer, None);
+    }
+
+    #[test]
+    fn test_parse_kv_lines() {
+        let header = EzStr::new(
+            "*  Thé - Nicotine Dreams   ♩≈117BPM   page 1/2
+ By: Édrihan Lévesque   /   Alin Rogoz  ©2024*
+Key: A
+Key: B");
+        let kv = header.parse_kv_lines(": ");
+        let keys: Vec<&str> = kv.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["By", "©", "Key", "Key"]);
+        assert_eq!(kv.get("By"), Some(&EzStr::new("Édrihan Lévesque   /   Alin Rogoz")));
+        assert_eq!(kv.get("©"), Some(&EzStr::new("2024")));
+        assert_eq!(kv.get("Key"), Some(&EzStr::new("A")));
+        let by = kv.entry("By").unwrap();
+        assert_eq!((by.key.start, by.key.end), (48, 50));
+        assert_eq!(header.slice(by.value.start, by.value.end), by.value.text);
+    }
+
+    #[cfg(feature = "music")]
+    #[test]
+    fn test_music_tokens() {
+        use ezstr::music::ChordSymbol;
+
+        let chart = EzStr::new(
+            "*  Thé - Nicotine Dreams   ♩≈117BPM   page 1/2
+ By: Édrihan Lévesque   /   Alin Rogoz  ©2024*
+[4/4 Pickup]          𝄽  𝄽 𝆔♪  ♪ 𝆔♪  ♪
+                     |N.C   A1 C1 A1 F#m7/C#|");
+
+        let tempos = chart.tempos();
+        assert_eq!(tempos.len(), 1);
+        assert_eq!(tempos[0].bpm, 117.0);
+        assert_eq!(tempos[0].note, Some(EzStr::new("♩")));
+        assert!(tempos[0].approximate);
+        assert_eq!(tempos[0].matched.text, EzStr::new("♩≈117BPM"));
+        assert_eq!(tempos[0].matched.start, 27);
+
+        let signatures = chart.time_signatures();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!((signatures[0].beats, signatures[0].unit), (4, 4));
+        assert_eq!(signatures[0].annotation, EzStr::new("Pickup"));
+        assert_eq!(signatures[0].matched.text, EzStr::new("[4/4 Pickup]"));
+
+        let chords = chart.chords();
+        let texts: Vec<&str> = chords.iter().map(|c| c.matched.text.as_str()).collect();
+        assert_eq!(texts, vec!["N.C", "A1", "C1", "A1", "F#m7/C#"]);
+        assert_eq!(chords[0].symbol, ChordSymbol::NoChord);
+        assert_eq!(
+            chords[4].symbol,
+            ChordSymbol::Chord { root: EzStr::new("F#"), quality: EzStr::new("m7"), bass: Some(EzStr::new("C#")) }
+        );
+        assert_eq!(chart.slice(chords[1].matched.start, chords[1].matched.end), EzStr::new("A1"));
+    }
+
+    #[test]
+    fn test_runs_by() {
+        use ezstr::runs::GraphemeClass;
+
+        let line = EzStr::new("Thé ♩≈117BPM 👍🏽");
+        let runs: Vec<_> = line.runs_by(GraphemeClass::of).collect();
+        let kinds: Vec<GraphemeClass> = runs.iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                GraphemeClass::Letter,
+                GraphemeClass::Whitespace,
+                GraphemeClass::Symbol,
+                GraphemeClass::Digit,
+                GraphemeClass::Letter,
+                GraphemeClass::Whitespace,
+                GraphemeClass::Symbol,
+            ]
+        );
+        assert_eq!(runs[3].1.text, EzStr::new("117"));
+        assert_eq!((runs[3].1.start, runs[3].1.end, runs[3].1.char_start), (6, 9, 6));
+        assert_eq!(runs[6].1.text, EzStr::new("👍🏽"));
+
+        let by_width: Vec<_> = EzStr::new("ab漢字c").runs_by(|g| g.as_str().len() > 1).map(|(_, m)| m.text).collect();
+        assert_eq!(by_width, vec![EzStr::new("ab"), EzStr::new("漢字"), EzStr::new("c")]);
+        assert_eq!(EzStr::new("").runs_by(GraphemeClass::of).count(), 0);
+    }
+
+    #[test]
+    fn test_filter_and_map_graphemes() {
+        let raw = EzStr::new("Th\u{7}é\u{1b} ♪");
+        let (clean, map) = raw.filter_graphemes(|g| !g.as_str().chars().all(char::is_control));
+        assert_eq!(clean, EzStr::new("Thé ♪"));
+        assert_eq!(map.to_source(2..5), Some(3..7));
+        assert_eq!(map.output_span(2), Some(2..2));
+
+        let mut text = raw.clone();
+        let retained = text.retain(|g| !g.as_str().chars().all(char::is_control));
+        assert_eq!((text, retained), (clean, map));
+
+        let (mapped, map) = EzStr::new("a♪b").map_graphemes(|g| match g.as_str() {
+            "♪" => Some(EzStr::new("note")),
+            "b" => None,
+            _ => Some(g.to_grapheme().as_str().into()),
+        });
+        assert_eq!(mapped, EzStr::new("anote"));
+        assert_eq!(map.source_span(3), Some(1..2));
+        assert_eq!(map.output_span(2), Some(5..5));
+    }
+
+    #[test]
+    fn test_skeleton_and_confusables() {
+        let spoof = EzStr::new("Nicоtine Drеams"); // Cyrillic о and е
+        assert_ne!(spoof, EzStr::new("Nicotine Dreams"));
+        assert!(spoof.is_confusable_with("Nicotine Dreams"));
+        assert_eq!(EzStr::new("раураl").skeleton(), EzStr::new("paypal").skeleton());
+        assert_eq!(EzStr::new("Ｔhé\u{200B}").skeleton(), EzStr::new("Thé"));
+        assert!(EzStr::new("l0gin").is_confusable_with("IOgin"));
+        assert!(!EzStr::new("Thé").is_confusable_with("The"));
+    }
+
+    #[test]
+    fn test_canonical_equivalence() {
+        use ezstr::normalize::EquivKey;
+        use std::collections::HashMap;
+
+        let composed = EzStr::new("Thé Lévesque");
+        let decomposed = EzStr::new("The\u{301} Le\u{301}vesque");
+        assert_ne!(composed, decomposed);
+        assert!(composed.canonical_eq(&decomposed));
+        assert_eq!(decomposed.to_nfc(), composed);
+        assert_eq!(composed.canonical_hash(), decomposed.canonical_hash());
+        assert_ne!(composed.canonical_hash(), EzStr::new("The Levesque").canonical_hash());
+
+        // Marks are reordered canonically and Hangul composes arithmetically.
+        assert_eq!(EzStr::new("a\u{323}\u{302}").to_nfc(), EzStr::new("ậ"));
+        assert_eq!(EzStr::new("a\u{302}\u{323}").to_nfc(), EzStr::new("ậ"));
+        assert_eq!(EzStr::new("\u{1112}\u{1161}\u{11AB}").to_nfc(), EzStr::new("한"));
+        assert_eq!(EzStr::new("\u{212B}").to_nfc(), EzStr::new("Å"));
+
+        let mut plays: HashMap<EquivKey, u32> = HashMap::new();
+        *plays.entry(EquivKey::new(composed.clone())).or_default() += 1;
+        *plays.entry(EquivKey::new(decomposed)).or_default() += 1;
+        assert_eq!(plays.len(), 1);
+        assert_eq!(plays[&EquivKey::from("Thé Lévesque")], 2);
+        assert_eq!(plays.keys().next().unwrap().original(), &composed);
+    }
+
+    #[cfg(feature = "collate")]
+    #[test]
+    fn test_collation() {
+        use std::cmp::Ordering;
+
+        let mut songs: Vec<EzStr> = ["Zèbre", "Étoile", "eau", "Ensemble", "été", "Ete"].iter().map(|&s| s.into()).collect();
+        songs.sort_by_key(|s| s.collation_key("fr"));
+        let sorted: Vec<&str> = songs.iter().map(|s| s.as_str()).collect();
+        assert_eq!(sorted, vec!["eau", "Ensemble", "Ete", "été", "Étoile", "Zèbre"]);
+
+        assert_eq!(EzStr::new("ö").cmp_collated("z", "de"), Ordering::Less);
+        assert_eq!(EzStr::new("ö").cmp_collated("z", "sv-SE"), Ordering::Greater);
+        assert_eq!(EzStr::new("ñu").cmp_collated("nz", "es"), Ordering::Greater);
+        assert_eq!(EzStr::new("Straße").cmp_collated("strasse", "de"), Ordering::Greater);
+        assert_eq!(EzStr::new("e\u{301}").collation_key("fr"), EzStr::new("é").collation_key("fr"));
+        assert_eq!(EzStr::new("a 2").cmp_collated("a10", "en"), Ordering::Less);
+
+        // Canadian French compares accents from the end of the word.
+        assert_eq!(EzStr::new("cote").cmp_collated("côté", "fr"), Ordering::Less);
+        assert_eq!(EzStr::new("côte").cmp_collated("coté", "fr"), Ordering::Greater);
+        assert_eq!(EzStr::new("côte").cmp_collated("coté", "fr-CA"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_cmp_natural() {
+        use std::cmp::Ordering;
+
+        assert_eq!(EzStr::new("page 2/2").cmp_natural("page 10/2"), Ordering::Less);
+        assert_eq!(EzStr::new("track 007").cmp_natural("track 8"), Ordering::Less);
+        assert_eq!(EzStr::new("track 07").cmp_natural("track 7"), Ordering::Greater);
+        assert_eq!(EzStr::new("Thé 2").cmp_natural("Thé 2"), Ordering::Equal);
+        assert_eq!(EzStr::new("x99999999999999999999999").cmp_natural("x100000000000000000000000"), Ordering::Less);
+
+        let mut charts: Vec<EzStr> = ["chart10.txt", "chart9.txt", "chart1.txt", "chart"].iter().map(|&s| s.into()).collect();
+        charts.sort_by(|a, b| a.cmp_natural(b));
+        let sorted: Vec<&str> = charts.iter().map(|s| s.as_str()).collect();
+        assert_eq!(sorted, vec!["chart", "chart1.txt", "chart9.txt", "chart10.txt"]);
+    }
+
+    #[test]
+    fn test_title_and_sentence_case() {
+        assert_eq!(EzStr::new("thé - nicotine DREAMS").to_title_case("en"), EzStr::new("Thé - Nicotine Dreams"));
+        assert_eq!(EzStr::new("édrihan lévesque-rogoz").to_title_case("fr"), EzStr::new("Édrihan Lévesque-Rogoz"));
+        assert_eq!(EzStr::new("e\u{301}toile don't stop").to_title_case("en"), EzStr::new("E\u{301}toile Don't Stop"));
+        assert_eq!(EzStr::new("het ijsselmeer").to_title_case("nl-NL"), EzStr::new("Het IJsselmeer"));
+        assert_eq!(EzStr::new("het ijsselmeer").to_title_case("en"), EzStr::new("Het Ijsselmeer"));
+        assert_eq!(EzStr::new("istanbul IRMAK").to_title_case("tr"), EzStr::new("İstanbul Irmak"));
+        assert_eq!(EzStr::new("ǆungla").to_title_case("hr"), EzStr::new("ǅungla"));
+
+        assert_eq!(
+            EzStr::new("THE PICKUP IS 4/4. then it SWINGS! \"quiet\" ending").to_sentence_case(),
+            EzStr::new("The pickup is 4/4. Then it swings! \"Quiet\" ending")
+        );
+    }
+
+    #[test]
+    fn test_whitespace_normalization() {
+        use ezstr::whitespace::NewlineStyle;
+
+        let pasted = EzStr::new("\u{FEFF}  Thé\u{A0}\t- Nicotine  \r\n\t[4/4]\r𝄽  ♪\n");
+        let (no_bom, map) = pasted.strip_bom();
+        assert_eq!(no_bom.as_str(), "  Thé\u{A0}\t- Nicotine  \r\n\t[4/4]\r𝄽  ♪\n");
+        assert_eq!(map.output_span(0), Some(0..0));
+        assert_eq!(map.source_span(0), Some(1..2));
+
+        let (lf, _) = no_bom.normalize_newlines(NewlineStyle::Lf);
+        assert_eq!(lf.as_str(), "  Thé\u{A0}\t- Nicotine  \n\t[4/4]\n𝄽  ♪\n");
+        assert_eq!(EzStr::new("a\nb").normalize_newlines(NewlineStyle::CrLf).0.as_str(), "a\r\nb");
+
+        let (collapsed, map) = lf.collapse_whitespace();
+        assert_eq!(collapsed.as_str(), "Thé - Nicotine\n[4/4]\n𝄽 ♪\n");
+        // The space after "Thé" stands for the NBSP and the tab.
+        assert_eq!(map.source_span(3), Some(5..7));
+        assert_eq!(map.to_source(0..3), Some(2..5));
+        assert_eq!(map.output_span(0), Some(0..0));
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let grid = EzStr::new("A1\tC1\n漢字\tG1\n\t|");
+        let (expanded, map) = grid.expand_tabs(4);
+        assert_eq!(expanded.as_str(), "A1  C1\n漢字    G1\n    |");
+        assert_eq!(map.output_span(2), Some(2..4));
+        assert_eq!(map.to_source(9..13), Some(8..9));
+        assert_eq!(map.source_span(13), Some(9..10));
+        assert_eq!(grid.expand_tabs(0).0.as_str(), "A1C1\n漢字G1\n|");
+    }
+
+    #[test]
+    fn test_indentation() {
+        use ezstr::indent::Indent;
+
+        let section = EzStr::new("    [Verse]\n        A1 C1\n  \n        G1\n    [Chorus]\n");
+        assert_eq!(section.detect_indent(), Some(Indent::Spaces(4)));
+        assert_eq!(EzStr::new("a\n\tb\n\t\tc").detect_indent(), Some(Indent::Tabs));
+        assert_eq!(EzStr::new("a\nb").detect_indent(), None);
+
+        let dedented = section.dedent();
+        assert_eq!(dedented.as_str(), "[Verse]\n    A1 C1\n\n    G1\n[Chorus]\n");
+        assert_eq!(dedented.indent("> ").as_str(), "> [Verse]\n>     A1 C1\n\n>     G1\n> [Chorus]\n");
+
+        // A space carrying a combining mark is content, not indentation.
+        assert_eq!(EzStr::new("  \u{301}x\n  y").dedent().as_str(), " \u{301}x\n y");
+    }
+
+    #[test]
+    fn test_split_respecting() {
+        let row = EzStr::new(r#"|N.C "lyrics | with pipe" A1|C1 \| G1|'it''s'|"#);
+        let cells: Vec<String> = row.split_respecting('|', &['"', '\''], &['\\']).iter().map(|m| m.text.to_string()).collect();
+        assert_eq!(cells, vec!["", r#"N.C "lyrics | with pipe" A1"#, r"C1 \| G1", "'it''s'", ""]);
+
+        let parts = row.split_respecting("|", &['"'], &[]);
+        assert_eq!(parts.len(), 6);
+        assert_eq!(row.slice(parts[2].start, parts[2].end), parts[2].text);
+
+        let unclosed = EzStr::new("a|\"b|c");
+        assert_eq!(unclosed.split_respecting('|', &['"'], &['\\']).len(), 2);
+        assert_eq!(row.split_respecting('|', &['"'], &['\\'])[2].text.as_str(), r"C1 \| G1");
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_parse_csv() {
+        use ezstr::csv::{CsvError, Dialect};
+
+        let doc = EzStr::new("title,tempo\r\n\"Thé, \"\"live\"\"\",♩≈117\n\"two\nlines\",\n");
+        let rows = doc.parse_csv(Dialect::CSV).unwrap();
+        assert_eq!(rows.len(), 3);
+        let values: Vec<&str> = rows[1].values().map(|v| v.as_str()).collect();
+        assert_eq!(values, vec!["Thé, \"live\"", "♩≈117"]);
+        assert_eq!(rows[1].cells[0].raw.text.as_str(), "\"Thé, \"\"live\"\"\"");
+        assert_eq!((rows[1].cells[1].raw.start, rows[1].cells[1].raw.end), (28, 33));
+        assert_eq!(rows[0].raw.text.as_str(), "title,tempo");
+        assert_eq!(rows[2].cells[0].value.as_str(), "two\nlines");
+        assert_eq!(rows[2].cells[1].value.as_str(), "");
+
+        let tsv = EzStr::new("A1\tC1\tG1");
+        assert_eq!(tsv.parse_csv(Dialect::TSV).unwrap()[0].cells.len(), 3);
+        assert_eq!(EzStr::new("a,\"b").parse_csv(Dialect::CSV), Err(CsvError::UnclosedQuote { at: 2 }));
+        assert_eq!(EzStr::new("\"a\"b,c").parse_csv(Dialect::CSV), Err(CsvError::TextAfterQuote { at: 3 }));
+        assert_eq!(EzStr::new("a;b").parse_csv(Dialect::CSV.delimiter(';')).unwrap()[0].cells.len(), 2);
+    }
+
+    #[test]
+    fn test_escape_and_unescape() {
+        use ezstr::escape::EscapeStyle;
+
+        let title = EzStr::new("Thé \"live\"\n♩<117>");
+        let (json, map) = title.escape_json();
+        assert_eq!(json.as_str(), "Thé \\\"live\\\"\\n♩<117>");
+        assert_eq!(map.output_span(4), Some(4..6));
+        assert_eq!(map.source_span(13), Some(10..11));
+
+        let (decoded, map) = json.unescape_json().unwrap();
+        assert_eq!(decoded, title);
+        assert_eq!(map.source_span(10), Some(12..14));
+        assert_eq!(EzStr::new("\\u00e9\\ud834\\udd1e").unescape_json().unwrap().0.as_str(), "é𝄞");
+        assert_eq!(EzStr::new("ok\\x").unescape_json(), Err(ezstr::Error::InvalidEscape { at: 2 }));
+        assert!(EzStr::new("\\ud834").unescape_json().is_err());
+
+        assert_eq!(title.escape(EscapeStyle::Html).0.as_str(), "Thé &quot;live&quot;\n♩&lt;117&gt;");
+        assert_eq!(EzStr::new("it's a|b").escape(EscapeStyle::Shell).0.as_str(), "it\\'s\\ a\\|b");
+        assert_eq!(EzStr::new("").escape(EscapeStyle::Shell).0.as_str(), "''");
+        let (pattern, _) = EzStr::new("|N.C|").escape(EscapeStyle::Regex);
+        assert!(Regex::new(pattern.as_str()).unwrap().is_match("x|N.C|y"));
+    }
+
+    #[test]
+    fn test_regex_literal_and_pattern_builder() {
+        use ezstr::pattern::PatternBuilder;
+
+        let cell = EzStr::new("|N.C|");
+        assert_eq!(cell.to_regex_literal().as_str(), r"\|N\.C\|");
+        assert!(Regex::new(cell.to_regex_literal().as_str()).unwrap().is_match("x|N.C|"));
+
+        let chords = PatternBuilder::new().literals(["A", "A1", "N.C", ""]).literal("C1").whole_words(true).build().unwrap();
+        let line = EzStr::new("|N.C   A1 C1 A12 A NxC|");
+        let texts: Vec<String> = line.find_iter(&chords).map(|m| m.text.to_string()).collect();
+        assert_eq!(texts, vec!["N.C", "A1", "C1", "A"]);
+
+        let loose = PatternBuilder::new().literal("a1").case_insensitive(true).build().unwrap();
+        assert_eq!(line.find_iter(&loose).count(), 2);
+        assert!(!PatternBuilder::new().build().unwrap().is_match("anything"));
+    }
+
+    #[test]
+    fn test_find_iter_lines() {
+        let chart = EzStr::new("Thé ♩≈117BPM\r\n[4/4 Pickup]\n|N.C   A1 C1 A1|");
+        let re = Regex::new(r"A1|^\[|\d+$").unwrap();
+        let found = chart.find_iter_lines(&re);
+        let summary: Vec<(usize, std::ops::Range<usize>, std::ops::Range<usize>)> =
+            found.iter().map(|m| (m.line_number, m.in_line.range(), m.matched.start..m.matched.end)).collect();
+        assert_eq!(summary, vec![(2, 0..1, 13..14), (3, 7..9, 33..35), (3, 13..15, 39..41)]);
+        assert_eq!(found[1].matched.text.as_str(), "A1");
+        assert_eq!(chart.find_iter_lines(&Regex::new(r"BPM$").unwrap())[0].in_line, 9..12);
+    }
+
+    #[test]
+    fn test_find_in_and_find_at() {
+        let line = EzStr::new("|N.C   A1 C1 A1 G1|");
+        let chord = Regex::new(r"\b[A-G]1\b").unwrap();
+        let spans: Vec<(usize, usize)> = line.find_in(&chord, 9..16).map(|m| (m.start, m.end)).collect();
+        assert_eq!(spans, vec![(10, 12), (13, 15)]);
+        assert_eq!(line.find_in(&chord, 8..13).map(|m| m.text.to_string()).collect::<Vec<_>>(), vec!["C1"]);
+        // The range start keeps its left context: "1" inside "A1" is not at a word boundary.
+        assert_eq!(line.find_in(&Regex::new(r"\b1").unwrap(), 8..20).count(), 0);
+        assert_eq!(line.find_in(&Regex::new("").unwrap(), 17..40).count(), 3);
+
+        assert_eq!(line.find_at(&chord, 11).map(|m| m.start), Some(13));
+        assert_eq!(line.find_at(&chord, 18), None);
+        assert_eq!(line.find_at(&chord, 40), None);
+    }
+
+    #[test]
+    fn test_preview_replace() {
+        let line = EzStr::new("Thé ♩≈117BPM |A1 C1|");
+        let re = Regex::new(r"(?<root>[A-G])1").unwrap();
+        let preview = line.preview_replace(&re, "${root}maj7");
+        let pairs: Vec<(usize, usize, &str)> = preview.iter().map(|(old, new)| (old.start, old.end, new.as_str())).collect();
+        assert_eq!(pairs, vec![(14, 16, "Amaj7"), (17, 19, "Cmaj7")]);
+        assert_eq!(preview[0].0.text.as_str(), "A1");
+        assert_eq!(line.preview_replace(&Regex::new(r"(\d+)BPM").unwrap(), "$1 bpm")[0].1.as_str(), "117 bpm");
+        assert!(line.preview_replace(&Regex::new("x").unwrap(), "y").is_empty());
+    }
+
+    #[test]
+    fn test_with_replaced_reindexes_incrementally() {
+        let base = EzStr::new("Thé 👍🏽 ♩≈117BPM\r\n|N.C A1|e 🇨🇦🇫🇷");
+        base.index_all();
+        let edits = [
+            (7..10, "120"),
+            (0..0, "*"),
+            (2..3, "e\u{301}\u{302}"),
+            (4..5, ""),
+            (5..5, "\u{1F3FF}"),
+            (15..16, "\n"),
+            (23..24, "\u{301}"),
+            (23..23, "\u{301}"),
+            (25..27, "🇺"),
+            (0..27, ""),
+        ];
+        for (range, replacement) in edits {
+            let edited = base.with_replaced(range.clone(), replacement);
+            let fresh = EzStr::new(edited.as_str());
+            assert_eq!(edited.grapheme_index(), fresh.grapheme_index(), "{:?} -> {:?}", range, replacement);
+            assert_eq!(edited.len(), fresh.len());
+        }
+
+        let mut text = EzStr::new("Thé ♩≈117BPM");
+        text.replace_range(6..9, "96");
+        assert_eq!(text.as_str(), "Thé ♩≈96BPM");
+    }
+
+    #[test]
+    fn test_persistent_versions() {
+        use ezstr::persistent::EzStrPersistent;
+
+        let chart = "|N.C   A1 C1 A1 G1|\n".repeat(200);
+        let v1 = EzStrPersistent::new(chart.as_str());
+        assert_eq!(v1.len(), 4000);
+        assert!(v1.chunks().count() > 1);
+
+        let v2 = v1.replace(7..9, "Am7").insert(0, "Thé ♩≈117BPM\n");
+        let v3 = v2.remove(0..13);
+        assert_eq!(v1.to_ezstr().as_str(), chart);
+        assert_eq!(v2.slice(13..33).as_str(), "|N.C   Am7 C1 A1 G1|");
+        assert_eq!(v3.len(), 4001);
+        assert_eq!(v3.to_string(), chart.replacen("A1", "Am7", 1));
+
+        // Only the edited leaf is new; the rest are the same allocations as in v1.
+        let v1_chunks: Vec<*const u8> = v1.chunks().map(|c| c.as_str().as_ptr()).collect();
+        let shared = v3.chunks().filter(|c| v1_chunks.contains(&c.as_str().as_ptr())).count();
+        assert!(shared >= v1_chunks.len() - 1, "{} of {}", shared, v1_chunks.len());
+
+        // Graphemes on either side of an edit are re-segmented.
+        let accented = EzStrPersistent::new("The").insert(3, "\u{301}");
+        assert_eq!(accented.len(), 3);
+        assert_eq!(accented.slice(2..3).as_str(), "e\u{301}");
+
+        let mut version = EzStrPersistent::default();
+        for i in 0..200 {
+            version = version.insert(version.len(), i.to_string());
+        }
+        assert_eq!(version.len(), (0..200).map(|i: i32| i.to_string().len()).sum::<usize>());
+    }
+
+    #[test]
+    fn test_span_arithmetic() {
+        let line = EzStr::new("|N.C   A1 C1 A1 G1|");
+        let chords: Vec<GraphemeMatch> = line.find_iter(&Regex::new(r"[A-G]1").unwrap()).collect();
+        let (a1, c1) = (&chords[0], &chords[1]);
+        assert_eq!((a1.len(), a1.is_empty()), (2, false));
+        assert!(a1.contains(8) && !a1.contains(9));
+        assert!(!a1.overlaps(c1));
+        assert_eq!(a1.intersect(c1), None);
+        assert_eq!(a1.union(c1), 7..12);
+        assert_eq!(c1.shift(-3), 7..9);
+        let wide = line.find(&Regex::new(r"A1 C").unwrap()).unwrap();
+        assert!(wide.overlaps(c1));
+        assert_eq!(wide.intersect(c1), Some(GSpan::new(10, 11)));
+
+        let mut sorted = chords.clone();
+        sorted.reverse();
+        sorted.sort();
+        assert_eq!(sorted, chords);
+
+        let mut set: SpanSet = chords.into_iter().collect();
+        assert_eq!(set.spans(), &[7..9, 10..12, 13..15, 16..18]);
+        set.insert(9..10);
+        set.insert(12..13);
+        assert_eq!(set.spans(), &[7..15, 16..18]);
+        assert!(set.contains(14) && !set.contains(15));
+        set.remove(8..14);
+        assert_eq!(set.spans(), &[7..8, 14..15, 16..18]);
+        assert_eq!(set.covered(), 4);
+        let other: SpanSet = [0..8, 17..30].into_iter().collect();
+        assert_eq!(set.intersection(&other).spans(), &[7..8, 17..18]);
+        assert_eq!(set.union(&other).spans(), &[0..8, 14..15, 16..30]);
+    }
+
+    #[test]
+    fn test_gspan() {
+        let text = EzStr::new("Thé 👍🏽 ♩≈117BPM");
+        let bpm = text.find(&Regex::new(r"\d+").unwrap()).unwrap();
+        let span = bpm.span();
+        assert_eq!(span, 8..11);
+        assert_eq!(span.to_byte_range(&text), bpm.byte_range());
+        assert_eq!(GSpan::from_byte_range(&text, bpm.byte_range()), span);
+        assert_eq!(GSpan::from_byte_range(&text, 6..19), GSpan::new(5, 8));
+        assert_eq!(format!("{:?}", span), "8..11");
+        let range: std::ops::Range<usize> = span.into();
+        assert_eq!(text.slice_view(range).as_str(), "117");
+        assert_eq!(text.slice_view(span).span(), span);
+        assert_eq!(text.with_replaced(span, "96").as_str(), "Thé 👍🏽 ♩≈96BPM");
+    }
+
+    #[test]
+    fn test_annotated_ezstr() {
+        use ezstr::annotated::AnnotatedEzStr;
+        let mut doc = AnnotatedEzStr::new("let café = 1;");
+        doc.annotate(0..3, "keyword");
+        doc.annotate(4..8, "ident");
+        doc.annotate(0..13, "statement");
+        assert!(!doc.annotate_exclusive(5..6, "other"));
+        assert_eq!(doc.labels_at(5).collect::<Vec<_>>(), vec![&"statement", &"ident"]);
+        assert_eq!(doc.labels_at(3).collect::<Vec<_>>(), vec![&"statement"]);
+        assert_eq!(doc.segments()[0], (GSpan::new(0, 3), vec![&"keyword", &"statement"]));
+
+        let renamed = doc.with_replaced(4..8, "naïveté");
+        assert_eq!(renamed.text().as_str(), "let naïveté = 1;");
+        let spans: Vec<_> = renamed.annotations().iter().map(|a| (a.span, a.label)).collect();
+        assert_eq!(spans, vec![(GSpan::new(0, 3), "keyword"), (GSpan::new(0, 16), "statement"), (GSpan::new(4, 11), "ident")]);
+
+        let (ascii, map) = renamed.text().transliterate();
+        let ascii = renamed.remap(ascii, &map);
+        assert_eq!(ascii.text().as_str(), "let naivete = 1;");
+        assert_eq!(ascii.labels_at(10).collect::<Vec<_>>(), vec![&"statement", &"ident"]);
+    }
+
+    #[test]
+    fn test_highlighter() {
+        use ezstr::highlight::{Conflict, Highlighter};
+        let text = EzStr::new("if iffy == \"ñ if\" { 42 }");
+        let highlighter = Highlighter::new()
+            .rule(Regex::new(r"\bif\b").unwrap(), "keyword")
+            .rule(Regex::new(r"[a-z]+").unwrap(), "ident")
+            .rule(Regex::new(r#""[^"]*""#).unwrap(), "string")
+            .rule(Regex::new(r"\d+").unwrap(), "number");
+        let labels = |h: &Highlighter<&'static str>| {
+            h.highlight(&text).annotations().iter().map(|a| (a.span.range(), a.label)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels(&highlighter),
+            vec![(0..2, "keyword"), (3..7, "ident"), (11..17, "string"), (20..22, "number")]
+        );
+        let first = highlighter.clone().rule(Regex::new(r"iffy").unwrap(), "odd").conflict(Conflict::First);
+        assert_eq!(labels(&first)[1], (3..7, "ident"));
+        let first = Highlighter::new()
+            .rule(Regex::new(r"i").unwrap(), "i")
+            .rule(Regex::new(r"iffy").unwrap(), "iffy")
+            .conflict(Conflict::First);
+        assert_eq!(labels(&first)[..3], [(0..1, "i"), (3..4, "i"), (14..15, "i")]);
+    }
+
+    #[cfg(feature = "index")]
+    #[test]
+    fn test_ezstr_index() {
+        use ezstr::index::EzStrIndex;
+        let text = EzStr::new("banana bandana cafe\u{301} café");
+        let index = EzStrIndex::build(&text);
+        assert_eq!(index.find_all("ana"), vec![GSpan::new(1, 4), GSpan::new(3, 6), GSpan::new(11, 14)]);
+        assert_eq!(index.count("ban"), 2);
+        assert_eq!(index.find_all("caf"), vec![GSpan::new(15, 18), GSpan::new(20, 23)]);
+        assert!(index.find_all("cafe").is_empty());
+        assert_eq!(index.find_all("café"), vec![GSpan::new(20, 24)]);
+        assert!(!index.contains("nab"));
+        assert!(!index.contains(""));
+    }
+
+    #[test]
+    fn test_trie() {
+        use ezstr::trie::EzTrie;
+        let mut chords = EzTrie::new();
+        chords.insert("Am", 1);
+        chords.insert("Am7", 2);
+        chords.insert("Am7b5", 3);
+        chords.insert("A", 0);
+        let hit = chords.longest_prefix(&EzStr::new("Am7b5/G")).unwrap();
+        assert_eq!((hit.matched.end, hit.key.as_str(), *hit.value), (5, "Am7b5", 3));
+        assert_eq!(chords.longest_prefix(&EzStr::new("Am9")).unwrap().value, &1);
+        assert!(chords.longest_prefix(&EzStr::new("am")).is_none());
+        let keys: Vec<_> = chords.completions("Am").into_iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["Am", "Am7", "Am7b5"]);
+
+        let mut titles = EzTrie::case_insensitive();
+        titles.insert("Straße", "street");
+        titles.insert("Thé", "tea");
+        assert_eq!(titles.get("STRASSE"), Some(&"street"));
+        assert_eq!(titles.insert("THÉ", "TEA"), Some("tea"));
+        assert_eq!(titles.len(), 2);
+        assert_eq!(titles.completions("th").len(), 1);
+        // a prefix may not end inside the "e" + acute accent grapheme of the text
+        assert!(titles.longest_prefix(&EzStr::new("the\u{301}")).is_none());
+        assert_eq!(titles.longest_prefix(&EzStr::new("thé time")).unwrap().matched.end, 3);
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        let title = EzStr::new("Thé Nicotine Dreams");
+        assert!(!title.matches_glob("The?*Dreams"));
+        assert!(title.matches_glob("Th? *Dreams"));
+        assert!(title.matches_glob("*[Nn]icotine*"));
+        assert!(!title.matches_glob("*[!A-Z]icotine*"));
+        assert!(EzStr::new("Nicotine 👨‍👩‍👧 Dreams").matches_glob("Nicotine ? Dreams"));
+        assert!(EzStr::new("cafe\u{301}").matches_glob("caf?"));
+        assert!(!EzStr::new("cafe\u{301}").matches_glob("cafe*"));
+        assert!(EzStr::new("a*b").matches_glob(r"a\*b"));
+        assert!(!EzStr::new("axb").matches_glob(r"a\*b"));
+        assert!(EzStr::new("[x").matches_glob("[x"));
+        assert!(EzStr::new("]").matches_glob("[]]"));
+        assert!(EzStr::new("").matches_glob("**"));
+        assert!(EzStr::new("aaab").matches_glob("*a*b"));
+    }
+
+    #[test]
+    fn test_pattern_from_glob() {
+        use ezstr::pattern::Pattern;
+        let text = EzStr::new("Thé Dreams\nThe 🇫🇷 Dreams\nThe e\u{301} Dreams (a.k.a. [live])");
+        let regex = Pattern::from_glob("Th? *Dreams").unwrap();
+        let lines: Vec<usize> = text.find_iter_lines(&regex).iter().map(|m| m.line_number).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        let one = Pattern::from_glob("The ? Dreams").unwrap();
+        assert_eq!(text.find_iter(&one).map(|m| m.start).collect::<Vec<_>>(), vec![11, 24]);
+        assert_eq!(text.find_iter(&Pattern::from_glob("(a.k.a. [[]live])").unwrap()).count(), 1);
+        assert_eq!(text.find_iter(&Pattern::from_glob("[!a-z]reams").unwrap()).count(), 3);
+        assert!(Pattern::from_glob("[z-a]").unwrap().find(text.as_str()).is_none());
+        assert!(matches!(Pattern::from_glob("?".repeat(300)), Err(Error::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn test_lines_with_terminators() {
+        use ezstr::whitespace::NewlineStyle;
+        let text = EzStr::new("Intro\r\n\nVerse é\rOutro");
+        let lines = text.lines_with_terminators();
+        let endings: Vec<_> = lines.iter().map(|l| l.ending).collect();
+        assert_eq!(endings, vec![Some(NewlineStyle::CrLf), Some(NewlineStyle::Lf), Some(NewlineStyle::Cr), None]);
+        let contents: Vec<_> = lines.iter().map(|l| l.content.text.as_str()).collect();
+        assert_eq!(contents, vec!["Intro", "", "Verse é", "Outro"]);
+        assert_eq!((lines[2].full.start, lines[2].full.end), (7, 15));
+        assert_eq!(lines.iter().map(|l| l.full.text.as_str()).collect::<String>(), text.as_str());
+        assert_eq!(EzStr::new("a\n").lines_with_terminators().len(), 1);
+
+        let parts: Vec<_> = EzStr::new("A;B;;").split_terminator(';').into_iter().map(|p| p.text.to_string()).collect();
+        assert_eq!(parts, vec!["A", "B", ""]);
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let chart = EzStr::new("\n[Verse]\nAm  C\r\n  \n\n[Chorus]\nF G\n\t\nOutro");
+        let blocks: Vec<_> = chart.paragraphs().into_iter().map(|p| (p.start, p.end, p.text.to_string())).collect();
+        assert_eq!(
+            blocks,
+            vec![(1, 14, "[Verse]\nAm  C".to_string()), (19, 31, "[Chorus]\nF G".to_string()), (34, 39, "Outro".to_string())]
+        );
+        assert!(EzStr::new(" \n\n").paragraphs().is_empty());
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let chart = EzStr::new("Key: Am\n[Verse]\nAm C\n[Chorus]\nF G\n");
+        let sections = chart.split_sections(&Regex::new(r"\[\w+\]").unwrap());
+        let flat: Vec<_> = sections.iter().map(|s| (s.heading.text.to_string(), s.body.range())).collect();
+        assert_eq!(flat, vec![("[Verse]".to_string(), 15..21), ("[Chorus]".to_string(), 29..34)]);
+
+        let doc = EzStr::new("# Song\nintro\n## Verse\nla\n### Bridge\n## Chorus\nna\n# Notes\n");
+        let sections = doc.split_sections(&Regex::new(r"(?m)^(?<level>#+) .*$").unwrap());
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].body.range(), 6..13);
+        let children: Vec<_> = sections[0].children.iter().map(|c| (c.heading.text.to_string(), c.level, c.children.len())).collect();
+        assert_eq!(children, vec![("## Verse".to_string(), 2, 1), ("## Chorus".to_string(), 2, 0)]);
+        assert_eq!(sections[0].span(), GSpan::new(0, 49));
+        assert_eq!(sections[1].span(), GSpan::new(49, doc.len()));
+    }
+
+    #[test]
+    fn test_checked_index_arithmetic() {
+        let long = EzStr::new("é🇫🇷".repeat(50_000));
+        assert_eq!(long.slice_signed(-3, -1).as_str(), "é🇫🇷");
+        assert_eq!(long.slice_signed(99_998, -1).as_str(), "é🇫🇷");
+        assert_eq!(long.slice_signed(-100_001, 1).as_str(), "é");
+        assert!(std::panic::catch_unwind(|| long.slice_signed(-100_002, 1)).is_err());
+        assert!(std::panic::catch_unwind(|| long.slice_signed(0, isize::MIN)).is_err());
+
+        let huge = 3_000_000_000usize;
+        assert_eq!(GIndex::from(-1isize).resolve(huge), huge - 1);
+        assert_eq!(GIndex::from(huge).checked_resolve(huge), Some(huge));
+        assert_eq!(GIndex::Pos(huge + 1).checked_resolve(huge), None);
+        assert_eq!(GIndex::Neg(usize::MAX).checked_resolve(huge), None);
+        assert_eq!(GIndex::Neg(huge).checked_resolve(usize::MAX), Some(usize::MAX - huge));
+    }
+
+    #[test]
+    fn test_unsigned_slice_indices() {
+        let chart = EzStr::new("|Am7 C1|🇫🇷");
+        let bar = chart.find(&Regex::new(r"C\d").unwrap()).unwrap();
+        assert_eq!(chart.slice(bar.start, bar.end).as_str(), "C1");
+        assert_eq!(chart.slice(8, 3), EzStr::default());
+        assert_eq!(chart.index_of_offset(-1), Some(8));
+        assert_eq!(chart.index_of_offset(9), Some(9));
+        assert_eq!(chart.index_of_offset(-10), None);
+        let last = chart.index_of_offset(-1).unwrap();
+        assert_eq!(chart.slice(last, chart.len()).as_str(), "🇫🇷");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_testing_generators() {
+        use ezstr::testing::{check_byte_index_round_trip, check_matches, check_slice_round_trip, nasty_ezstr, NastyGen};
+        assert_eq!(nasty_ezstr(7, 20), nasty_ezstr(7, 20));
+        let mut generator = NastyGen::new(42);
+        let any = Regex::new(r"\w+|\s|\p{M}").unwrap();
+        for _ in 0..200 {
+            let text = generator.ezstr(24);
+            check_slice_round_trip(&text).unwrap();
+            check_byte_index_round_trip(&text).unwrap();
+            check_matches(&text, &any).unwrap();
+        }
+        let text = EzStr::new("cafe\u{301}");
+        let mut m = text.find(&Regex::new("caf").unwrap()).unwrap();
+        m.char_start = 1;
+        assert_eq!(ezstr::testing::check_match(&text, &m).unwrap_err().check, "match validity");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_fuzz_helpers() {
+        use ezstr::fuzz_helpers::{byte_ranges, find_round_trip, slicing};
+        let inputs: [&[u8]; 5] = [
+            b"",
+            b"\x00",
+            "\x07\x03e\u{301}👨‍👩‍👧🇫🇷\r\n".as_bytes(),
+            b"\xff\x10caf\xc3\xa9\xe2\x80",
+            "\x02\x7e\u{301}\u{200d}🇩🇪🇫".as_bytes(),
+        ];
+        for input in inputs {
+            byte_ranges(input);
+            slicing(input);
+            find_round_trip(input);
+        }
+    }
+
+    #[test]
+    fn test_snap_to_boundary() {
+        let text = EzStr::new("ae\u{301}👨‍👩‍👧!");
+        // "e" + accent spans bytes 1..4, the family 4..22
+        assert_eq!(text.snap_to_boundary(2, Bias::Before), Some((1, 1)));
+        assert_eq!(text.snap_to_boundary(2, Bias::After), Some((4, 2)));
+        assert_eq!(text.snap_to_boundary(4, Bias::Before), Some((4, 2)));
+        assert_eq!(text.snap_to_boundary(10, Bias::Before), Some((4, 2)));
+        assert_eq!(text.snap_to_boundary(10, Bias::After), Some((22, 3)));
+        assert_eq!(text.snap_to_boundary(23, Bias::Before), Some((23, 4)));
+        assert_eq!(text.snap_to_boundary(24, Bias::After), None);
+    }
+
+    #[test]
+    fn test_segmenter() {
+        use ezstr::{LegacyGraphemes, Segmenter};
+        // Devanagari "ki": a consonant and a spacing vowel sign
+        let word = "कि 👍🏽";
+        assert_eq!(EzStr::new(word).len(), 3);
+        let legacy = EzStr::with_segmenter(word, &LegacyGraphemes);
+        assert_eq!(legacy.len(), 4);
+        assert_eq!(legacy.slice(0, 1).as_str(), "क");
+        assert_eq!(legacy.find(&Regex::new("👍").unwrap()).unwrap().start, 3);
+
+        // a tailored segmenter keeping chord names like "N.C" together
+        struct Tokens;
+        impl Segmenter for Tokens {
+            fn grapheme_starts(&self, text: &str) -> Vec<usize> {
+                let mut starts = vec![];
+                let mut prev_space = true;
+                for (i, c) in text.char_indices() {
+                    if c == ' ' || prev_space {
+                        starts.push(i);
+                    }
+                    prev_space = c == ' ';
+                }
+                starts
+            }
+        }
+        let chart = EzStr::new("N.C Am7").resegment(&Tokens);
+        assert_eq!(chart.iter().map(|g| g.as_str()).collect::<Vec<_>>(), vec!["N.C", " ", "Am7"]);
+        assert_eq!(chart.get(2).unwrap().as_str(), "Am7");
+    }
+
+    #[test]
+    fn test_words_with() {
+        use ezstr::words::WordRules;
+        let text = EzStr::new("N.C | A♭maj7 F#m7b5/G | rock'n'roll 'til dancin' 夜の");
+        let words = |rules: &WordRules| text.words_with(rules).into_iter().map(|w| w.text.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            text.words().into_iter().map(|w| w.text.to_string()).collect::<Vec<_>>(),
+            vec!["N.C", "A", "maj7", "F", "m7b5", "G", "rock'n'roll", "til", "dancin", "夜", "の"]
+        );
+        let rules = WordRules::new().word_chars("♭#/").joiners(".").keep_apostrophes(true);
+        assert_eq!(words(&rules), vec!["N.C", "A♭maj7", "F#m7b5/G", "rock'n'roll", "'til", "dancin'", "夜", "の"]);
+        let spans: Vec<_> = text.words_with(&rules).iter().map(|w| (w.start, w.end)).collect();
+        assert_eq!(spans[1], (6, 12));
+    }
+
+    #[test]
+    fn test_wrap_and_justify() {
+        use ezstr::wrap::Align;
+        let text = EzStr::new("The night bus hums along the river road\n夜の街に雨が降る 静かな歌\n");
+        let lines: Vec<_> = text.wrap(12).into_iter().map(|l| l.text.to_string()).collect();
+        assert_eq!(lines, vec!["The night", "bus hums", "along the", "river road", "夜の街に雨が", "降る 静かな", "歌"]);
+        assert!(EzStr::new("Supercalifragilistic").wrap(8).iter().all(|l| l.text.display_width() <= 8));
+
+        let full: Vec<_> = text.justify(12, Align::Full).into_iter().map(|l| l.to_string()).collect();
+        assert_eq!(full[..4], ["The    night", "bus     hums", "along    the", "river road  "]);
+        // no stretching around CJK, just padding
+        assert_eq!(full[5], "降る 静かな ");
+        assert!(full.iter().all(|l| EzStr::new(l.as_str()).display_width() == 12));
+        let right = text.justify(12, Align::Right);
+        assert_eq!(right[1].as_str(), "    bus hums");
+        assert_eq!(EzStr::new("A1 C1").justify(9, Align::Center)[0].as_str(), "  A1 C1  ");
+    }
+
+    #[test]
+    fn test_layout() {
+        let chart = EzStr::new("[Verse]\nAm7 C1 A1 G1\nThe night bus hums\n\n[Chorus]\nF G Am\nla la la\n[Outro]\nC");
+        let page = chart.layout(2, 10, 4);
+        assert_eq!(page.columns.len(), 2);
+        assert_eq!(page.columns[0].text.as_str(), "[Verse]\nAm7 C1 A1\nG1\nThe night");
+        assert_eq!(page.columns[1].lines[0], GSpan::new(31, 39));
+        assert_eq!(page.columns[0].span(), Some(GSpan::new(0, 30)));
+        let rest = page.overflow.unwrap();
+        assert_eq!(chart.slice(rest, chart.len()).as_str(), "la la la\n[Outro]\nC");
+        assert_eq!(
+            page.render(3).as_str(),
+            "[Verse]      bus hums\nAm7 C1 A1\nG1           [Chorus]\nThe night    F G Am\n"
+        );
+        assert!(chart.layout(3, 10, 20).overflow.is_none());
+    }
+
+    #[test]
+    fn test_render_numbered() {
+        let excerpt = EzStr::new("[Chorus]\n\tF G\n夜 Am");
+        assert_eq!(excerpt.render_numbered(9, 3), "  9 | [Chorus]\n 10 | \tF G\n 11 | 夜 Am\n");
+        assert_eq!(excerpt.render_numbered(99, 1), " 99 | [Chorus]\n100 | \tF G\n101 | 夜 Am\n");
+        let am = excerpt.find(&Regex::new("Am").unwrap()).unwrap();
+        let g = excerpt.find(&Regex::new("G").unwrap()).unwrap();
+        assert_eq!(
+            excerpt.render_numbered_with_labels(1, 2, &[(am, "minor"), (g, "")]),
+            " 1 | [Chorus]\n 2 | \tF G\n   | \t  ^\n 3 | 夜 Am\n   |    ^^ minor\n"
+        );
+    }
+
+    #[test]
+    fn test_selection() {
+        use ezstr::selection::Selection;
+        let text = EzStr::new("Thé night bus\nA♭maj7 C1\n");
+        let caret = Selection::caret(5);
+        assert_eq!(caret.extend_by_word(&text, true), Selection::new(5, 9));
+        assert_eq!(caret.extend_by_word(&text, false), Selection::new(5, 4));
+        assert_eq!(caret.select_word(&text).span(), GSpan::new(4, 9));
+        assert_eq!(caret.extend_by_line(&text, true).head, 14);
+        let lines = Selection::new(16, 5).select_lines(&text);
+        assert_eq!((lines.anchor, lines.head), (24, 0));
+        assert!(lines.is_backward());
+        assert_eq!(lines.collapse(true), Selection::caret(24));
+        assert!(Selection::new(3, 30).validate(&text).is_err());
+        assert_eq!(Selection::new(3, 30).clamp(&text), Selection::new(3, 24));
+    }
+
+    #[test]
+    fn test_word_and_line_navigation() {
+        let text = EzStr::new("Thé night\r\n  A♭maj7, C1\n\nOutro");
+        assert_eq!(text.next_word_boundary(0), 3);
+        assert_eq!(text.next_word_boundary(3), 9);
+        assert_eq!(text.next_word_boundary(9), 13);
+        assert_eq!(text.next_word_boundary(21), 22);
+        assert_eq!(text.next_word_boundary(22), 29);
+        assert_eq!(text.prev_word_boundary(29), 24);
+        assert_eq!(text.prev_word_boundary(24), 20);
+        assert_eq!(text.prev_word_boundary(12), 4);
+        assert_eq!(text.prev_word_boundary(2), 0);
+        assert_eq!((text.line_start(9), text.line_end(0)), (0, 9));
+        assert_eq!((text.line_start(12), text.line_end(12)), (10, 22));
+        assert_eq!((text.line_start(23), text.line_end(23)), (23, 23));
+        assert_eq!((text.line_start(28), text.line_end(26)), (24, 29));
+    }
+
+    #[test]
+    fn test_composition() {
+        use ezstr::compose::ComposingEzStr;
+        let mut input = ComposingEzStr::new("한");
+        input.push_composing("\u{1100}");
+        input.push_composing("\u{1100}\u{1161}");
+        assert!(input.is_composing());
+        assert_eq!(input.len(), 1);
+        assert_eq!(input.display(), "한\u{1100}\u{1161}");
+        assert_eq!(input.commit_composition(), 1);
+        assert_eq!(input.committed().len(), 2);
+        assert_eq!(input.preedit(), "");
+
+        // a trailing combining mark joins the last committed grapheme
+        let mut input = ComposingEzStr::new("e");
+        input.push_composing("\u{301}");
+        input.cancel_composition();
+        assert_eq!(input.display(), "e");
+        input.push_composing("\u{301}");
+        assert_eq!(input.commit_composition(), 0);
+        assert_eq!(input.committed(), &EzStr::new("e\u{301}"));
+    }
+
+    #[test]
+    fn test_sanitize_paste() {
+        use ezstr::paste::PasteOptions;
+        let pasted = EzStr::new("\u{FEFF}Nicotine\r\nDre\u{0}ams\rcafe\u{301}\t\u{1b}!");
+        let (clean, report) = pasted.sanitize_paste(PasteOptions::default());
+        assert_eq!(clean.as_str(), "Nicotine\nDreams\ncafé\t!");
+        assert!(report.bom_removed);
+        assert_eq!(report.newlines_changed, 2);
+        assert_eq!(report.controls_removed, 2);
+        assert!(report.normalized);
+
+        let (same, report) = EzStr::new("A1 | N.C\n").sanitize_paste(PasteOptions::default());
+        assert_eq!(same.as_str(), "A1 | N.C\n");
+        assert!(report.is_clean());
+
+        let keep_crlf = PasteOptions { newlines: None, nfc: false, ..Default::default() };
+        let (kept, _) = EzStr::new("a\r\nb\u{7f}").sanitize_paste(keep_crlf);
+        assert_eq!(kept.as_str(), "a\r\nb");
+    }
+
+    #[test]
+    fn test_detect_suspicious() {
+        use ezstr::confusable::{Suspicious, SuspiciousKind};
+        use ezstr::script::Script;
+        let found = EzStr::new("p\u{430}ypal \u{202E}txt.exe\u{202C} pay\u{200B}\u{200B}pal").detect_suspicious();
+        assert_eq!(
+            found,
+            vec![
+                Suspicious { kind: SuspiciousKind::MixedScript(Script::Cyrillic), span: GSpan::new(1, 2) },
+                Suspicious { kind: SuspiciousKind::BidiControl, span: GSpan::new(7, 8) },
+                Suspicious { kind: SuspiciousKind::BidiControl, span: GSpan::new(15, 16) },
+                Suspicious { kind: SuspiciousKind::Invisible, span: GSpan::new(20, 22) },
+            ]
+        );
+        assert!(EzStr::new("Thé Nicotine Dreams 👨\u{200D}👩\u{200D}👧 Москва").detect_suspicious().is_empty());
+    }
+
+    #[test]
+    fn test_mask_and_redact() {
+        use ezstr::redact::Mask;
+        let text = EzStr::new("mail thé@x.io or 日本@x.jp");
+        let email = Regex::new(r"\S+@\S+").unwrap();
+        assert_eq!(text.mask(&email, '*').as_str(), "mail ******** or *******");
+        assert_eq!(text.mask(&email, Mask::new("*").keep_width(true)).as_str(), "mail ******** or *********");
+        assert_eq!(text.mask(&email, Mask::new("█").keep_width(true)).display_width(), text.display_width());
+        assert_eq!(text.mask(&email, Mask::new("＊").keep_width(true)).as_str(), "mail ＊＊＊＊ or ＊＊＊＊ ");
+
+        let redacted = EzStr::new("Thé Nicotine Dreams").redact_spans(&[GSpan::new(4, 8), GSpan::new(0, 3), GSpan::new(6, 12)], "#");
+        assert_eq!(redacted.as_str(), "### ######## Dreams");
+    }
+
+    #[test]
+    fn test_rect() {
+        use ezstr::rect::ColumnUnit;
+        let grid = EzStr::new("| A1 | N.C |\n| 日本 | Em |\n| G\n");
+        let block = grid.rect(0..3, 2..4, ColumnUnit::Graphemes);
+        assert_eq!(block, vec![EzStr::new("A1"), EzStr::new("日本"), EzStr::new("G")]);
+        let block = grid.rect(0..9, 2..4, ColumnUnit::Width);
+        assert_eq!(block, vec![EzStr::new("A1"), EzStr::new("日"), EzStr::new("G")]);
+
+        let edited = grid.replace_rect(0..2, 2..4, ColumnUnit::Graphemes, &["Bm", "C7"]);
+        assert_eq!(edited.as_str(), "| Bm | N.C |\n| C7 | Em |\n| G\n");
+        let padded = grid.replace_rect(2..3, 6..8, ColumnUnit::Width, &["D"]);
+        assert_eq!(padded.as_str(), "| A1 | N.C |\n| 日本 | Em |\n| G   D\n");
+        assert_eq!(grid.replace_rect(1..2, 2..6, ColumnUnit::Width, &[""]).as_str(), "| A1 | N.C |\n|  | Em |\n| G\n");
+    }
+
+    #[test]
+    fn test_match_char_offsets_across_blocks() {
+        let text = EzStr::new("é♪日\n".repeat(300));
+        let lines = text.lines_with_terminators();
+        assert_eq!(lines[299].content.char_range(), 1196..1199);
+        assert_eq!(lines[299].content, GraphemeMatch::from_source(&text, 1196, 1199));
+        assert_eq!(GraphemeMatch::from_source(&text, 1200, 1200).char_start, 1200);
+        let found: Vec<usize> = text.find_iter(&Regex::new("日").unwrap()).map(|m| m.char_start).collect();
+        assert!(found.iter().enumerate().all(|(i, &c)| c == i * 4 + 2));
+    }
+
+    #[test]
+    fn test_grapheme_match_new_equals_found() {
+        let text = EzStr::new("Thé abc");
+        let found = text.find(&Regex::new("abc").unwrap()).unwrap();
+        assert_eq!(found, GraphemeMatch::new(4, 7, "abc"));
+        assert_eq!(found.char_start, 4);
+    }
+
+    #[test]
+    fn test_indic_marks_are_combining() {
+        let tamil = EzStr::new("தமிழ்");
+        assert_eq!(tamil.len(), 3);
+        assert!(tamil.graphemes().iter().all(Grapheme::is_alphabetic));
+        let kannada = EzStr::new("ಕಿ ಕನ್ನಡ");
+        assert!(kannada[0].is_alphabetic());
+        assert!(EzStr::new("\u{0CBF}").graphemes()[0].is_combining());
+        assert_eq!(kannada.words().len(), 2);
+        assert_eq!(EzStr::new("க்").display_width(), 1);
+    }
+
+    #[test]
+    fn test_nfc_indic_and_tibetan() {
+        use ezstr::normalize::nfc;
+        assert!(EzStr::new("ऩ").canonical_eq("न\u{93c}"));
+        assert_eq!(nfc("\u{0F73}"), "\u{0F71}\u{0F72}");
+        assert_eq!(nfc("ে\u{9be}"), "ো");
+        assert_eq!(nfc("\u{0958}"), "\u{0915}\u{093C}");
+        assert_eq!(EzStr::new("ো").canonical_hash(), EzStr::new("ে\u{9be}").canonical_hash());
+    }
+
+    #[test]
+    fn test_make_patch_is_small() {
+        use ezstr::diff::{make_patch, PatchError};
+
+        let verse = "[Verse]\nA1 C1 G1 A1\n".repeat(50);
+        let old = EzStr::new(format!("{}|A1 C1 A1 G1|\n{}", verse, verse));
+        let new = EzStr::new(format!("{}|Ä1 C1 G1|\n{}", verse, verse));
+        let patch = make_patch(&old, &new, 3);
+        assert_eq!(patch.len(), 2);
+        let payload: usize = patch.iter().map(|h| h.before.len() + h.deleted.len() + h.inserted.len() + h.after.len()).sum();
+        assert!(payload < 20, "{}", payload);
+        assert_eq!(old.apply_hunks(&patch), Ok(new.clone()));
+
+        // applies after edits elsewhere, and after its context was edited
+        let edited = EzStr::new(format!("[Intro]\n{}", old.as_str()));
+        assert_eq!(edited.apply_hunks(&patch), Ok(EzStr::new(format!("[Intro]\n{}", new.as_str()))));
+        let retouched = EzStr::new("|A1 C1 A1 G1|".replace('|', "/"));
+        assert_eq!(retouched.apply_hunks(&make_patch(&EzStr::new("|A1 C1 A1 G1|"), &EzStr::new("|Ä1 C1 G1|"), 3)), Ok(EzStr::new("/Ä1 C1 G1/")));
+        assert!(matches!(EzStr::new("|B1|").apply_hunks(&patch), Err(PatchError::DeletedTextNotFound { hunk: 0, .. })));
+        assert_eq!(EzStr::new("ab").apply_hunks(&make_patch(&EzStr::new("ab"), &EzStr::new("abc"), 2)), Ok(EzStr::new("abc")));
+    }
+
+    #[test]
+    fn test_persistent_insert_joins_zwj_sequence() {
+        use ezstr::persistent::EzStrPersistent;
+
+        let text = "\u{301}\u{200d}👨\u{200d}";
+        let edited = EzStrPersistent::new(text).insert(0, "👨");
+        assert_eq!(edited.len(), EzStr::new(format!("👨{text}")).len());
+        assert_eq!(edited.len(), 1);
+    }
+
+    #[test]
+    fn test_persistent_insert_repairs_regional_indicators() {
+        use ezstr::persistent::EzStrPersistent;
+
+        let edited = EzStrPersistent::new("🇫🇷🇩🇪").insert(0, "🇫");
+        assert_eq!(edited.len(), 3);
+        assert_eq!(edited.slice(1..2).as_str(), "🇷🇩");
+        assert_eq!(edited.slice(2..3).as_str(), "🇪");
+    }
+
+    #[test]
+    fn test_grapheme_match_ord_agrees_with_eq() {
+        use std::cmp::Ordering;
+
+        let latin = GraphemeMatch::from_source(&EzStr::new("aé"), 1, 2);
+        let emoji = GraphemeMatch::from_source(&EzStr::new("🙂é"), 1, 2);
+        assert_eq!(latin, emoji);
+        assert_eq!(latin.cmp(&emoji), Ordering::Equal);
+        assert!(GraphemeMatch::new(1, 2, "a") < GraphemeMatch::new(1, 2, "b"));
+    }
+
+    #[cfg(feature = "bidi")]
+    #[test]
+    fn test_bidi_visual_order_keeps_separator_at_line_end() {
+        let visual = EzStr::new("שלום abc\nxyz").visual_order();
+        assert_eq!(visual.text, EzStr::new("abc םולש\nxyz"));
+        assert_eq!(visual.logical_indices[8], 8);
+    }
+
+    #[test]
+    fn test_ansi_style_spans_overlapping() {
+        use ezstr::ansi::{Color, Style};
+
+        let red = Style::new().fg(Color::Red);
+        let bold = Style::new().bold();
+        #[allow(clippy::reversed_empty_ranges)]
+        let spans = [(0..4, red), (2..3, bold), (3..2, Style::new().fg(Color::Green)), (9..12, bold)];
+        let styled = EzStr::new("Am7 夜e\u{301}").style_spans(&spans);
+        assert_eq!(styled.as_str(), "\x1b[31mAm\x1b[0m\x1b[1m7\x1b[0m\x1b[31m \x1b[0m夜e\u{301}");
+    }
+}
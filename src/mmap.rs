@@ -0,0 +1,146 @@
+//! Read-only memory-mapped text. The file is never copied to the heap and graphemes are
+//! segmented only as far as the highest position asked for so far.
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::prefix_index::PrefixIndex;
+use crate::{EzStr, GraphemeMatch};
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+
+// `off_t` as used by the `mmap` symbol: 32 bits on 32-bit targets unless the C side
+// opts into 64-bit file offsets, which a plain `extern` declaration cannot do.
+#[cfg(target_pointer_width = "64")]
+type OffT = i64;
+#[cfg(target_pointer_width = "32")]
+type OffT = i32;
+
+unsafe extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: i32, flags: i32, fd: i32, offset: OffT) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+/// UTF-8 text backed by a memory-mapped file; see `EzStr::from_mmap`.
+pub struct MappedEzStr {
+    ptr: *mut c_void,
+    byte_len: usize,
+    boundaries: RefCell<PrefixIndex>,
+}
+
+impl Drop for MappedEzStr {
+    fn drop(&mut self) {
+        if self.byte_len > 0 {
+            unsafe { munmap(self.ptr, self.byte_len) };
+        }
+    }
+}
+
+impl MappedEzStr {
+    pub fn as_str(&self) -> &str {
+        if self.byte_len == 0 {
+            return "";
+        }
+        // validated as UTF-8 when mapped; `from_mmap`'s caller promised it stays unchanged
+        unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.ptr as *const u8, self.byte_len)) }
+    }
+
+    /// Byte offset of grapheme `index`, or the end of the text past the last grapheme.
+    fn grapheme_to_byte(&self, index: usize) -> usize {
+        self.boundaries.borrow_mut().byte_of(self.as_str(), index).unwrap_or(self.byte_len)
+    }
+
+    fn byte_to_grapheme(&self, byte: usize) -> usize {
+        let mut b = self.boundaries.borrow_mut();
+        b.extend_past(self.as_str(), byte);
+        b.starts().partition_point(|&s| s < byte)
+    }
+
+    /// Segments the whole file; `len` and `find` do this implicitly.
+    pub fn index_all(&self) {
+        self.boundaries.borrow_mut().extend_to(self.as_str(), usize::MAX);
+    }
+
+    pub fn len(&self) -> usize {
+        self.index_all();
+        self.boundaries.borrow().starts().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.byte_len == 0
+    }
+
+    /// Graphemes `start..end`, borrowed from the mapping. Only the text up to `end` is segmented.
+    pub fn slice(&self, start: usize, end: usize) -> &str {
+        if start >= end {
+            return "";
+        }
+        let (from, to) = (self.grapheme_to_byte(start), self.grapheme_to_byte(end));
+        &self.as_str()[from..to]
+    }
+
+    /// First match of `regex`, in grapheme indices; text is segmented up to the match.
+    pub fn find(&self, regex: &Regex) -> Option<GraphemeMatch> {
+        let text = self.as_str();
+        regex.find(text).map(|m| {
+            let (start, end) = (self.byte_to_grapheme(m.start()), self.byte_to_grapheme(m.end()));
+            let (byte_start, byte_end) = (self.grapheme_to_byte(start), self.grapheme_to_byte(end));
+            let char_start = text[..byte_start].chars().count();
+            GraphemeMatch {
+                start,
+                end,
+                text: EzStr::new(&text[byte_start..byte_end]),
+                byte_start,
+                byte_end,
+                char_start,
+                char_end: char_start + text[byte_start..byte_end].chars().count(),
+            }
+        })
+    }
+
+    /// Copies the whole text into an `EzStr`.
+    pub fn to_ezstr(&self) -> EzStr {
+        EzStr::new(self.as_str())
+    }
+}
+
+impl EzStr {
+    /// Maps the file at `path` read-only, failing with `InvalidData` if it is not UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the `MappedEzStr` is alive,
+    /// by this or any other process. The text is only validated once, when mapped, so
+    /// bytes changed afterwards can make `as_str` return invalid UTF-8, and reading
+    /// past the end of a truncated file raises SIGBUS.
+    pub unsafe fn from_mmap<P: AsRef<Path>>(path: P) -> io::Result<MappedEzStr> {
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large to map"))?;
+        let ptr = if len == 0 {
+            std::ptr::null_mut()
+        } else {
+            let ptr = unsafe { mmap(std::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, file.as_raw_fd(), 0) };
+            if ptr as isize == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            ptr
+        };
+        let mapped = MappedEzStr {
+            ptr,
+            byte_len: len,
+            boundaries: RefCell::new(PrefixIndex::new(len)),
+        };
+        if len > 0 {
+            let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+            std::str::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(mapped)
+    }
+}
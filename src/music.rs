@@ -0,0 +1,114 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{EzStr, GraphemeMatch};
+
+static TIME_SIGNATURE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\s*([0-9]+)\s*/\s*([0-9]+)\s*([^\]\n]*?)\s*\]").unwrap());
+static TEMPO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([♩♪𝅗𝅥𝅘𝅥𝅘𝅥𝅮]\s*)?([≈~=]\s*)?([0-9]+(?:\.[0-9]+)?)\s*(?i:bpm)").unwrap());
+static CHORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-G](?:#|b|♯|♭)?)((?:maj|min|dim|aug|sus|add|m|M|°|ø|\+|-|[0-9]|#|b|♯|♭|\(|\))*)(?:/([A-G](?:#|b|♯|♭)?))?$")
+        .unwrap()
+});
+
+/// A bracketed time signature such as "[4/4 Pickup]".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeSignature {
+    pub beats: u32,
+    pub unit: u32,
+    /// Text after the fraction, e.g. "Pickup"; empty when there is none.
+    pub annotation: EzStr,
+    pub matched: GraphemeMatch,
+}
+
+/// A tempo marking such as "♩≈117BPM" or "120 bpm".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tempo {
+    pub bpm: f64,
+    /// The note value the beat is given in, e.g. "♩".
+    pub note: Option<EzStr>,
+    /// Whether the marking used `≈` or `~`.
+    pub approximate: bool,
+    pub matched: GraphemeMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordSymbol {
+    /// "N.C", "N.C." or "NC".
+    NoChord,
+    /// Root with accidental ("F#"), everything up to a slash ("m7", "1") and the slash bass note.
+    Chord { root: EzStr, quality: EzStr, bass: Option<EzStr> },
+}
+
+/// A chord symbol such as "A1", "F#m7/C#" or "N.C".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    pub symbol: ChordSymbol,
+    pub matched: GraphemeMatch,
+}
+
+impl EzStr {
+    fn match_at_bytes(&self, start: usize, end: usize) -> GraphemeMatch {
+        let (start, end) = self.byte_range_to_grapheme_indices(start, end);
+        GraphemeMatch::from_source(self, start, end)
+    }
+
+    /// Bracketed time signatures, so "page 1/2" is not mistaken for one.
+    pub fn time_signatures(&self) -> Vec<TimeSignature> {
+        TIME_SIGNATURE
+            .captures_iter(self.as_str())
+            .filter_map(|caps| {
+                let whole = caps.get(0)?;
+                Some(TimeSignature {
+                    beats: caps[1].parse().ok()?,
+                    unit: caps[2].parse().ok()?,
+                    annotation: EzStr::new(&caps[3]),
+                    matched: self.match_at_bytes(whole.start(), whole.end()),
+                })
+            })
+            .collect()
+    }
+
+    /// Tempo markings: a number followed by "BPM", optionally preceded by a note glyph and `≈`.
+    pub fn tempos(&self) -> Vec<Tempo> {
+        TEMPO
+            .captures_iter(self.as_str())
+            .filter_map(|caps| {
+                let whole = caps.get(0)?;
+                Some(Tempo {
+                    bpm: caps[3].parse().ok()?,
+                    note: caps.get(1).map(|m| EzStr::new(m.as_str().trim_end())),
+                    approximate: caps.get(2).is_some_and(|m| !m.as_str().starts_with('=')),
+                    matched: self.match_at_bytes(whole.start(), whole.end()),
+                })
+            })
+            .collect()
+    }
+
+    /// Chord symbols among the tokens separated by whitespace and bar lines. Every
+    /// token is checked whole, so this is meant for chord lines; a lone "A" in prose
+    /// also reads as a chord.
+    pub fn chords(&self) -> Vec<Chord> {
+        let data = self.as_str();
+        let mut chords = Vec::new();
+        let mut start = 0;
+        for token in data.split(|c: char| c.is_whitespace() || c == '|') {
+            let end = start + token.len();
+            let symbol = match token {
+                "" => None,
+                "N.C" | "N.C." | "NC" => Some(ChordSymbol::NoChord),
+                _ => CHORD.captures(token).map(|caps| ChordSymbol::Chord {
+                    root: EzStr::new(&caps[1]),
+                    quality: EzStr::new(&caps[2]),
+                    bass: caps.get(3).map(|m| EzStr::new(m.as_str())),
+                }),
+            };
+            if let Some(symbol) = symbol {
+                chords.push(Chord { symbol, matched: self.match_at_bytes(start, end) });
+            }
+            start = end + data[end..].chars().next().map_or(0, char::len_utf8);
+        }
+        chords
+    }
+}
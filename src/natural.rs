@@ -0,0 +1,54 @@
+use std::cmp::Ordering;
+
+use crate::{EzStr, GraphemeRef};
+
+fn is_digit(g: &GraphemeRef<'_>) -> bool {
+    g.as_str().len() == 1 && g.as_str().as_bytes()[0].is_ascii_digit()
+}
+
+/// Compares two runs of ASCII digits by value; with equal values the one with fewer
+/// leading zeros comes first.
+fn cmp_digits(a: &str, b: &str) -> Ordering {
+    let (a_trimmed, b_trimmed) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+impl EzStr {
+    /// Natural order: runs of digits compare by value and everything else grapheme by
+    /// grapheme, so "page 2/2" < "page 10/2" and "track 007" < "track 8". Numbers of
+    /// any length are compared without overflow.
+    pub fn cmp_natural<T: AsRef<str>>(&self, other: T) -> Ordering {
+        let other = EzStr::new(other.as_ref());
+        let mut a = self.iter().peekable();
+        let mut b = other.iter().peekable();
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) if is_digit(&x) && is_digit(&y) => {
+                    let mut x_run = String::from(x.as_str());
+                    let mut y_run = String::from(y.as_str());
+                    while let Some(g) = a.next_if(is_digit) {
+                        x_run += g.as_str();
+                    }
+                    while let Some(g) = b.next_if(is_digit) {
+                        y_run += g.as_str();
+                    }
+                    match cmp_digits(&x_run, &y_run) {
+                        Ordering::Equal => {}
+                        ord => return ord,
+                    }
+                }
+                (Some(x), Some(y)) => match x.cmp(&y) {
+                    Ordering::Equal => {}
+                    ord => return ord,
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{EzStr, GraphemeMatch};
+
+impl EzStr {
+    /// Char offset at which each grapheme starts, plus the total char count at the end.
+    fn char_offsets(&self) -> Vec<usize> {
+        let mut offsets = Vec::with_capacity(self.len() + 1);
+        let mut chars = 0;
+        for g in self.graphemes() {
+            offsets.push(chars);
+            chars += g.as_str().chars().count();
+        }
+        offsets.push(chars);
+        offsets
+    }
+
+    /// Consecutive groups of `size` graphemes; the last one may be shorter.
+    /// Panics if `size` is 0, like `slice::chunks`.
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = GraphemeMatch> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+        let offsets = self.char_offsets();
+        let len = self.len();
+        (0..len)
+            .step_by(size)
+            .map(move |start| GraphemeMatch::from_source_at(self, start, (start + size).min(len), offsets[start]))
+    }
+
+    /// Every run of `size` consecutive graphemes, overlapping; none if the string is shorter.
+    /// Panics if `size` is 0, like `slice::windows`.
+    pub fn windows(&self, size: usize) -> impl Iterator<Item = GraphemeMatch> + '_ {
+        assert!(size != 0, "window size must be non-zero");
+        let offsets = self.char_offsets();
+        let count = (self.len() + 1).saturating_sub(size);
+        (0..count).map(move |start| GraphemeMatch::from_source_at(self, start, start + size, offsets[start]))
+    }
+}
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+pub(crate) const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl EzStr {
+    /// Text of every run of `n` consecutive graphemes, like `windows` without the spans.
+    pub fn ngrams(&self, n: usize) -> impl Iterator<Item = EzStr> + '_ {
+        assert!(n != 0, "n-gram size must be non-zero");
+        let count = (self.len() + 1).saturating_sub(n);
+        (0..count).map(move |start| self.slice_strict(start, start + n))
+    }
+
+    /// 64-bit FNV-1a hash of each grapheme n-gram, in order. Each grapheme is followed by
+    /// an 0xFF byte, which never occurs in UTF-8, so the hashes are stable across platforms
+    /// and releases and depend on grapheme boundaries as well as bytes.
+    pub fn shingle_hashes(&self, n: usize) -> Vec<u64> {
+        assert!(n != 0, "n-gram size must be non-zero");
+        let graphemes = self.graphemes();
+        graphemes
+            .windows(n)
+            .map(|gram| {
+                let mut hash = FNV_OFFSET;
+                for g in gram {
+                    for &b in g.as_str().as_bytes().iter().chain(std::iter::once(&0xFF)) {
+                        hash = (hash ^ b as u64).wrapping_mul(FNV_PRIME);
+                    }
+                }
+                hash
+            })
+            .collect()
+    }
+}
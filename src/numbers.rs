@@ -0,0 +1,65 @@
+use std::any::Any;
+use std::num::{ParseFloatError, ParseIntError};
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{Error, EzStr, GraphemeMatch};
+
+static NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"-?[0-9]+(?:\.[0-9]+)?(?:[eE][-+]?[0-9]+)?").unwrap());
+
+/// A numeric literal found by `EzStr::extract_numbers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Number {
+    pub value: f64,
+    /// The value as an integer, when the literal has no fraction or exponent and fits in an `i64`.
+    pub integer: Option<i64>,
+    pub matched: GraphemeMatch,
+}
+
+impl EzStr {
+    /// Parses the whole text like `str::parse`. Integer and float failures keep their
+    /// specific `Error` variant; other types give `Error::Parse`.
+    pub fn parse<T>(&self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display + 'static,
+    {
+        self.as_str().parse::<T>().map_err(|e| {
+            let any = &e as &dyn Any;
+            if let Some(e) = any.downcast_ref::<ParseIntError>() {
+                Error::ParseInt(e.clone())
+            } else if let Some(e) = any.downcast_ref::<ParseFloatError>() {
+                Error::ParseFloat(e.clone())
+            } else {
+                Error::Parse(e.to_string())
+            }
+        })
+    }
+
+    /// Every integer or decimal literal with its span, so "♩≈117BPM" gives 117.
+    /// A leading `-` counts as a sign only when it does not follow a letter or digit,
+    /// so "1-2" gives 1 and 2.
+    pub fn extract_numbers(&self) -> Vec<Number> {
+        let data = self.as_str();
+        NUMBER
+            .find_iter(data)
+            .filter_map(|m| {
+                let mut start = m.start();
+                let prev = data[..start].chars().next_back();
+                if data[start..].starts_with('-') && prev.is_some_and(char::is_alphanumeric) {
+                    start += 1;
+                }
+                let literal = &data[start..m.end()];
+                let value = literal.parse::<f64>().ok()?;
+                let (g_start, g_end) = self.byte_range_to_grapheme_indices(start, m.end());
+                Some(Number {
+                    value,
+                    integer: literal.parse::<i64>().ok(),
+                    matched: GraphemeMatch::from_source(self, g_start, g_end),
+                })
+            })
+            .collect()
+    }
+}
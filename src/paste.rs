@@ -0,0 +1,75 @@
+use crate::whitespace::{is_newline, NewlineStyle};
+use crate::EzStr;
+
+/// Steps run by `EzStr::sanitize_paste`; all of them are on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasteOptions {
+    /// Drop a leading byte order mark.
+    pub strip_bom: bool,
+    /// Rewrite every line break in this style, or leave them alone with `None`.
+    pub newlines: Option<NewlineStyle>,
+    /// Drop control characters other than tab and line breaks, such as NUL, ESC or DEL.
+    pub remove_controls: bool,
+    /// Convert to NFC.
+    pub nfc: bool,
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        PasteOptions { strip_bom: true, newlines: Some(NewlineStyle::Lf), remove_controls: true, nfc: true }
+    }
+}
+
+/// What `EzStr::sanitize_paste` changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PasteReport {
+    pub bom_removed: bool,
+    /// Line breaks rewritten in another style.
+    pub newlines_changed: usize,
+    pub controls_removed: usize,
+    /// Whether NFC changed the text.
+    pub normalized: bool,
+}
+
+impl PasteReport {
+    /// True if the pasted text came out unchanged.
+    pub fn is_clean(&self) -> bool {
+        *self == PasteReport::default()
+    }
+}
+
+impl EzStr {
+    /// Cleans up text coming from the clipboard, running the steps enabled in `options`
+    /// in the order they are listed there, and reports what each of them did.
+    pub fn sanitize_paste(&self, options: PasteOptions) -> (EzStr, PasteReport) {
+        let mut report = PasteReport::default();
+        let mut text = self.clone();
+        if options.strip_bom && text.as_str().starts_with('\u{FEFF}') {
+            text = text.strip_bom().0;
+            report.bom_removed = true;
+        }
+        if let Some(style) = options.newlines {
+            report.newlines_changed = text.iter().filter(|g| is_newline(*g) && g.as_str() != style.as_str()).count();
+            if report.newlines_changed > 0 {
+                text = text.normalize_newlines(style).0;
+            }
+        }
+        if options.remove_controls {
+            let is_stray_control = |s: &str| {
+                let mut chars = s.chars();
+                matches!((chars.next(), chars.next()), (Some(c), None) if c.is_control() && c != '\t')
+                    && !matches!(s, "\n" | "\r" | "\u{85}")
+            };
+            report.controls_removed = text.iter().filter(|g| is_stray_control(g.as_str())).count();
+            if report.controls_removed > 0 {
+                text = text.filter_graphemes(|g| !is_stray_control(g.as_str())).0;
+            }
+        }
+        if options.nfc {
+            let normalized = text.to_nfc();
+            report.normalized = normalized != text;
+            text = normalized;
+        }
+        (text, report)
+    }
+}
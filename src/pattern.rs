@@ -0,0 +1,142 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::glob::{parse_glob, ClassItem, GlobToken};
+use crate::{Error, EzStr};
+
+/// One grapheme, as far as a regex can tell without `\X`: a regional indicator pair,
+/// or any char but a line break followed by its combining marks, emoji modifiers and
+/// zero-width-joined pictographs.
+const ONE_GRAPHEME: &str = r"(?:\p{gcb=RI}{2}|[^\r\n](?:\p{gcb=Extend}|\p{gcb=SpacingMark}|\p{gcb=ZWJ}\p{Extended_Pictographic}?)*)";
+
+/// Builds one `Regex` out of an alternation of literals, escaping each of them:
+/// `PatternBuilder::new().literal("A1").literal("N.C").build()` matches either chord.
+#[derive(Debug, Clone, Default)]
+pub struct PatternBuilder {
+    literals: Vec<String>,
+    case_insensitive: bool,
+    whole_words: bool,
+}
+
+impl PatternBuilder {
+    pub fn new() -> Self {
+        PatternBuilder::default()
+    }
+
+    pub fn literal<T: AsRef<str>>(mut self, literal: T) -> Self {
+        self.literals.push(literal.as_ref().to_string());
+        self
+    }
+
+    pub fn literals<I, T>(mut self, literals: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.literals.extend(literals.into_iter().map(|l| l.as_ref().to_string()));
+        self
+    }
+
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Only match literals whose first and last word chars are not directly preceded or
+    /// followed by another word char, so "A1" is not found in "A12".
+    pub fn whole_words(mut self, yes: bool) -> Self {
+        self.whole_words = yes;
+        self
+    }
+
+    /// The pattern source. Longer literals come first so that "A1" wins over "A" where
+    /// both match, and empty literals are dropped; with no literals the pattern
+    /// matches nothing.
+    pub fn pattern(&self) -> String {
+        let mut literals: Vec<&str> = self.literals.iter().map(String::as_str).filter(|l| !l.is_empty()).collect();
+        literals.sort_by_key(|l| std::cmp::Reverse(l.len()));
+        literals.dedup();
+        if literals.is_empty() {
+            return r"[^\s\S]".to_string();
+        }
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let alternatives: Vec<String> = literals
+            .iter()
+            .map(|l| {
+                let escaped = regex::escape(l);
+                if !self.whole_words {
+                    return escaped;
+                }
+                let start = if is_word(l.chars().next()) { r"\b" } else { "" };
+                let end = if is_word(l.chars().next_back()) { r"\b" } else { "" };
+                format!("{}{}{}", start, escaped, end)
+            })
+            .collect();
+        format!("(?:{})", alternatives.join("|"))
+    }
+
+    pub fn build(&self) -> Result<Regex, Error> {
+        Ok(RegexBuilder::new(&self.pattern()).case_insensitive(self.case_insensitive).build()?)
+    }
+}
+
+impl EzStr {
+    /// Regex source matching this text literally, e.g. `\|N\.C\|` for "|N.C|".
+    pub fn to_regex_literal(&self) -> EzStr {
+        EzStr::new(regex::escape(self.as_str()))
+    }
+}
+
+/// Regexes from user-facing pattern syntaxes.
+#[derive(Debug, Clone, Copy)]
+pub struct Pattern;
+
+impl Pattern {
+    /// Compiles a shell-style wildcard (see `EzStr::matches_glob`) into an unanchored
+    /// regex for the find APIs. `?` matches one whole grapheme, `*` any run of text
+    /// within a line, and everything else is escaped. In `[!...]` classes only
+    /// single-char graphemes and ranges are excluded. Fails with `Error::InvalidPattern`
+    /// when the regex grows past the compiled size limit, e.g. for hundreds of `?`.
+    pub fn from_glob<T: AsRef<str>>(glob: T) -> Result<Regex, Error> {
+        Ok(Regex::new(&glob_to_regex(glob.as_ref()))?)
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    for token in parse_glob(glob) {
+        match token {
+            GlobToken::Star => out += ".*",
+            GlobToken::One => out += ONE_GRAPHEME,
+            GlobToken::Literal(g) => out += &regex::escape(&g),
+            GlobToken::Class { negated: false, items } => {
+                let alternatives: Vec<String> = items
+                    .iter()
+                    .map(|item| match item {
+                        ClassItem::Grapheme(g) => regex::escape(g),
+                        ClassItem::Range(lo, hi) if lo > hi => r"[^\s\S]".to_string(),
+                        ClassItem::Range(lo, hi) => format!("[{}-{}]", class_char(*lo), class_char(*hi)),
+                    })
+                    .collect();
+                out += &format!("(?:{})", alternatives.join("|"));
+            }
+            GlobToken::Class { negated: true, items } => {
+                let mut class = String::from(r"[^\r\n");
+                for item in &items {
+                    match item {
+                        ClassItem::Grapheme(g) if g.chars().count() == 1 => class += &class_char(g.chars().next().unwrap()),
+                        ClassItem::Grapheme(_) => {}
+                        ClassItem::Range(lo, hi) if lo > hi => {}
+                        ClassItem::Range(lo, hi) => class += &format!("{}-{}", class_char(*lo), class_char(*hi)),
+                    }
+                }
+                out += &format!(r"{}](?:\p{{gcb=Extend}}|\p{{gcb=SpacingMark}})*", class);
+            }
+        }
+    }
+    out
+}
+
+/// `c` escaped for use inside a regex char class.
+fn class_char(c: char) -> String {
+    if c.is_ascii_punctuation() { format!("\\{}", c) } else { c.to_string() }
+}
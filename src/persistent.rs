@@ -0,0 +1,289 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{EzStr, GSpan};
+
+/// Graphemes per leaf when text is split up for the tree.
+const LEAF_GRAPHEMES: usize = 512;
+/// Depth at which an edit rebuilds the tree from its (shared) leaves.
+const MAX_DEPTH: usize = 48;
+
+#[derive(Debug)]
+enum Node {
+    Leaf(EzStr),
+    Branch { left: Arc<Node>, right: Arc<Node>, len: usize, byte_len: usize, depth: usize },
+}
+
+impl Node {
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(text) => text.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        match self {
+            Node::Leaf(text) => text.as_str().len(),
+            Node::Branch { byte_len, .. } => *byte_len,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Branch { depth, .. } => *depth,
+        }
+    }
+
+    fn push_leaves<'a>(self: &'a Arc<Node>, out: &mut Vec<&'a Arc<Node>>) {
+        match &**self {
+            Node::Leaf(_) => out.push(self),
+            Node::Branch { left, right, .. } => {
+                left.push_leaves(out);
+                right.push_leaves(out);
+            }
+        }
+    }
+}
+
+type Tree = Option<Arc<Node>>;
+
+fn join(left: Tree, right: Tree) -> Tree {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(left), Some(right)) => Some(Arc::new(Node::Branch {
+            len: left.len() + right.len(),
+            byte_len: left.byte_len() + right.byte_len(),
+            depth: left.depth().max(right.depth()) + 1,
+            left,
+            right,
+        })),
+    }
+}
+
+fn balanced(leaves: &[Arc<Node>]) -> Tree {
+    match leaves {
+        [] => None,
+        [leaf] => Some(leaf.clone()),
+        _ => {
+            let (left, right) = leaves.split_at(leaves.len() / 2);
+            join(balanced(left), balanced(right))
+        }
+    }
+}
+
+fn from_text(text: &EzStr) -> Tree {
+    let leaves: Vec<Arc<Node>> = text.chunks(LEAF_GRAPHEMES).map(|chunk| Arc::new(Node::Leaf(chunk.text))).collect();
+    balanced(&leaves)
+}
+
+/// Splits `tree` before grapheme `at`, sharing every node that is not cut.
+fn split(tree: &Tree, at: usize) -> (Tree, Tree) {
+    let Some(node) = tree else {
+        return (None, None);
+    };
+    if at == 0 {
+        return (None, Some(node.clone()));
+    }
+    if at >= node.len() {
+        return (Some(node.clone()), None);
+    }
+    match &**node {
+        Node::Leaf(text) => (
+            Some(Arc::new(Node::Leaf(text.slice_strict(0, at)))),
+            Some(Arc::new(Node::Leaf(text.slice_strict(at, text.len())))),
+        ),
+        Node::Branch { left, right, .. } => {
+            if at <= left.len() {
+                let (a, b) = split(&Some(left.clone()), at);
+                (a, join(b, Some(right.clone())))
+            } else {
+                let (a, b) = split(&Some(right.clone()), at - left.len());
+                (join(Some(left.clone()), a), b)
+            }
+        }
+    }
+}
+
+/// Byte offset of every grapheme start in `tree`, counted from `base`.
+fn leaf_starts(tree: &Tree, base: usize) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut at = base;
+    if let Some(node) = tree {
+        let mut leaves = Vec::new();
+        node.push_leaves(&mut leaves);
+        for leaf in leaves {
+            if let Node::Leaf(text) = &**leaf {
+                starts.extend(text.grapheme_index().iter().map(|start| at + start));
+                at += text.as_str().len();
+            }
+        }
+    }
+    starts
+}
+
+fn push_text(tree: &Tree, out: &mut String) {
+    if let Some(node) = tree {
+        let mut leaves = Vec::new();
+        node.push_leaves(&mut leaves);
+        for leaf in leaves {
+            if let Node::Leaf(text) = &**leaf {
+                *out += text.as_str();
+            }
+        }
+    }
+}
+
+/// Immutable text where every edit returns a new version sharing all untouched parts
+/// with the old one, so keeping each version for undo costs about the size of the edit.
+/// Versions are cheap to clone and safe to share between threads.
+///
+/// Text is kept in leaves of up to 512 graphemes under a balanced binary tree. Indices
+/// are grapheme indices. An edit segments the text again from the grapheme before it
+/// up to where the new boundaries line up with the old ones, so inserting a combining
+/// mark after "e" gives one grapheme and a flag stays paired after an inserted one.
+#[derive(Clone, Default)]
+pub struct EzStrPersistent {
+    root: Tree,
+}
+
+impl EzStrPersistent {
+    pub fn new<T: Into<EzStr>>(text: T) -> Self {
+        EzStrPersistent { root: from_text(&text.into()) }
+    }
+
+    /// Number of graphemes.
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.byte_len())
+    }
+
+    /// New version with the graphemes in `range` replaced by `replacement`.
+    /// Panics if `range` is out of bounds.
+    pub fn replace<S: Into<GSpan>, T: AsRef<str>>(&self, range: S, replacement: T) -> Self {
+        let range = range.into();
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "grapheme range {}..{} out of bounds for length {}",
+            range.start,
+            range.end,
+            self.len()
+        );
+        let (left, rest) = split(&self.root, range.start);
+        let (_, right) = split(&rest, range.end - range.start);
+        // the grapheme before the edit may absorb the start of the replacement
+        let (left, before) = split(&left, range.start.saturating_sub(1));
+        let mut head = String::new();
+        push_text(&before, &mut head);
+        head += replacement.as_ref();
+
+        // Take more graphemes after the edit until a new boundary lines up with an old
+        // one, past which the old segmentation still holds.
+        let right_len = right.as_ref().map_or(0, |node| node.len());
+        let mut take = 1;
+        let (middle, right) = loop {
+            let (after, rest) = split(&right, take);
+            let mut middle = head.clone();
+            let old_starts = leaf_starts(&after, head.len());
+            push_text(&after, &mut middle);
+            let middle = EzStr::new(middle);
+            let aligned = middle
+                .grapheme_index()
+                .iter()
+                .any(|start| start >= head.len() && start < middle.as_str().len() && old_starts.binary_search(&start).is_ok());
+            if aligned || take >= right_len {
+                break (middle, rest);
+            }
+            take *= 2;
+        };
+
+        let root = join(join(left, from_text(&middle)), right);
+        match root {
+            Some(node) if node.depth() > MAX_DEPTH => {
+                let mut leaves = Vec::new();
+                node.push_leaves(&mut leaves);
+                let leaves: Vec<Arc<Node>> = leaves.into_iter().cloned().collect();
+                EzStrPersistent { root: balanced(&leaves) }
+            }
+            root => EzStrPersistent { root },
+        }
+    }
+
+    pub fn insert<T: AsRef<str>>(&self, at: usize, text: T) -> Self {
+        self.replace(GSpan::new(at, at), text)
+    }
+
+    pub fn remove<S: Into<GSpan>>(&self, range: S) -> Self {
+        self.replace(range, "")
+    }
+
+    /// The graphemes in `range`, copied out. Panics if `range` is out of bounds.
+    pub fn slice<S: Into<GSpan>>(&self, range: S) -> EzStr {
+        let range = range.into();
+        assert!(range.start <= range.end && range.end <= self.len(), "grapheme range out of bounds");
+        let (_, rest) = split(&self.root, range.start);
+        let (middle, _) = split(&rest, range.end - range.start);
+        let mut out = String::new();
+        push_text(&middle, &mut out);
+        EzStr::new(out)
+    }
+
+    /// The leaves holding the text, in order. Leaves are shared between versions,
+    /// so this also shows which parts of two versions are the same allocation.
+    pub fn chunks(&self) -> impl Iterator<Item = &EzStr> {
+        let mut leaves = Vec::new();
+        if let Some(node) = &self.root {
+            node.push_leaves(&mut leaves);
+        }
+        leaves.into_iter().filter_map(|leaf| match &**leaf {
+            Node::Leaf(text) => Some(text),
+            Node::Branch { .. } => None,
+        })
+    }
+
+    pub fn to_ezstr(&self) -> EzStr {
+        let mut out = String::with_capacity(self.byte_len());
+        push_text(&self.root, &mut out);
+        EzStr::new(out)
+    }
+}
+
+impl From<EzStr> for EzStrPersistent {
+    fn from(text: EzStr) -> Self {
+        EzStrPersistent::new(text)
+    }
+}
+
+impl From<&str> for EzStrPersistent {
+    fn from(text: &str) -> Self {
+        EzStrPersistent::new(text)
+    }
+}
+
+impl fmt::Display for EzStrPersistent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk.as_str()))
+    }
+}
+
+impl fmt::Debug for EzStrPersistent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EzStrPersistent({:?})", self.to_ezstr().as_str())
+    }
+}
+
+impl PartialEq for EzStrPersistent {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.to_ezstr() == other.to_ezstr()
+    }
+}
+
+impl Eq for EzStrPersistent {}
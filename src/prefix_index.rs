@@ -0,0 +1,54 @@
+use unicode_segmentation::GraphemeCursor;
+
+use crate::segment;
+
+/// Grapheme start offsets found so far by segmenting a text from the front, for callers
+/// that only need the first few graphemes of a long text.
+#[derive(Debug, Clone)]
+pub(crate) struct PrefixIndex {
+    starts: Vec<usize>,
+    cursor: GraphemeCursor,
+    complete: bool,
+}
+
+impl PrefixIndex {
+    pub(crate) fn new(len: usize) -> Self {
+        PrefixIndex { starts: Vec::new(), cursor: GraphemeCursor::new(0, len, true), complete: len == 0 }
+    }
+
+    /// Segments `text` until `count` graphemes are known or the text is exhausted.
+    pub(crate) fn extend_to(&mut self, text: &str, count: usize) {
+        while self.starts.len() < count && !self.complete {
+            let at = self.cursor.cur_cursor();
+            self.starts.push(at);
+            let next = segment::next_boundary(text, &mut self.cursor, at);
+            self.cursor.set_cursor(next);
+            self.complete = next >= text.len();
+        }
+    }
+
+    /// Segments `text` until the grapheme containing `byte` is known.
+    pub(crate) fn extend_past(&mut self, text: &str, byte: usize) {
+        while !self.complete && self.cursor.cur_cursor() <= byte {
+            self.extend_to(text, self.starts.len() + 1);
+        }
+    }
+
+    /// Byte offset of grapheme `index`, the text length for the index one past the last
+    /// grapheme, and `None` beyond that.
+    pub(crate) fn byte_of(&mut self, text: &str, index: usize) -> Option<usize> {
+        self.extend_to(text, index.saturating_add(1));
+        match self.starts.get(index) {
+            Some(&b) => Some(b),
+            None => (self.complete && index == self.starts.len()).then_some(text.len()),
+        }
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub(crate) fn starts(&self) -> &[usize] {
+        &self.starts
+    }
+}
@@ -0,0 +1,91 @@
+use std::ops::Range;
+
+use crate::{EzStr, GSpan, GraphemeMatch};
+
+/// What the column range of `EzStr::rect` and `EzStr::replace_rect` counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColumnUnit {
+    /// Graphemes from the start of the line.
+    #[default]
+    Graphemes,
+    /// Display columns, as in a terminal: a wide grapheme takes two. A grapheme
+    /// belongs to the columns it starts in. Tabs are not expanded, so run
+    /// `expand_tabs` first on tab-aligned text.
+    Width,
+}
+
+impl EzStr {
+    /// The block of text in `cols` on each of `lines` (0-based, without terminators),
+    /// one `EzStr` per line, as selected with an editor's block selection. Lines too
+    /// short to reach `cols` give shorter or empty rows; `lines` is clamped to the
+    /// number of lines.
+    pub fn rect(&self, lines: Range<usize>, cols: Range<usize>, unit: ColumnUnit) -> Vec<EzStr> {
+        let all = self.lines_with_terminators();
+        let lines = lines.start.min(all.len())..lines.end.min(all.len());
+        all[lines]
+            .iter()
+            .map(|line| {
+                let (cell, _) = rect_cell(&line.content, &cols, unit);
+                self.slice_strict(cell.start, cell.end)
+            })
+            .collect()
+    }
+
+    /// Inverse of `rect`: copy with the block in `cols` on each of `lines` replaced by
+    /// the matching entry of `rows`, which should not contain line breaks. Lines too
+    /// short to reach `cols.start` are padded with spaces before a non-empty row. Panics
+    /// if `lines` goes past the last line or `rows` has a different length.
+    pub fn replace_rect<T: AsRef<str>>(&self, lines: Range<usize>, cols: Range<usize>, unit: ColumnUnit, rows: &[T]) -> EzStr {
+        let all = self.lines_with_terminators();
+        assert!(
+            lines.start <= lines.end && lines.end <= all.len(),
+            "line range {}..{} out of bounds for {} lines",
+            lines.start,
+            lines.end,
+            all.len()
+        );
+        assert_eq!(rows.len(), lines.len(), "one row is needed per line of the block");
+        let mut out = self.clone();
+        // right to left, so the spans of the lines still to edit stay valid
+        for (line, row) in all[lines].iter().zip(rows).rev() {
+            let (cell, pad) = rect_cell(&line.content, &cols, unit);
+            let row = row.as_ref();
+            let replacement = if row.is_empty() { String::new() } else { format!("{}{}", " ".repeat(pad), row) };
+            out.replace_range(cell, replacement);
+        }
+        out
+    }
+}
+
+/// Document span of the graphemes of `line` within `cols`, and how many columns the
+/// line falls short of `cols.start`.
+fn rect_cell(line: &GraphemeMatch, cols: &Range<usize>, unit: ColumnUnit) -> (GSpan, usize) {
+    let graphemes = line.text.graphemes();
+    let (start, end, pad) = match unit {
+        ColumnUnit::Graphemes => {
+            let len = graphemes.len();
+            let start = cols.start.min(len);
+            (start, cols.end.min(len).max(start), cols.start.saturating_sub(len))
+        }
+        ColumnUnit::Width => {
+            let mut col = 0;
+            let mut start = None;
+            let mut end = graphemes.len();
+            for (i, g) in graphemes.iter().enumerate() {
+                if col >= cols.start && start.is_none() {
+                    start = Some(i);
+                }
+                if col >= cols.end {
+                    end = i;
+                    break;
+                }
+                col += g.display_width();
+            }
+            match start {
+                Some(start) => (start, end.max(start), 0),
+                None => (graphemes.len(), graphemes.len(), cols.start.saturating_sub(col)),
+            }
+        }
+    };
+    (GSpan::new(line.start + start, line.start + end), pad)
+}
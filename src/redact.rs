@@ -0,0 +1,103 @@
+use regex::Regex;
+
+use crate::{EzStr, GSpan, Grapheme, SpanSet};
+
+/// How `EzStr::mask` and `EzStr::redact_spans` cover the hidden text. Built from a
+/// `&str`, `char` or `Grapheme`, so `text.mask(&re, '*')` works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mask {
+    grapheme: EzStr,
+    keep_width: bool,
+}
+
+impl Mask {
+    /// `grapheme` is repeated over the hidden text, by default once per grapheme.
+    pub fn new<T: Into<EzStr>>(grapheme: T) -> Self {
+        Mask { grapheme: grapheme.into(), keep_width: false }
+    }
+
+    /// Repeat the mask to cover the display width of the hidden text instead, so
+    /// masked table cells and chord grids keep their columns. A leftover column, such
+    /// as when a wide mask covers an odd width, is filled with a space.
+    pub fn keep_width(mut self, yes: bool) -> Self {
+        self.keep_width = yes;
+        self
+    }
+
+    fn cover(&self, hidden: &[Grapheme], out: &mut String) {
+        if !self.keep_width {
+            hidden.iter().for_each(|_| *out += self.grapheme.as_str());
+            return;
+        }
+        let width: usize = hidden.iter().map(Grapheme::display_width).sum();
+        let mask_width = self.grapheme.display_width().max(1);
+        *out += &self.grapheme.as_str().repeat(width / mask_width);
+        *out += &" ".repeat(width % mask_width);
+    }
+}
+
+impl Default for Mask {
+    fn default() -> Self {
+        Mask::new("*")
+    }
+}
+
+impl From<&str> for Mask {
+    fn from(grapheme: &str) -> Self {
+        Mask::new(grapheme)
+    }
+}
+
+impl From<char> for Mask {
+    fn from(grapheme: char) -> Self {
+        Mask::new(grapheme)
+    }
+}
+
+impl From<Grapheme> for Mask {
+    fn from(grapheme: Grapheme) -> Self {
+        Mask::new(grapheme.as_str())
+    }
+}
+
+impl EzStr {
+    /// Copy with every match of `regex` hidden behind `mask`, for logging user content
+    /// without leaking emails, phone numbers and the like. Matches are widened to
+    /// whole graphemes.
+    pub fn mask<M: Into<Mask>>(&self, regex: &Regex, mask: M) -> EzStr {
+        let mut spans = SpanSet::new();
+        self.find_iter(regex).for_each(|m| spans.insert(m.span()));
+        self.hide(&spans, &mask.into())
+    }
+
+    /// Copy with the graphemes in `spans` hidden behind `mask`. Spans may overlap and
+    /// come in any order; overlapping or touching spans are masked as one. Panics if a
+    /// span is out of bounds.
+    pub fn redact_spans<M: Into<Mask>>(&self, spans: &[GSpan], mask: M) -> EzStr {
+        let mut set = SpanSet::new();
+        for &span in spans {
+            assert!(
+                span.start <= span.end && span.end <= self.len(),
+                "grapheme range {}..{} out of bounds for length {}",
+                span.start,
+                span.end,
+                self.len()
+            );
+            set.insert(span);
+        }
+        self.hide(&set, &mask.into())
+    }
+
+    fn hide(&self, spans: &SpanSet, mask: &Mask) -> EzStr {
+        let graphemes = self.graphemes();
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut pos = 0;
+        for span in spans.iter() {
+            out += &self.as_str()[self.grapheme_to_byte(pos)..self.grapheme_to_byte(span.start)];
+            mask.cover(&graphemes[span.range()], &mut out);
+            pos = span.end;
+        }
+        out += &self.as_str()[self.grapheme_to_byte(pos)..];
+        EzStr::new(out)
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+pub(crate) const INLINE_CAP: usize = 22;
+
+/// Backing text of an `EzStr`: owned, borrowed for `'static` without a copy, or
+/// stored inline when it is at most `INLINE_CAP` bytes.
+#[derive(Clone)]
+pub(crate) enum Repr {
+    Owned(String),
+    Static(&'static str),
+    Inline { len: u8, buf: [u8; INLINE_CAP] },
+}
+
+impl Repr {
+    /// Copies `s`, inline when it is short enough.
+    pub(crate) fn small(s: &str) -> Repr {
+        if s.len() <= INLINE_CAP {
+            let mut buf = [0; INLINE_CAP];
+            buf[..s.len()].copy_from_slice(s.as_bytes());
+            Repr::Inline { len: s.len() as u8, buf }
+        } else {
+            Repr::Owned(s.to_string())
+        }
+    }
+
+    /// Takes `s`, moving short strings inline so the allocation is released.
+    pub(crate) fn from_string(s: String) -> Repr {
+        if s.len() <= INLINE_CAP { Repr::small(&s) } else { Repr::Owned(s) }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            Repr::Owned(s) => s,
+            Repr::Static(s) => s,
+            // the buffer is only ever filled from a &str
+            Repr::Inline { len, buf } => unsafe { std::str::from_utf8_unchecked(&buf[..*len as usize]) },
+        }
+    }
+
+    pub(crate) fn into_string(self) -> String {
+        match self {
+            Repr::Owned(s) => s,
+            other => other.as_str().to_string(),
+        }
+    }
+
+    /// The bytes `start..end`, still borrowed when the text is static.
+    pub(crate) fn slice(&self, start: usize, end: usize) -> Repr {
+        match self {
+            Repr::Static(s) => Repr::Static(&s[start..end]),
+            other => Repr::small(&other.as_str()[start..end]),
+        }
+    }
+}
+
+impl Deref for Repr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for Repr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Hash for Repr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Display for Repr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for Repr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{EzStr, GraphemeMatch, GraphemeRef};
+
+/// Coarse classification of a grapheme, for use with `EzStr::runs_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphemeClass {
+    Whitespace,
+    Digit,
+    Letter,
+    /// Punctuation, symbols, emoji and anything else visible.
+    Symbol,
+    Control,
+}
+
+impl GraphemeClass {
+    pub fn of(g: GraphemeRef<'_>) -> GraphemeClass {
+        if g.is_whitespace() {
+            GraphemeClass::Whitespace
+        } else if g.is_numeric() {
+            GraphemeClass::Digit
+        } else if g.is_alphabetic() {
+            GraphemeClass::Letter
+        } else if g.as_str().chars().all(char::is_control) {
+            GraphemeClass::Control
+        } else {
+            GraphemeClass::Symbol
+        }
+    }
+}
+
+impl EzStr {
+    /// Maximal runs of consecutive graphemes that `class` maps to the same value, with
+    /// their spans: `runs_by(GraphemeClass::of)` splits "A1 C1" into letter, digit,
+    /// whitespace, letter, digit.
+    pub fn runs_by<'a, K, F>(&'a self, mut class: F) -> impl Iterator<Item = (K, GraphemeMatch)> + 'a
+    where
+        K: PartialEq + 'a,
+        F: FnMut(GraphemeRef<'_>) -> K + 'a,
+    {
+        let mut graphemes = self.iter().map(move |g| (class(g), g.as_str().chars().count())).peekable();
+        let (mut start, mut char_start) = (0, 0);
+        std::iter::from_fn(move || {
+            let (key, mut chars) = graphemes.next()?;
+            let mut end = start + 1;
+            while let Some((_, n)) = graphemes.next_if(|(next, _)| *next == key) {
+                chars += n;
+                end += 1;
+            }
+            let run = GraphemeMatch::from_source_at(self, start, end, char_start);
+            start = end;
+            char_start += chars;
+            Some((key, run))
+        })
+    }
+}
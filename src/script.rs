@@ -0,0 +1,178 @@
+use crate::{EzStr, Grapheme, GraphemeRef};
+
+/// Unicode script of a grapheme, as used by `Grapheme::script` and `EzStr::script_runs`.
+///
+/// Letters of scripts without their own variant are reported as `Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Thai,
+    Georgian,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Han,
+    /// Shared by all scripts: digits, punctuation, symbols, spaces.
+    Common,
+    /// Combining marks and joiners that take the script of their base.
+    Inherited,
+    Other,
+}
+
+// Sorted, non-overlapping; anything not listed is Common (or Other for letters).
+const SCRIPT_TABLE: &[(u32, u32, Script)] = &[
+    (0x0041, 0x005A, Script::Latin), (0x0061, 0x007A, Script::Latin),
+    (0x00AA, 0x00AA, Script::Latin), (0x00BA, 0x00BA, Script::Latin),
+    (0x00C0, 0x00D6, Script::Latin), (0x00D8, 0x00F6, Script::Latin),
+    (0x00F8, 0x02B8, Script::Latin), (0x02E0, 0x02E4, Script::Latin),
+    (0x0300, 0x036F, Script::Inherited),
+    (0x0370, 0x0373, Script::Greek), (0x0375, 0x0377, Script::Greek),
+    (0x037A, 0x037D, Script::Greek), (0x037F, 0x037F, Script::Greek),
+    (0x0384, 0x0384, Script::Greek), (0x0386, 0x0386, Script::Greek),
+    (0x0388, 0x03FF, Script::Greek),
+    (0x0400, 0x0484, Script::Cyrillic), (0x0485, 0x0486, Script::Inherited),
+    (0x0487, 0x052F, Script::Cyrillic),
+    (0x0531, 0x058F, Script::Armenian),
+    (0x0591, 0x05FF, Script::Hebrew),
+    (0x0600, 0x064A, Script::Arabic), (0x064B, 0x0655, Script::Inherited),
+    (0x0656, 0x066F, Script::Arabic), (0x0670, 0x0670, Script::Inherited),
+    (0x0671, 0x06FF, Script::Arabic), (0x0750, 0x077F, Script::Arabic),
+    (0x08A0, 0x08FF, Script::Arabic),
+    (0x0900, 0x0950, Script::Devanagari), (0x0951, 0x0954, Script::Inherited),
+    (0x0955, 0x097F, Script::Devanagari),
+    (0x0980, 0x09FF, Script::Bengali),
+    (0x0E00, 0x0E7F, Script::Thai),
+    (0x10A0, 0x10FF, Script::Georgian),
+    (0x1100, 0x11FF, Script::Hangul),
+    (0x1AB0, 0x1AFF, Script::Inherited),
+    (0x1D00, 0x1D25, Script::Latin), (0x1D26, 0x1D2A, Script::Greek),
+    (0x1D2C, 0x1D5C, Script::Latin), (0x1D62, 0x1D65, Script::Latin),
+    (0x1D6B, 0x1D77, Script::Latin), (0x1D79, 0x1DBE, Script::Latin),
+    (0x1DC0, 0x1DFF, Script::Inherited),
+    (0x1E00, 0x1EFF, Script::Latin), (0x1F00, 0x1FFE, Script::Greek),
+    (0x200C, 0x200D, Script::Inherited),
+    (0x2071, 0x2071, Script::Latin), (0x207F, 0x207F, Script::Latin),
+    (0x2090, 0x209C, Script::Latin),
+    (0x20D0, 0x20FF, Script::Inherited),
+    (0x2126, 0x2126, Script::Greek), (0x212A, 0x212B, Script::Latin),
+    (0x2132, 0x2132, Script::Latin), (0x214E, 0x214E, Script::Latin),
+    (0x2160, 0x2188, Script::Latin), (0x2C60, 0x2C7F, Script::Latin),
+    (0x2D00, 0x2D2F, Script::Georgian), (0x2DE0, 0x2DFF, Script::Cyrillic),
+    (0x2E80, 0x2FDF, Script::Han),
+    (0x3005, 0x3005, Script::Han), (0x3007, 0x3007, Script::Han),
+    (0x3021, 0x3029, Script::Han), (0x302A, 0x302D, Script::Inherited),
+    (0x3038, 0x303B, Script::Han),
+    (0x3041, 0x3096, Script::Hiragana), (0x3099, 0x309A, Script::Inherited),
+    (0x309D, 0x309F, Script::Hiragana),
+    (0x30A1, 0x30FA, Script::Katakana), (0x30FD, 0x30FF, Script::Katakana),
+    (0x3131, 0x318E, Script::Hangul),
+    (0x31F0, 0x31FF, Script::Katakana), (0x32D0, 0x32FE, Script::Katakana),
+    (0x3300, 0x3357, Script::Katakana),
+    (0x3400, 0x4DBF, Script::Han), (0x4E00, 0x9FFF, Script::Han),
+    (0xA640, 0xA69F, Script::Cyrillic),
+    (0xA722, 0xA787, Script::Latin), (0xA78B, 0xA7FF, Script::Latin),
+    (0xA960, 0xA97F, Script::Hangul),
+    (0xAB30, 0xAB5A, Script::Latin), (0xAB5C, 0xAB64, Script::Latin),
+    (0xAC00, 0xD7FF, Script::Hangul),
+    (0xF900, 0xFAFF, Script::Han),
+    (0xFB00, 0xFB06, Script::Latin), (0xFB1D, 0xFB4F, Script::Hebrew),
+    (0xFB50, 0xFDFF, Script::Arabic),
+    (0xFE00, 0xFE0F, Script::Inherited), (0xFE20, 0xFE2F, Script::Inherited),
+    (0xFE70, 0xFEFF, Script::Arabic),
+    (0xFF21, 0xFF3A, Script::Latin), (0xFF41, 0xFF5A, Script::Latin),
+    (0xFF66, 0xFF6F, Script::Katakana), (0xFF71, 0xFF9D, Script::Katakana),
+    (0xFFA0, 0xFFDC, Script::Hangul),
+    (0x1D167, 0x1D169, Script::Inherited), (0x1D17B, 0x1D182, Script::Inherited),
+    (0x1D185, 0x1D18B, Script::Inherited), (0x1D1AA, 0x1D1AD, Script::Inherited),
+    (0x20000, 0x323AF, Script::Han),
+    (0xE0100, 0xE01EF, Script::Inherited),
+];
+
+/// Script of a single code point.
+pub fn char_script(c: char) -> Script {
+    let cp = c as u32;
+    let found = SCRIPT_TABLE.binary_search_by(|&(lo, hi, _)| {
+        if hi < cp {
+            std::cmp::Ordering::Less
+        } else if lo > cp {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    });
+    match found {
+        Ok(i) => SCRIPT_TABLE[i].2,
+        Err(_) if c.is_alphabetic() => Script::Other,
+        Err(_) => Script::Common,
+    }
+}
+
+impl Grapheme {
+    /// Script of the cluster: that of its first char with a specific script, else
+    /// `Common` (or `Inherited` for a cluster made only of combining marks).
+    pub fn script(&self) -> Script {
+        self.as_grapheme_ref().script()
+    }
+}
+
+impl GraphemeRef<'_> {
+    /// Script of the cluster; see `Grapheme::script`.
+    pub fn script(&self) -> Script {
+        let mut ret = None;
+        for c in self.as_str().chars() {
+            match char_script(c) {
+                Script::Common => ret = Some(Script::Common),
+                Script::Inherited => {
+                    ret.get_or_insert(Script::Inherited);
+                }
+                script => return script,
+            }
+        }
+        ret.unwrap_or(Script::Common)
+    }
+}
+
+/// A maximal run of graphemes sharing a script, as returned by `EzStr::script_runs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptRun {
+    pub script: Script,
+    pub start: usize,
+    pub end: usize,
+    pub text: EzStr,
+}
+
+impl EzStr {
+    /// Splits the string into runs of one script. Common and Inherited graphemes
+    /// join the run before them (or the first run, at the start of the string).
+    pub fn script_runs(&self) -> Vec<ScriptRun> {
+        let mut runs: Vec<(Script, usize, usize)> = Vec::new();
+        for (i, g) in self.graphemes().iter().enumerate() {
+            let script = g.script();
+            let neutral = matches!(script, Script::Common | Script::Inherited);
+            match runs.last_mut() {
+                Some(last) if neutral || last.0 == script => last.2 = i + 1,
+                Some(last) if matches!(last.0, Script::Common | Script::Inherited) => {
+                    // a leading neutral run takes the first real script
+                    last.0 = script;
+                    last.2 = i + 1;
+                }
+                _ => runs.push((script, i, i + 1)),
+            }
+        }
+        runs.into_iter()
+            .map(|(script, start, end)| ScriptRun {
+                script,
+                start,
+                end,
+                text: self.slice_strict(start, end),
+            })
+            .collect()
+    }
+}
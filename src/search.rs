@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::{EzStr, GSpan, GraphemeMatch};
+
+/// A match with the text around it, from `EzStr::find_with_context`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextMatch {
+    pub matched: GraphemeMatch,
+    /// Context ending where the match starts.
+    pub before: GraphemeMatch,
+    /// Context starting where the match ends.
+    pub after: GraphemeMatch,
+}
+
+/// A match from `EzStr::find_iter_lines`, in line and document coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// 1-based, as shown by editors and grep.
+    pub line_number: usize,
+    /// Grapheme span within the line.
+    pub in_line: GSpan,
+    /// The match with its grapheme span in the whole text.
+    pub matched: GraphemeMatch,
+}
+
+impl EzStr {
+    /// Finds every match of `regex` together with grep-style context: the rest of the
+    /// match's lines plus `before` whole lines above and `after` whole lines below.
+    /// Line terminators at the edges of the context are not included.
+    pub fn find_with_context(&self, regex: &Regex, before: usize, after: usize) -> Vec<ContextMatch> {
+        let starts = self.line_starts();
+        let len = self.len();
+        let line_of = |idx: usize| starts.partition_point(|&s| s <= idx) - 1;
+        // end of a line's content, before its terminator
+        let line_end = |line: usize| match starts.get(line + 1) {
+            Some(&next) => next - 1,
+            None => len,
+        };
+        self.find_iter(regex)
+            .map(|m| {
+                let first = line_of(m.start).saturating_sub(before);
+                let last = (line_of(m.end.saturating_sub(1).max(m.start)) + after).min(starts.len() - 1);
+                let context_start = starts[first].min(m.start);
+                let context_end = line_end(last).max(m.end);
+                ContextMatch {
+                    before: GraphemeMatch::from_source(self, context_start, m.start),
+                    after: GraphemeMatch::from_source(self, m.end, context_end),
+                    matched: m,
+                }
+            })
+            .collect()
+    }
+
+    /// Like `find_with_context`, with `before` and `after` counted in graphemes rather than lines.
+    pub fn find_with_grapheme_context(&self, regex: &Regex, before: usize, after: usize) -> Vec<ContextMatch> {
+        let len = self.len();
+        self.find_iter(regex)
+            .map(|m| ContextMatch {
+                before: GraphemeMatch::from_source(self, m.start.saturating_sub(before), m.start),
+                after: GraphemeMatch::from_source(self, m.end, (m.end + after).min(len)),
+                matched: m,
+            })
+            .collect()
+    }
+
+    /// Runs `regex` on each line separately, without its terminator, so `^` and `$`
+    /// anchor at line edges and no match spans two lines.
+    pub fn find_iter_lines(&self, regex: &Regex) -> Vec<LineMatch> {
+        let data = self.as_str();
+        let starts = self.line_starts();
+        let mut matches = Vec::new();
+        let mut line_byte = 0;
+        for (line, text) in data.split_inclusive('\n').enumerate() {
+            let content = text.trim_end_matches(['\n', '\r']);
+            for m in regex.find_iter(content) {
+                let (start, end) = self.byte_range_to_grapheme_indices(line_byte + m.start(), line_byte + m.end());
+                matches.push(LineMatch {
+                    line_number: line + 1,
+                    in_line: GSpan::new(start - starts[line], end - starts[line]),
+                    matched: GraphemeMatch::from_source(self, start, end),
+                });
+            }
+            line_byte += text.len();
+        }
+        matches
+    }
+
+    /// First match starting at or after grapheme `start`, like `Regex::find_at`: the
+    /// text before `start` is still visible to `^` and `\b`. `None` past the end.
+    pub fn find_at(&self, regex: &Regex, start: usize) -> Option<GraphemeMatch> {
+        let byte = self.try_grapheme_to_byte(start)?;
+        regex.find_at(self.as_str(), byte).map(|m| {
+            let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+            GraphemeMatch::from_source(self, g_start, g_end)
+        })
+    }
+
+    /// Matches lying within the grapheme `range`, with spans in the whole text. Nothing
+    /// is copied: the text after the range is cut off, so `$` matches at its end, while
+    /// the text before it still gives context as in `find_at`. The range is clamped to
+    /// `len()`.
+    pub fn find_in<'a, S: Into<GSpan>>(&'a self, regex: &'a Regex, range: S) -> impl Iterator<Item = GraphemeMatch> + 'a {
+        let range = range.into();
+        let end = self.grapheme_to_byte(range.end);
+        let haystack = &self.as_str()[..end];
+        let mut pos = Some(self.grapheme_to_byte(range.start).min(end));
+        std::iter::from_fn(move || {
+            let m = regex.find_at(haystack, pos?)?;
+            pos = if m.is_empty() {
+                haystack[m.end()..].chars().next().map(|c| m.end() + c.len_utf8())
+            } else {
+                Some(m.end())
+            };
+            let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+            Some(GraphemeMatch::from_source(self, g_start, g_end))
+        })
+    }
+
+    /// What `regex.replace_all` would do, as (old span, new text) pairs in order, without
+    /// building the replaced string. `replacement` is expanded like in `Regex::replace_all`,
+    /// so `$1` and `${name}` refer to capture groups.
+    pub fn preview_replace<R: AsRef<str>>(&self, regex: &Regex, replacement: R) -> Vec<(GraphemeMatch, EzStr)> {
+        regex
+            .captures_iter(self.as_str())
+            .map(|caps| {
+                let m = caps.get(0).unwrap();
+                let (g_start, g_end) = self.byte_range_to_grapheme_indices(m.start(), m.end());
+                let mut new_text = String::new();
+                caps.expand(replacement.as_ref(), &mut new_text);
+                (GraphemeMatch::from_source(self, g_start, g_end), EzStr::new(new_text))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// Length of the ASCII prefix of `bytes`, checked eight bytes at a time.
+fn ascii_prefix_len(bytes: &[u8]) -> usize {
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+    let mut i = 0;
+    while i + 8 <= bytes.len() {
+        let word = u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        if word & HIGH_BITS != 0 {
+            break;
+        }
+        i += 8;
+    }
+    i + bytes[i..].iter().take_while(|b| b.is_ascii()).count()
+}
+
+/// End of the grapheme starting at byte `at`. Two adjacent ASCII chars are always
+/// separate graphemes unless they are CR LF, so only non-ASCII text needs UAX #29.
+pub(crate) fn next_boundary(text: &str, cursor: &mut GraphemeCursor, at: usize) -> usize {
+    let bytes = text.as_bytes();
+    match (bytes[at], bytes.get(at + 1)) {
+        (b'\r', Some(b'\n')) => at + 2,
+        (b, Some(next)) if b.is_ascii() && next.is_ascii() => at + 1,
+        (b, None) if b.is_ascii() => at + 1,
+        _ => {
+            cursor.set_cursor(at);
+            cursor.next_boundary(text, 0).ok().flatten().unwrap_or(text.len())
+        }
+    }
+}
+
+/// Byte offset at which each grapheme of `text` starts. ASCII runs are split byte by
+/// byte; segmentation only runs from the last char before a non-ASCII region.
+pub(crate) fn grapheme_starts(text: &str) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    // every grapheme starts a char, so the char count bounds the number of starts
+    let chars = bytes.iter().filter(|&&b| (b as i8) >= -0x40).count();
+    let mut starts = Vec::with_capacity(chars);
+    let mut cursor = GraphemeCursor::new(0, text.len(), true);
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let run_end = pos + ascii_prefix_len(&bytes[pos..]);
+        // the last ASCII char may combine with what follows
+        let stop = if run_end == bytes.len() { run_end } else { run_end.saturating_sub(1).max(pos) };
+        while pos < stop {
+            starts.push(pos);
+            pos += if bytes[pos] == b'\r' && bytes.get(pos + 1) == Some(&b'\n') { 2 } else { 1 };
+        }
+        if pos < bytes.len() {
+            starts.push(pos);
+            cursor.set_cursor(pos);
+            pos = cursor.next_boundary(text, 0).ok().flatten().unwrap_or(text.len());
+        }
+    }
+    starts.shrink_to_fit();
+    starts
+}
+
+/// Decides where graphemes start, for `EzStr::with_segmenter`. Renderers disagree on
+/// cluster rules, so tailored segmentation (Indic syllables, custom joiners) can be
+/// plugged in here.
+pub trait Segmenter {
+    /// Ascending byte offsets at which each grapheme of `text` starts, on char
+    /// boundaries; the first is 0 unless `text` is empty.
+    fn grapheme_starts(&self, text: &str) -> Vec<usize>;
+}
+
+/// UAX #29 extended grapheme clusters, what every `EzStr` uses by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtendedGraphemes;
+
+/// UAX #29 legacy grapheme clusters: spacing marks and prepended chars are separate
+/// graphemes, as some terminals render them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LegacyGraphemes;
+
+impl Segmenter for ExtendedGraphemes {
+    fn grapheme_starts(&self, text: &str) -> Vec<usize> {
+        grapheme_starts(text)
+    }
+}
+
+impl Segmenter for LegacyGraphemes {
+    fn grapheme_starts(&self, text: &str) -> Vec<usize> {
+        text.grapheme_indices(false).map(|(i, _)| i).collect()
+    }
+}
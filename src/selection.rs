@@ -0,0 +1,192 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Error, EzStr, GSpan};
+
+/// A selection in grapheme units, as in an editor: `anchor` stays where the selection
+/// started and `head` is where the caret is. A caret without selection has both equal.
+///
+/// A selection does not hold its text; methods that need it take the `EzStr`, and
+/// `validate` or `clamp` keep it in bounds after the text changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Selection {
+    pub anchor: usize,
+    pub head: usize,
+}
+
+impl Selection {
+    pub fn new(anchor: usize, head: usize) -> Self {
+        Selection { anchor, head }
+    }
+
+    /// A caret at `at`, selecting nothing.
+    pub fn caret(at: usize) -> Self {
+        Selection { anchor: at, head: at }
+    }
+
+    pub fn start(&self) -> usize {
+        self.anchor.min(self.head)
+    }
+
+    pub fn end(&self) -> usize {
+        self.anchor.max(self.head)
+    }
+
+    /// The selected graphemes, whichever way the selection was made.
+    pub fn span(&self) -> GSpan {
+        GSpan::new(self.start(), self.end())
+    }
+
+    pub fn is_caret(&self) -> bool {
+        self.anchor == self.head
+    }
+
+    /// Whether the head is before the anchor, as after selecting with Shift+Left.
+    pub fn is_backward(&self) -> bool {
+        self.head < self.anchor
+    }
+
+    /// `Error::OutOfBounds` unless both ends are within `0..=text.len()`.
+    pub fn validate(&self, text: &EzStr) -> Result<(), Error> {
+        if self.end() > text.len() {
+            return Err(Error::OutOfBounds { start: self.start(), end: self.end(), len: text.len() });
+        }
+        Ok(())
+    }
+
+    /// Both ends moved into `0..=text.len()`.
+    pub fn clamp(&self, text: &EzStr) -> Self {
+        Selection::new(self.anchor.min(text.len()), self.head.min(text.len()))
+    }
+
+    /// Moves the caret to `at`, dropping the selection.
+    pub fn move_to(&self, at: usize) -> Self {
+        Selection::caret(at)
+    }
+
+    /// Moves the head to `at`, keeping the anchor, as with Shift+click.
+    pub fn extend_to(&self, at: usize) -> Self {
+        Selection::new(self.anchor, at)
+    }
+
+    /// A caret at the start or end of the selection, as Left or Right does with one.
+    pub fn collapse(&self, to_end: bool) -> Self {
+        Selection::caret(if to_end { self.end() } else { self.start() })
+    }
+
+    /// Moves the head to the end of the next word, or the start of the previous one,
+    /// keeping the anchor (Ctrl+Shift+Right or Left).
+    pub fn extend_by_word(&self, text: &EzStr, forward: bool) -> Self {
+        let head = if forward { text.next_word_boundary(self.head) } else { text.prev_word_boundary(self.head) };
+        self.extend_to(head)
+    }
+
+    /// Moves the head to the start of the next line, or to the start of its line (or
+    /// the previous one if already there), keeping the anchor (Shift+Down or Up).
+    pub fn extend_by_line(&self, text: &EzStr, forward: bool) -> Self {
+        let head = if forward {
+            let end = text.line_end(self.head);
+            if end < text.len() { end + 1 } else { end }
+        } else {
+            match text.line_start(self.head) {
+                start if start < self.head => start,
+                start => text.line_start(start.saturating_sub(1)),
+            }
+        };
+        self.extend_to(head)
+    }
+
+    /// Selects the word under the head (double click); a caret between words stays.
+    pub fn select_word(&self, text: &EzStr) -> Self {
+        text.words()
+            .iter()
+            .find(|w| (w.start..=w.end).contains(&self.head))
+            .map_or(*self, |w| Selection::new(w.start, w.end))
+    }
+
+    /// Grows the selection to whole lines, terminators included (triple click).
+    pub fn select_lines(&self, text: &EzStr) -> Self {
+        let starts = text.line_starts();
+        let start = starts[starts.partition_point(|&s| s <= self.start()) - 1];
+        let last = self.end().saturating_sub(1).max(self.start());
+        let end = starts.iter().copied().find(|&s| s > last).unwrap_or(text.len());
+        if self.is_backward() { Selection::new(end, start) } else { Selection::new(start, end) }
+    }
+}
+
+impl EzStr {
+    /// Grapheme index where the line holding grapheme `index` starts; a line's
+    /// terminator belongs to it. Only that line is scanned.
+    pub fn line_start(&self, index: usize) -> usize {
+        let byte = self.grapheme_to_byte(index);
+        match self.as_str()[..byte].rfind('\n') {
+            Some(newline) => self.grapheme_at_byte(newline + 1),
+            None => 0,
+        }
+    }
+
+    /// Grapheme index where the content of the line holding grapheme `index` ends,
+    /// before its terminator ("\n" or "\r\n"), or `len()` on the last line.
+    pub fn line_end(&self, index: usize) -> usize {
+        let data = self.as_str();
+        let byte = self.grapheme_to_byte(index);
+        match data[byte..].find('\n') {
+            Some(newline) => {
+                let newline = byte + newline;
+                let terminator = if data[..newline].ends_with('\r') { newline - 1 } else { newline };
+                self.grapheme_at_byte(terminator)
+            }
+            None => self.len(),
+        }
+    }
+
+    /// The end of the first word ending after `index`, or `len()` (Ctrl+Right). Words
+    /// are those of `words`; only the lines between `index` and the result are segmented.
+    pub fn next_word_boundary(&self, index: usize) -> usize {
+        let data = self.as_str();
+        let mut byte = self.grapheme_to_byte(index);
+        let mut line_start = self.grapheme_to_byte(self.line_start(index));
+        while line_start < data.len() {
+            let line_end = data[line_start..].find('\n').map_or(data.len(), |i| line_start + i + 1);
+            let word_end = data[line_start..line_end]
+                .split_word_bound_indices()
+                .filter(|(_, w)| w.chars().any(char::is_alphanumeric))
+                .map(|(i, w)| line_start + i + w.len())
+                .find(|&end| end > byte);
+            if let Some(end) = word_end {
+                return self.grapheme_at_byte(end);
+            }
+            byte = line_end;
+            line_start = line_end;
+        }
+        self.len()
+    }
+
+    /// The start of the last word starting before `index`, or 0 (Ctrl+Left).
+    pub fn prev_word_boundary(&self, index: usize) -> usize {
+        let data = self.as_str();
+        let byte = self.grapheme_to_byte(index);
+        let mut line_start = data[..byte].rfind('\n').map_or(0, |i| i + 1);
+        let mut line_end = data[byte..].find('\n').map_or(data.len(), |i| byte + i + 1);
+        loop {
+            let word_start = data[line_start..line_end]
+                .split_word_bound_indices()
+                .filter(|(_, w)| w.chars().any(char::is_alphanumeric))
+                .map(|(i, _)| line_start + i)
+                .filter(|&start| start < byte)
+                .last();
+            if let Some(start) = word_start {
+                return self.grapheme_at_byte(start);
+            }
+            if line_start == 0 {
+                return 0;
+            }
+            line_end = line_start;
+            line_start = data[..line_end - 1].rfind('\n').map_or(0, |i| i + 1);
+        }
+    }
+
+    /// Index of the grapheme starting at `byte`, which must be a grapheme boundary.
+    fn grapheme_at_byte(&self, byte: usize) -> usize {
+        self.byte_range_to_grapheme_indices(byte, byte).0
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{EzStr, Grapheme};
+
+/// Settings for `EzStr::slugify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlugOptions {
+    /// Put between words, and used to replace any run of disallowed graphemes.
+    pub separator: String,
+    pub lowercase: bool,
+    /// Maximum slug length in graphemes; truncation never leaves a trailing separator.
+    pub max_len: Option<usize>,
+    /// Convert to ASCII first (needs the `translit` feature, otherwise ignored).
+    pub transliterate: bool,
+    /// Graphemes kept as-is besides letters and digits, e.g. `"._"`.
+    pub allowed: String,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        SlugOptions {
+            separator: String::from("-"),
+            lowercase: true,
+            max_len: None,
+            transliterate: true,
+            allowed: String::new(),
+        }
+    }
+}
+
+impl EzStr {
+    /// Builds a URL- and filename-safe identifier, e.g. "Thé - Nicotine Dreams" becomes
+    /// "the-nicotine-dreams" with the default options.
+    pub fn slugify(&self, options: SlugOptions) -> EzStr {
+        let allowed = EzStr::new(options.allowed.as_str());
+        let is_kept = |g: &Grapheme| {
+            g.is_alphabetic() || g.is_numeric() || allowed.graphemes().contains(g)
+        };
+
+        // words as lists of graphemes; a disallowed grapheme ends the current word
+        let mut words: Vec<Vec<Grapheme>> = vec![Vec::new()];
+        let mut push = |g: Grapheme| {
+            if is_kept(&g) {
+                words.last_mut().unwrap().push(g);
+            } else if !words.last().unwrap().is_empty() {
+                words.push(Vec::new());
+            }
+        };
+        for g in self.graphemes() {
+            #[cfg(feature = "translit")]
+            if options.transliterate {
+                match crate::translit::transliterate_grapheme(g) {
+                    Some(ascii) => EzStr::new(ascii).into_iter().for_each(&mut push),
+                    None => push(Grapheme::new(" ")),
+                }
+                continue;
+            }
+            push(g.clone());
+        }
+
+        let separator = EzStr::new(options.separator.as_str());
+        let mut slug: Vec<Grapheme> = Vec::new();
+        for word in words.into_iter().filter(|w| !w.is_empty()) {
+            let sep_len = if slug.is_empty() { 0 } else { separator.len() };
+            if let Some(max_len) = options.max_len {
+                let room = max_len.saturating_sub(slug.len() + sep_len);
+                if room == 0 {
+                    break;
+                }
+                if word.len() > room {
+                    slug.extend(separator.graphemes()[..sep_len].iter().cloned());
+                    slug.extend(word.into_iter().take(room));
+                    break;
+                }
+            }
+            slug.extend(separator.graphemes()[..sep_len].iter().cloned());
+            slug.extend(word);
+        }
+
+        let slug = EzStr::from_graphemes(slug);
+        if options.lowercase {
+            EzStr::new(slug.as_str().to_lowercase())
+        } else {
+            slug
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{EzStr, GraphemeMatch};
+
+/// A span of graphemes, `start..end`, as used by matches, slices, diffs and annotations.
+/// It converts to and from `Range<usize>` and compares equal to the same range. Like a
+/// range, it is only meaningful with `start <= end`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct GSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl GSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        GSpan { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// True if grapheme `index` is inside the span.
+    pub fn contains(&self, index: usize) -> bool {
+        self.start <= index && index < self.end
+    }
+
+    /// True if the two spans share at least one grapheme.
+    pub fn overlaps(&self, other: GSpan) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// The graphemes both spans cover, if any.
+    pub fn intersect(&self, other: GSpan) -> Option<GSpan> {
+        let (start, end) = (self.start.max(other.start), self.end.min(other.end));
+        (start < end).then_some(GSpan { start, end })
+    }
+
+    /// The smallest span covering both, including any gap between them.
+    pub fn union(&self, other: GSpan) -> GSpan {
+        GSpan { start: self.start.min(other.start), end: self.end.max(other.end) }
+    }
+
+    /// The span moved by `delta` graphemes, e.g. to follow an insertion before it.
+    /// Panics if it would start before 0.
+    pub fn shift(&self, delta: isize) -> GSpan {
+        let moved = |i: usize| i.checked_add_signed(delta).expect("span shifted before the start");
+        GSpan { start: moved(self.start), end: moved(self.end) }
+    }
+
+    /// Byte range of the span in `source`; indices past the end map to its length.
+    pub fn to_byte_range(&self, source: &EzStr) -> Range<usize> {
+        source.grapheme_to_byte(self.start)..source.grapheme_to_byte(self.end)
+    }
+
+    /// Grapheme span of the bytes `bytes` of `source`. A byte offset inside a grapheme
+    /// moves to the start of the next one; use `EzStr::snap_to_boundary` to choose.
+    pub fn from_byte_range(source: &EzStr, bytes: Range<usize>) -> GSpan {
+        let (start, end) = source.byte_range_to_grapheme_indices(bytes.start, bytes.end);
+        GSpan { start, end }
+    }
+}
+
+/// Which way `EzStr::snap_to_boundary` moves a byte offset that falls inside a grapheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bias {
+    /// To the start of the grapheme containing it.
+    Before,
+    /// To the start of the next grapheme.
+    After,
+}
+
+impl EzStr {
+    /// Moves `byte_offset` to a grapheme boundary, returning `(byte offset, grapheme
+    /// index)`. Offsets already on a boundary, including the end of the text, are kept;
+    /// `None` past the end. For offsets from tools that don't know about graphemes,
+    /// such as LSP positions or matches on raw bytes.
+    pub fn snap_to_boundary(&self, byte_offset: usize, bias: Bias) -> Option<(usize, usize)> {
+        let len = self.as_str().len();
+        if byte_offset >= len {
+            return (byte_offset == len).then(|| (len, self.len()));
+        }
+        let index = self.grapheme_index();
+        let grapheme = match (index.search(byte_offset), bias) {
+            (Ok(g), _) | (Err(g), Bias::After) => g,
+            (Err(g), Bias::Before) => g - 1,
+        };
+        Some((self.grapheme_to_byte(grapheme), grapheme))
+    }
+}
+
+impl From<Range<usize>> for GSpan {
+    fn from(range: Range<usize>) -> Self {
+        GSpan::new(range.start, range.end)
+    }
+}
+
+impl From<GSpan> for Range<usize> {
+    fn from(span: GSpan) -> Self {
+        span.start..span.end
+    }
+}
+
+impl PartialEq<Range<usize>> for GSpan {
+    fn eq(&self, other: &Range<usize>) -> bool {
+        self.start == other.start && self.end == other.end
+    }
+}
+
+impl fmt::Debug for GSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl GraphemeMatch {
+    /// Number of graphemes matched.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn span(&self) -> GSpan {
+        GSpan::new(self.start, self.end)
+    }
+
+    /// True if grapheme `index` is inside the match.
+    pub fn contains(&self, index: usize) -> bool {
+        self.span().contains(index)
+    }
+
+    /// True if the two matches share at least one grapheme.
+    pub fn overlaps(&self, other: &GraphemeMatch) -> bool {
+        self.span().overlaps(other.span())
+    }
+
+    /// The graphemes both matches cover, if any.
+    pub fn intersect(&self, other: &GraphemeMatch) -> Option<GSpan> {
+        self.span().intersect(other.span())
+    }
+
+    /// The smallest span covering both matches, including any gap between them.
+    pub fn union(&self, other: &GraphemeMatch) -> GSpan {
+        self.span().union(other.span())
+    }
+
+    /// The span moved by `delta` graphemes; see `GSpan::shift`.
+    pub fn shift(&self, delta: isize) -> GSpan {
+        self.span().shift(delta)
+    }
+}
+
+/// Ordered by position: start, then end, then text.
+impl PartialOrd for GraphemeMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GraphemeMatch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.start, self.end, self.text.as_str()).cmp(&(other.start, other.end, other.text.as_str()))
+    }
+}
+
+/// Set of grapheme indices stored as sorted, disjoint spans. Overlapping or touching
+/// spans are merged on insertion, so `0..3` and `3..5` become `0..5`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SpanSet {
+    spans: Vec<GSpan>,
+}
+
+impl SpanSet {
+    pub fn new() -> Self {
+        SpanSet::default()
+    }
+
+    /// Adds `span`, merging it with the spans it overlaps or touches. Empty spans are ignored.
+    pub fn insert<S: Into<GSpan>>(&mut self, span: S) {
+        let span = span.into();
+        if span.is_empty() {
+            return;
+        }
+        let first = self.spans.partition_point(|s| s.end < span.start);
+        let last = self.spans.partition_point(|s| s.start <= span.end);
+        let merged = match self.spans[first..last] {
+            [] => span,
+            ref touched => span.union(touched[0]).union(touched[touched.len() - 1]),
+        };
+        self.spans.splice(first..last, std::iter::once(merged));
+    }
+
+    /// Removes the graphemes of `span`, splitting spans that straddle it.
+    pub fn remove<S: Into<GSpan>>(&mut self, span: S) {
+        let span = span.into();
+        if span.is_empty() {
+            return;
+        }
+        let mut kept = Vec::with_capacity(self.spans.len() + 1);
+        for s in self.spans.drain(..) {
+            if s.end <= span.start || s.start >= span.end {
+                kept.push(s);
+                continue;
+            }
+            if s.start < span.start {
+                kept.push(GSpan::new(s.start, span.start));
+            }
+            if s.end > span.end {
+                kept.push(GSpan::new(span.end, s.end));
+            }
+        }
+        self.spans = kept;
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        let i = self.spans.partition_point(|s| s.end <= index);
+        self.spans.get(i).is_some_and(|s| s.contains(index))
+    }
+
+    /// The merged spans, in order.
+    pub fn spans(&self) -> &[GSpan] {
+        &self.spans
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GSpan> {
+        self.spans.iter()
+    }
+
+    /// Number of disjoint spans.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Number of graphemes covered.
+    pub fn covered(&self) -> usize {
+        self.spans.iter().map(|s| s.len()).sum()
+    }
+
+    pub fn union(&self, other: &SpanSet) -> SpanSet {
+        let mut out = self.clone();
+        other.spans.iter().for_each(|&s| out.insert(s));
+        out
+    }
+
+    pub fn intersection(&self, other: &SpanSet) -> SpanSet {
+        let mut spans = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.spans.len() && j < other.spans.len() {
+            let (a, b) = (self.spans[i], other.spans[j]);
+            spans.extend(a.intersect(b));
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        SpanSet { spans }
+    }
+}
+
+impl<S: Into<GSpan>> Extend<S> for SpanSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|s| self.insert(s));
+    }
+}
+
+impl FromIterator<GSpan> for SpanSet {
+    fn from_iter<I: IntoIterator<Item = GSpan>>(iter: I) -> Self {
+        let mut set = SpanSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl FromIterator<Range<usize>> for SpanSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut set = SpanSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Collects the spans of matches, e.g. `text.find_iter(&re).collect::<SpanSet>()`.
+impl FromIterator<GraphemeMatch> for SpanSet {
+    fn from_iter<I: IntoIterator<Item = GraphemeMatch>>(iter: I) -> Self {
+        iter.into_iter().map(|m| m.span()).collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a SpanSet {
+    type Item = &'a GSpan;
+    type IntoIter = std::slice::Iter<'a, GSpan>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.iter()
+    }
+}
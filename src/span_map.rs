@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+/// Links grapheme spans of a transformed string to the spans of the source they came from.
+///
+/// Segments are stored in output order; a segment with an empty output span records
+/// source graphemes that were removed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpanMap {
+    segments: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl SpanMap {
+    pub fn new() -> Self {
+        SpanMap::default()
+    }
+
+    /// Records that output graphemes `output` were produced from source graphemes `source`.
+    pub fn push(&mut self, output: Range<usize>, source: Range<usize>) {
+        self.segments.push((output, source));
+    }
+
+    /// The (output, source) segments, in output order.
+    pub fn segments(&self) -> &[(Range<usize>, Range<usize>)] {
+        &self.segments
+    }
+
+    /// Source span that produced the output grapheme at `output_index`.
+    pub fn source_span(&self, output_index: usize) -> Option<Range<usize>> {
+        let i = self.segments.partition_point(|(out, _)| out.end <= output_index);
+        self.segments[i..]
+            .iter()
+            .find(|(out, _)| out.contains(&output_index))
+            .map(|(_, src)| src.clone())
+    }
+
+    /// Output span produced from the source grapheme at `source_index`; empty if it was removed.
+    pub fn output_span(&self, source_index: usize) -> Option<Range<usize>> {
+        self.segments
+            .iter()
+            .find(|(_, src)| src.contains(&source_index))
+            .map(|(out, _)| out.clone())
+    }
+
+    /// Maps an output grapheme span to the smallest source span covering it.
+    pub fn to_source(&self, output: Range<usize>) -> Option<Range<usize>> {
+        if output.is_empty() {
+            return self.source_span(output.start).map(|src| src.start..src.start);
+        }
+        let first = self.source_span(output.start)?;
+        let last = self.source_span(output.end - 1)?;
+        Some(first.start..last.end)
+    }
+
+    /// Maps a source grapheme span to the output span produced from it. An empty span at
+    /// the end of the source maps to the end of the output.
+    pub fn to_output(&self, source: Range<usize>) -> Option<Range<usize>> {
+        if source.is_empty() {
+            let source_len = self.segments.iter().map(|(_, src)| src.end).max().unwrap_or(0);
+            if source.start == source_len {
+                let output_len = self.segments.last().map_or(0, |(out, _)| out.end);
+                return Some(output_len..output_len);
+            }
+            return self.output_span(source.start).map(|out| out.start..out.start);
+        }
+        let first = self.output_span(source.start)?;
+        let last = self.output_span(source.end - 1)?;
+        Some(first.start..last.end)
+    }
+}
@@ -0,0 +1,150 @@
+use regex::Regex;
+
+use crate::{EzStr, GraphemeMatch};
+
+/// Something to split on: a literal `&str`, `String` or `char`, or a `Regex`.
+pub trait Delimiter {
+    /// Byte spans of the non-overlapping occurrences in `haystack`, in order.
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)>;
+}
+
+impl Delimiter for &str {
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        haystack.match_indices(*self).map(|(i, m)| (i, i + m.len())).collect()
+    }
+}
+
+impl Delimiter for String {
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)> {
+        self.as_str().byte_spans(haystack)
+    }
+}
+
+impl Delimiter for char {
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)> {
+        haystack.match_indices(*self).map(|(i, m)| (i, i + m.len())).collect()
+    }
+}
+
+impl Delimiter for Regex {
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)> {
+        self.find_iter(haystack).filter(|m| !m.is_empty()).map(|m| (m.start(), m.end())).collect()
+    }
+}
+
+impl Delimiter for &Regex {
+    fn byte_spans(&self, haystack: &str) -> Vec<(usize, usize)> {
+        (*self).byte_spans(haystack)
+    }
+}
+
+impl EzStr {
+    /// Grapheme spans of the delimiters, skipping any that start or end inside a grapheme.
+    fn delimiter_spans<D: Delimiter>(&self, delimiter: &D) -> Vec<(usize, usize)> {
+        let index = self.grapheme_index();
+        let at = |byte: usize| if byte == self.as_str().len() { Ok(index.len()) } else { index.search(byte) };
+        delimiter
+            .byte_spans(self.as_str())
+            .into_iter()
+            .filter_map(|(start, end)| Some((at(start).ok()?, at(end).ok()?)))
+            .collect()
+    }
+
+    /// Parts between `spans`, with their positions in `self`.
+    fn parts_between(&self, spans: &[(usize, usize)]) -> Vec<GraphemeMatch> {
+        let mut parts = Vec::with_capacity(spans.len() + 1);
+        let mut from = 0;
+        for &(start, end) in spans {
+            parts.push(GraphemeMatch::from_source(self, from, start));
+            from = end;
+        }
+        parts.push(GraphemeMatch::from_source(self, from, self.len()));
+        parts
+    }
+
+    /// Splits around the first delimiter: `"By: Édrihan".split_once(": ")` gives "By" and "Édrihan".
+    pub fn split_once<D: Delimiter>(&self, delimiter: D) -> Option<(GraphemeMatch, GraphemeMatch)> {
+        let spans = self.delimiter_spans(&delimiter);
+        let &first = spans.first()?;
+        let mut parts = self.parts_between(&[first]).into_iter();
+        Some((parts.next()?, parts.next()?))
+    }
+
+    /// Splits around the last delimiter.
+    pub fn rsplit_once<D: Delimiter>(&self, delimiter: D) -> Option<(GraphemeMatch, GraphemeMatch)> {
+        let spans = self.delimiter_spans(&delimiter);
+        let &last = spans.last()?;
+        let mut parts = self.parts_between(&[last]).into_iter();
+        Some((parts.next()?, parts.next()?))
+    }
+
+    /// At most `n` parts, the last one holding the rest of the string, like `str::splitn`.
+    pub fn splitn<D: Delimiter>(&self, n: usize, delimiter: D) -> Vec<GraphemeMatch> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let spans = self.delimiter_spans(&delimiter);
+        self.parts_between(&spans[..spans.len().min(n - 1)])
+    }
+
+    /// At most `n` parts counted from the end, last part first, like `str::rsplitn`.
+    pub fn rsplitn<D: Delimiter>(&self, n: usize, delimiter: D) -> Vec<GraphemeMatch> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let spans = self.delimiter_spans(&delimiter);
+        let mut parts = self.parts_between(&spans[spans.len().saturating_sub(n - 1)..]);
+        parts.reverse();
+        parts
+    }
+
+    /// Splits on every delimiter, like `str::split_terminator`: a delimiter at the very
+    /// end terminates the last part instead of starting an empty one.
+    pub fn split_terminator<D: Delimiter>(&self, delimiter: D) -> Vec<GraphemeMatch> {
+        let spans = self.delimiter_spans(&delimiter);
+        let mut parts = self.parts_between(&spans);
+        if parts.last().is_some_and(|p| p.is_empty()) {
+            parts.pop();
+        }
+        parts
+    }
+
+    /// Splits on `delimiter` except where it is inside a region opened and closed by
+    /// one of the `quotes` chars, or directly follows one of the `escapes` chars. Quotes
+    /// and escapes are kept in the parts; an unclosed quote runs to the end of the text.
+    /// `r#"|"a|b"|c\|d|"#.split_respecting('|', &['"'], &['\\'])` gives "", `"a|b"`,
+    /// `c\|d` and "".
+    pub fn split_respecting<D: Delimiter>(&self, delimiter: D, quotes: &[char], escapes: &[char]) -> Vec<GraphemeMatch> {
+        let data = self.as_str();
+        let mut protected = vec![false; data.len() + 1];
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+        for (i, c) in data.char_indices() {
+            if escaped {
+                protected[i] = true;
+                escaped = false;
+            } else if escapes.contains(&c) {
+                escaped = true;
+            } else if quote == Some(c) {
+                quote = None;
+            } else if quote.is_some() {
+                protected[i] = true;
+            } else if quotes.contains(&c) {
+                quote = Some(c);
+            }
+        }
+
+        let index = self.grapheme_index();
+        let at = |byte: usize| if byte == data.len() { Ok(index.len()) } else { index.search(byte) };
+        let spans: Vec<(usize, usize)> = delimiter
+            .byte_spans(data)
+            .into_iter()
+            .filter(|&(start, _)| !protected[start])
+            .filter_map(|(start, end)| Some((at(start).ok()?, at(end).ok()?)))
+            .collect();
+        self.parts_between(&spans)
+    }
+}
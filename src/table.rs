@@ -0,0 +1,115 @@
+use crate::{EzStr, Grapheme};
+
+/// One cell of a `TextGrid`: its trimmed text and the grapheme span of that text in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridCell {
+    pub text: EzStr,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridRow {
+    /// Zero-based line number in the source.
+    pub line: usize,
+    pub cells: Vec<GridCell>,
+}
+
+/// Rows of cells parsed from pipe-delimited lines such as `|A1 C1|G1|`.
+///
+/// Rows may have different numbers of cells; lines without a `|` are not part of the grid.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextGrid {
+    pub rows: Vec<GridRow>,
+}
+
+impl TextGrid {
+    pub fn parse(source: &EzStr) -> TextGrid {
+        let graphemes = source.graphemes();
+        let mut rows = Vec::new();
+        let mut line = 0;
+        let mut line_start = 0;
+        for i in 0..=graphemes.len() {
+            let at_end = i == graphemes.len();
+            if !at_end && !graphemes[i].value.contains('\n') {
+                continue;
+            }
+            if let Some(cells) = parse_line(source, graphemes, line_start, i) {
+                rows.push(GridRow { line, cells });
+            }
+            line += 1;
+            line_start = i + 1;
+        }
+        TextGrid { rows }
+    }
+
+    /// Number of columns of the widest row.
+    pub fn column_count(&self) -> usize {
+        self.rows.iter().map(|r| r.cells.len()).max().unwrap_or(0)
+    }
+
+    /// Display width of the widest cell of each column.
+    pub fn column_widths(&self) -> Vec<usize> {
+        let mut widths = vec![0; self.column_count()];
+        for row in &self.rows {
+            for (col, cell) in row.cells.iter().enumerate() {
+                widths[col] = widths[col].max(cell.text.display_width());
+            }
+        }
+        widths
+    }
+
+    /// Re-renders the grid with every column padded to the same display width.
+    /// Short rows keep their own number of cells.
+    pub fn render(&self) -> EzStr {
+        let widths = self.column_widths();
+        let mut out = String::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push('|');
+            for (col, cell) in row.cells.iter().enumerate() {
+                let pad = widths[col] - cell.text.display_width();
+                out.push(' ');
+                out += cell.text.as_str();
+                out.extend(std::iter::repeat(' ').take(pad + 1));
+                out.push('|');
+            }
+        }
+        EzStr::new(out)
+    }
+}
+
+fn parse_line(source: &EzStr, graphemes: &[Grapheme], start: usize, end: usize) -> Option<Vec<GridCell>> {
+    let pipes: Vec<usize> = (start..end).filter(|&i| graphemes[i].value == "|").collect();
+    if pipes.is_empty() {
+        return None;
+    }
+    // text before the first and after the last pipe only counts when it is not blank
+    let mut bounds: Vec<(usize, usize)> = Vec::new();
+    let is_blank = |from: usize, to: usize| graphemes[from..to].iter().all(|g| g.is_whitespace());
+    if !is_blank(start, pipes[0]) {
+        bounds.push((start, pipes[0]));
+    }
+    for pair in pipes.windows(2) {
+        bounds.push((pair[0] + 1, pair[1]));
+    }
+    let last = *pipes.last().unwrap();
+    if !is_blank(last + 1, end) {
+        bounds.push((last + 1, end));
+    }
+    let cells = bounds
+        .into_iter()
+        .map(|(mut from, mut to)| {
+            while from < to && graphemes[from].is_whitespace() {
+                from += 1;
+            }
+            while to > from && graphemes[to - 1].is_whitespace() {
+                to -= 1;
+            }
+            GridCell { text: source.slice_strict(from, to), start: from, end: to }
+        })
+        .collect();
+    Some(cells)
+}
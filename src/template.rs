@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::{EzStr, GSpan};
+
+/// Output of `EzStr::render`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub text: EzStr,
+    /// Placeholder name and the grapheme span its value occupies in `text`, in order.
+    pub substitutions: Vec<(String, GSpan)>,
+}
+
+/// Why a template could not be rendered; `at` is a grapheme index in the template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    MissingValue { name: String, at: usize },
+    UnclosedPlaceholder { at: usize },
+    UnmatchedBrace { at: usize },
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::MissingValue { name, at } => write!(f, "no value for {{{}}} at {}", name, at),
+            TemplateError::UnclosedPlaceholder { at } => write!(f, "unclosed placeholder at {}", at),
+            TemplateError::UnmatchedBrace { at } => write!(f, "unmatched '}}' at {}", at),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl EzStr {
+    /// Replaces `{name}` placeholders with values from `values`; `{{` and `}}` give
+    /// literal braces. Returns the text with the span of every substitution.
+    pub fn render(&self, values: &HashMap<&str, EzStr>) -> Result<Rendered, TemplateError> {
+        let data = self.as_str();
+        let at = |byte: usize| self.byte_range_to_grapheme_indices(byte, byte).0;
+        let mut out = String::with_capacity(data.len());
+        let mut byte_spans: Vec<(String, Range<usize>)> = Vec::new();
+        let mut chars = data.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                    chars.next();
+                    out.push('{');
+                }
+                '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+                    chars.next();
+                    out.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace { at: at(i) }),
+                '{' => {
+                    let close = data[i..].find('}').map(|p| i + p);
+                    let close = close.ok_or(TemplateError::UnclosedPlaceholder { at: at(i) })?;
+                    let name = &data[i + 1..close];
+                    let value = values
+                        .get(name)
+                        .ok_or_else(|| TemplateError::MissingValue { name: name.to_string(), at: at(i) })?;
+                    let start = out.len();
+                    out += value.as_str();
+                    byte_spans.push((name.to_string(), start..out.len()));
+                    while chars.peek().map_or(false, |&(j, _)| j <= close) {
+                        chars.next();
+                    }
+                }
+                _ => out.push(c),
+            }
+        }
+
+        let text = EzStr::new(out);
+        let substitutions = byte_spans
+            .into_iter()
+            .map(|(name, bytes)| {
+                (name, GSpan::from_byte_range(&text, bytes))
+            })
+            .collect();
+        Ok(Rendered { text, substitutions })
+    }
+}
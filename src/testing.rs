@@ -0,0 +1,140 @@
+//! Generators of hostile Unicode text and checkers for the crate's index invariants, for
+//! property tests and fuzzing of code built on `EzStr`. Nothing here depends on a
+//! particular framework: generators are driven by a seed, which proptest or quickcheck
+//! can supply (`any::<u64>().prop_map(|seed| nasty_ezstr(seed, 64))`), and checkers
+//! return an error describing the first violation.
+use std::fmt;
+
+use regex::Regex;
+
+use crate::{EzStr, GSpan, GraphemeMatch};
+
+/// Grapheme clusters that have broken text code before: combining stacks, ZWJ
+/// sequences, flags, skin tones, CRLF, RTL and Indic text, and invisible chars.
+const NASTY: &[&str] = &[
+    "a", "Z", "7", " ", "\t", "\n", "\r\n", "\r", "é", "e\u{301}", "n\u{303}\u{323}",
+    "Z\u{324}\u{354}\u{36e}", "\u{301}", "ß", "İ", "ﬁ", "夜", "한", "\u{1100}\u{1161}\u{11a8}",
+    "ש", "م", "\u{915}\u{94d}\u{937}", "👍🏽", "👨‍👩‍👧", "🏳️‍🌈", "🇫🇷", "🇩🇪", "❤️", "♪",
+    "𝄞", "\u{200b}", "\u{200d}", "\u{feff}", "\u{2028}", "\u{85}", "\u{0}",
+];
+
+/// A seeded source of random hostile text (SplitMix64, so runs are reproducible).
+#[derive(Debug, Clone)]
+pub struct NastyGen {
+    state: u64,
+}
+
+impl NastyGen {
+    pub fn new(seed: u64) -> Self {
+        NastyGen { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`; `n` must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A piece from the hostile set. Neighbouring pieces may merge into one grapheme
+    /// (a leading combining mark or ZWJ joins the previous one), which is the point.
+    pub fn piece(&mut self) -> &'static str {
+        NASTY[self.below(NASTY.len())]
+    }
+
+    /// Text of up to `max_pieces` pieces.
+    pub fn ezstr(&mut self, max_pieces: usize) -> EzStr {
+        let n = self.below(max_pieces + 1);
+        EzStr::new((0..n).map(|_| self.piece()).collect::<String>())
+    }
+}
+
+/// Random hostile text for `seed`, of up to `max_pieces` pieces.
+pub fn nasty_ezstr(seed: u64, max_pieces: usize) -> EzStr {
+    NastyGen::new(seed).ezstr(max_pieces)
+}
+
+/// A failed invariant check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    pub check: &'static str,
+    pub detail: String,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.check, self.detail)
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+fn violation(check: &'static str, detail: String) -> Result<(), InvariantViolation> {
+    Err(InvariantViolation { check, detail })
+}
+
+/// Slicing agrees with the grapheme list and the byte offsets: every grapheme is
+/// `slice(i, i + 1)` and sits at its byte offset, and the graphemes add up to the text.
+pub fn check_slice_round_trip(text: &EzStr) -> Result<(), InvariantViolation> {
+    const CHECK: &str = "slice round trip";
+    let data = text.as_str();
+    let mut rebuilt = String::with_capacity(data.len());
+    for (i, g) in text.iter_indices() {
+        let span = GSpan::new(i, i + 1).to_byte_range(text);
+        if &data[span.clone()] != g.as_str() || text.slice(i, i + 1).as_str() != g.as_str() {
+            return violation(CHECK, format!("grapheme {} {:?} is not at bytes {:?} of {:?}", i, g.as_str(), span, data));
+        }
+        rebuilt += g.as_str();
+    }
+    if rebuilt != data {
+        return violation(CHECK, format!("graphemes of {:?} rebuild {:?}", data, rebuilt));
+    }
+    Ok(())
+}
+
+/// Every byte offset, including ones inside a char or a grapheme, maps to a grapheme
+/// span whose byte range starts on a grapheme boundary at or after it, without panicking.
+pub fn check_byte_index_round_trip(text: &EzStr) -> Result<(), InvariantViolation> {
+    const CHECK: &str = "byte index round trip";
+    let len = text.as_str().len();
+    for byte in 0..=len {
+        let span = GSpan::from_byte_range(text, byte..len);
+        let bytes = span.to_byte_range(text);
+        if span.end != text.len() || bytes.start < byte || bytes.end != len {
+            return violation(CHECK, format!("byte {} of {:?} maps to {:?} (bytes {:?})", byte, text.as_str(), span, bytes));
+        }
+        if bytes.start > byte && text.grapheme_index().search(byte).is_ok() {
+            return violation(CHECK, format!("boundary byte {} of {:?} was moved to {}", byte, text.as_str(), bytes.start));
+        }
+    }
+    Ok(())
+}
+
+/// The match's text, grapheme span, byte span and char span all describe the same
+/// part of `source`.
+pub fn check_match(source: &EzStr, m: &GraphemeMatch) -> Result<(), InvariantViolation> {
+    const CHECK: &str = "match validity";
+    if let Err(e) = m.validate(source) {
+        return violation(CHECK, e.to_string());
+    }
+    let data = source.as_str();
+    let bytes_ok = data.get(m.byte_range()) == Some(m.text.as_str());
+    let chars_ok = bytes_ok
+        && data[..m.byte_start].chars().count() == m.char_start
+        && m.char_end - m.char_start == m.text.as_str().chars().count();
+    if !chars_ok {
+        return violation(CHECK, format!("{:?} does not match its byte or char span in {:?}", m, data));
+    }
+    Ok(())
+}
+
+/// `check_match` for every match of `regex` in `text`.
+pub fn check_matches(text: &EzStr, regex: &Regex) -> Result<(), InvariantViolation> {
+    text.find_iter(regex).try_for_each(|m| check_match(text, &m))
+}
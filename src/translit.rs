@@ -0,0 +1,99 @@
+use crate::{is_combining_mark, EzStr, Grapheme, SpanMap};
+
+// U+00A0..=U+00FF
+const LATIN_1: [&str; 96] = [
+    " ", "!", "c", "L", "", "Y", "|", "S", "\"", "(c)", "a", "<<", "!", "-", "(r)", "-",
+    "deg", "+-", "2", "3", "'", "u", "P", ".", ",", "1", "o", ">>", " 1/4", " 1/2", " 3/4", "?",
+    "A", "A", "A", "A", "A", "A", "AE", "C", "E", "E", "E", "E", "I", "I", "I", "I",
+    "D", "N", "O", "O", "O", "O", "O", "x", "O", "U", "U", "U", "U", "Y", "Th", "ss",
+    "a", "a", "a", "a", "a", "a", "ae", "c", "e", "e", "e", "e", "i", "i", "i", "i",
+    "d", "n", "o", "o", "o", "o", "o", "/", "o", "u", "u", "u", "u", "y", "th", "y",
+];
+
+// U+0100..=U+017F
+const LATIN_EXTENDED_A: [&str; 128] = [
+    "A", "a", "A", "a", "A", "a", "C", "c", "C", "c", "C", "c", "C", "c", "D", "d",
+    "D", "d", "E", "e", "E", "e", "E", "e", "E", "e", "E", "e", "G", "g", "G", "g",
+    "G", "g", "G", "g", "H", "h", "H", "h", "I", "i", "I", "i", "I", "i", "I", "i",
+    "I", "i", "IJ", "ij", "J", "j", "K", "k", "q", "L", "l", "L", "l", "L", "l", "L",
+    "l", "L", "l", "N", "n", "N", "n", "N", "n", "'n", "NG", "ng", "O", "o", "O", "o",
+    "O", "o", "OE", "oe", "R", "r", "R", "r", "R", "r", "S", "s", "S", "s", "S", "s",
+    "S", "s", "T", "t", "T", "t", "T", "t", "U", "u", "U", "u", "U", "u", "U", "u",
+    "U", "u", "U", "u", "W", "w", "Y", "y", "Y", "Z", "z", "Z", "z", "Z", "z", "s",
+];
+
+// U+0391..=U+03A9, lowercase at +0x20
+const GREEK: [&str; 25] = [
+    "A", "V", "G", "D", "E", "Z", "I", "Th", "I", "K", "L", "M", "N", "X", "O", "P",
+    "R", "S", "S", "T", "Y", "F", "Ch", "Ps", "O",
+];
+
+// U+0410..=U+042F, lowercase at +0x20
+const CYRILLIC: [&str; 32] = [
+    "A", "B", "V", "G", "D", "E", "Zh", "Z", "I", "Y", "K", "L", "M", "N", "O", "P",
+    "R", "S", "T", "U", "F", "Kh", "Ts", "Ch", "Sh", "Shch", "", "Y", "", "E", "Yu", "Ya",
+];
+
+/// ASCII replacement for one char, or None if there is no known transliteration.
+pub fn transliterate_char(c: char) -> Option<String> {
+    let cp = c as u32;
+    let ret = match cp {
+        0x00..=0x7F => return Some(c.to_string()),
+        0xA0..=0xFF => LATIN_1[(cp - 0xA0) as usize],
+        0x100..=0x17F => LATIN_EXTENDED_A[(cp - 0x100) as usize],
+        0x391..=0x3A9 => GREEK[(cp - 0x391) as usize],
+        0x3B1..=0x3C9 => return Some(GREEK[(cp - 0x3B1) as usize].to_lowercase()),
+        0x401 => "Yo",
+        0x451 => "yo",
+        0x410..=0x42F => CYRILLIC[(cp - 0x410) as usize],
+        0x430..=0x44F => return Some(CYRILLIC[(cp - 0x430) as usize].to_lowercase()),
+        0x2010..=0x2015 => "-",
+        0x2018..=0x201B | 0x2032 => "'",
+        0x201C..=0x201F | 0x2033 => "\"",
+        0x2022 => "*",
+        0x2026 => "...",
+        0x2039 => "<",
+        0x203A => ">",
+        0x20AC => "EUR",
+        0x2122 => "TM",
+        0x2190 => "<-",
+        0x2192 => "->",
+        0x2248 => "~",
+        0x2260 => "!=",
+        0x2264 => "<=",
+        0x2265 => ">=",
+        0x2000..=0x200A | 0x202F | 0x3000 => " ",
+        0x200B..=0x200D | 0xFEFF => "",
+        _ if is_combining_mark(c) => "",
+        _ => return None,
+    };
+    Some(ret.to_string())
+}
+
+/// ASCII replacement for a grapheme, dropping combining marks; None if any char is unknown.
+pub fn transliterate_grapheme(grapheme: &Grapheme) -> Option<String> {
+    grapheme.value.chars().map(transliterate_char).collect()
+}
+
+impl EzStr {
+    /// Converts to ASCII using a built-in table, replacing graphemes without a
+    /// transliteration by `?`. The SpanMap links output spans to source graphemes.
+    pub fn transliterate(&self) -> (EzStr, SpanMap) {
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut map = SpanMap::new();
+        let mut out_len = 0;
+        for (i, g) in self.graphemes().iter().enumerate() {
+            let piece = transliterate_grapheme(g).unwrap_or_else(|| "?".to_string());
+            let piece_len = EzStr::new(piece.as_str()).len();
+            out += &piece;
+            map.push(out_len..out_len + piece_len, i..i + 1);
+            out_len += piece_len;
+        }
+        (EzStr::new(out), map)
+    }
+
+    /// Same as `transliterate`, without the span mapping.
+    pub fn to_ascii_lossy(&self) -> EzStr {
+        self.transliterate().0
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::case::fold_char;
+use crate::{EzStr, GraphemeMatch};
+
+/// A longest-prefix match from `EzTrie::longest_prefix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieMatch<'a, V> {
+    /// The matched prefix of the searched text.
+    pub matched: GraphemeMatch,
+    /// The key as it was inserted.
+    pub key: &'a EzStr,
+    pub value: &'a V,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    /// Children keyed by a (folded) grapheme.
+    children: BTreeMap<String, Node<V>>,
+    entry: Option<(EzStr, V)>,
+}
+
+impl<V> Default for Node<V> {
+    fn default() -> Self {
+        Node { children: BTreeMap::new(), entry: None }
+    }
+}
+
+/// A map from `EzStr` keys to values, walked one grapheme at a time, for
+/// autocompletion and longest-prefix lookups ("Am7" in "Am7b5/G").
+///
+/// A case-insensitive trie compares keys after full case folding, so "STRASSE" finds
+/// "Straße"; keys differing only in case are then the same key. Matches never end
+/// inside a grapheme of the searched text.
+#[derive(Debug, Clone)]
+pub struct EzTrie<V> {
+    root: Node<V>,
+    case_insensitive: bool,
+    len: usize,
+}
+
+impl<V> Default for EzTrie<V> {
+    fn default() -> Self {
+        EzTrie { root: Node::default(), case_insensitive: false, len: 0 }
+    }
+}
+
+impl<V> EzTrie<V> {
+    pub fn new() -> Self {
+        EzTrie::default()
+    }
+
+    pub fn case_insensitive() -> Self {
+        EzTrie { case_insensitive: true, ..EzTrie::default() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `key`, returning the value it replaces. A replaced key keeps its
+    /// original spelling.
+    pub fn insert<K: Into<EzStr>>(&mut self, key: K, value: V) -> Option<V> {
+        let key = key.into();
+        let (folded, _) = self.fold(key.as_str());
+        let mut node = &mut self.root;
+        for g in folded.graphemes(true) {
+            node = node.children.entry(g.to_string()).or_default();
+        }
+        match &mut node.entry {
+            Some((_, old)) => Some(std::mem::replace(old, value)),
+            entry @ None => {
+                *entry = Some((key, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    pub fn get<K: AsRef<str>>(&self, key: K) -> Option<&V> {
+        self.node(key.as_ref())?.entry.as_ref().map(|(_, v)| v)
+    }
+
+    pub fn contains_key<K: AsRef<str>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The longest key that `text` starts with.
+    pub fn longest_prefix(&self, text: &EzStr) -> Option<TrieMatch<'_, V>> {
+        let (folded, boundaries) = self.fold(text.as_str());
+        // each node along the walk, with the folded byte offset it is reached at
+        let mut path = vec![(&self.root, 0)];
+        for (i, g) in folded.grapheme_indices(true) {
+            let Some(child) = path[path.len() - 1].0.children.get(g) else { break };
+            path.push((child, i + g.len()));
+        }
+        let best = path.into_iter().rev().find_map(|(node, at)| {
+            let (key, value) = node.entry.as_ref()?;
+            Some((boundaries.binary_search(&at).ok()?, key, value))
+        });
+        best.map(|(end, key, value)| TrieMatch { matched: GraphemeMatch::from_source(text, 0, end), key, value })
+    }
+
+    /// Entries whose key starts with `prefix`, ordered by their (folded) key.
+    pub fn completions<K: AsRef<str>>(&self, prefix: K) -> Vec<(&EzStr, &V)> {
+        let mut out = Vec::new();
+        let mut stack: Vec<&Node<V>> = self.node(prefix.as_ref()).into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Some((key, value)) = &node.entry {
+                out.push((key, value));
+            }
+            stack.extend(node.children.values().rev());
+        }
+        out
+    }
+
+    /// All entries, ordered by their (folded) key.
+    pub fn iter(&self) -> impl Iterator<Item = (&EzStr, &V)> {
+        self.completions("").into_iter()
+    }
+
+    fn node(&self, key: &str) -> Option<&Node<V>> {
+        let (folded, _) = self.fold(key);
+        folded.graphemes(true).try_fold(&self.root, |node, g| node.children.get(g))
+    }
+
+    /// `s` folded if the trie is case-insensitive, with the byte offsets in the folded
+    /// text where each grapheme of `s` starts, plus its length.
+    fn fold(&self, s: &str) -> (String, Vec<usize>) {
+        let mut folded = String::with_capacity(s.len());
+        let mut boundaries = Vec::new();
+        for g in s.graphemes(true) {
+            boundaries.push(folded.len());
+            if self.case_insensitive {
+                g.chars().for_each(|c| fold_char(c, &mut folded));
+            } else {
+                folded += g;
+            }
+        }
+        boundaries.push(folded.len());
+        (folded, boundaries)
+    }
+}
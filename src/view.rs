@@ -0,0 +1,154 @@
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::{EzStr, GSpan, Grapheme, GraphemeMatch};
+
+/// A borrowed grapheme range of an `EzStr`, from `EzStr::slice_view`. Positions reported
+/// by its methods are relative to the view, as with `str` slices.
+#[derive(Clone, Copy)]
+pub struct EzSlice<'a> {
+    source: &'a EzStr,
+    start: usize,
+    end: usize,
+    /// Byte and char offsets of `start` in the source, for turning matches relative.
+    byte_start: usize,
+    char_start: usize,
+}
+
+impl<'a> EzSlice<'a> {
+    fn new(source: &'a EzStr, start: usize, end: usize) -> Self {
+        EzSlice { source, start, end, byte_start: source.grapheme_to_byte(start), char_start: source.char_offset(start) }
+    }
+
+    pub fn source(&self) -> &'a EzStr {
+        self.source
+    }
+
+    /// The grapheme range this view covers in its source.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn span(&self) -> GSpan {
+        GSpan::new(self.start, self.end)
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        let data = self.source.as_str();
+        &data[self.byte_start..self.source.grapheme_to_byte(self.end)]
+    }
+
+    pub fn graphemes(&self) -> &'a [Grapheme] {
+        &self.source.graphemes()[self.start..self.end]
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// A narrower view; `start..end` is relative to this one. Panics when out of bounds.
+    pub fn slice_view<S: Into<GSpan>>(&self, range: S) -> EzSlice<'a> {
+        let range = range.into();
+        assert!(range.start <= range.end && range.end <= self.len(), "view range {:?} out of bounds for length {}", range, self.len());
+        EzSlice::new(self.source, self.start + range.start, self.start + range.end)
+    }
+
+    pub fn contains<T: AsRef<str>>(&self, substring: T) -> bool {
+        self.as_str().contains(substring.as_ref())
+    }
+
+    pub fn display_width(&self) -> usize {
+        self.graphemes().iter().map(|g| g.display_width()).sum()
+    }
+
+    /// Turns a source match into one relative to this view.
+    fn relative(&self, m: GraphemeMatch) -> GraphemeMatch {
+        GraphemeMatch {
+            start: m.start - self.start,
+            end: m.end - self.start,
+            byte_start: m.byte_start - self.byte_start,
+            byte_end: m.byte_end - self.byte_start,
+            char_start: m.char_start - self.char_start,
+            char_end: m.char_end - self.char_start,
+            text: m.text,
+        }
+    }
+
+    /// First match of `regex` inside the view, in view-relative indices.
+    pub fn find(&self, regex: &Regex) -> Option<GraphemeMatch> {
+        self.find_iter(regex).next()
+    }
+
+    /// Matches of `regex` inside the view, in view-relative indices.
+    pub fn find_iter(&self, regex: &Regex) -> impl Iterator<Item = GraphemeMatch> + 'a {
+        let view = *self;
+        let base = self.byte_start;
+        let spans: Vec<(usize, usize)> = regex.find_iter(self.as_str()).map(|m| (m.start(), m.end())).collect();
+        spans.into_iter().map(move |(start, end)| {
+            let (g_start, g_end) = view.source.byte_range_to_grapheme_indices(base + start, base + end);
+            view.relative(GraphemeMatch::from_source(view.source, g_start, g_end))
+        })
+    }
+
+    /// Copies the view into its own `EzStr`.
+    pub fn to_ezstr(&self) -> EzStr {
+        self.source.slice_strict(self.start, self.end)
+    }
+}
+
+impl PartialEq for EzSlice<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<&str> for EzSlice<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<EzStr> for EzSlice<'_> {
+    fn eq(&self, other: &EzStr) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl std::fmt::Display for EzSlice<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Debug for EzSlice<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}@{}..{}", self.as_str(), self.start, self.end)
+    }
+}
+
+impl EzStr {
+    /// Borrows graphemes `range` as an `EzSlice` without copying. Panics when out of bounds.
+    ///
+    /// `s[range]` still gives a `[Grapheme]` slice: `Index` has to return a reference into
+    /// `s`, which a view built on the fly cannot be.
+    pub fn slice_view<S: Into<GSpan>>(&self, range: S) -> EzSlice<'_> {
+        let range = range.into();
+        let len = self.len();
+        self.get_range(range)
+            .unwrap_or_else(|| panic!("view range {:?} out of bounds for length {}", range, len))
+    }
+
+    /// Like `slice_view`, but `None` instead of panicking when `range` is out of bounds.
+    pub fn get_range<S: Into<GSpan>>(&self, range: S) -> Option<EzSlice<'_>> {
+        let range = range.into();
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        Some(EzSlice::new(self, range.start, range.end))
+    }
+}
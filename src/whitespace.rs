@@ -0,0 +1,78 @@
+use crate::{EzStr, GraphemeRef, SpanMap};
+
+/// Line terminator written by `EzStr::normalize_newlines`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NewlineStyle {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl NewlineStyle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::CrLf => "\r\n",
+            NewlineStyle::Cr => "\r",
+        }
+    }
+}
+
+/// True for a line break grapheme: "\n", "\r\n", "\r", NEL, LS or PS.
+pub(crate) fn is_newline(g: GraphemeRef<'_>) -> bool {
+    matches!(g.as_str(), "\n" | "\r\n" | "\r" | "\u{85}" | "\u{2028}" | "\u{2029}")
+}
+
+impl EzStr {
+    /// Replaces every run of spaces, tabs, NBSPs and other non-breaking whitespace inside
+    /// a line by a single space and drops whitespace at the start and end of each line.
+    /// Line breaks are kept as they are. The SpanMap maps each output space to the run it
+    /// replaced.
+    pub fn collapse_whitespace(&self) -> (EzStr, SpanMap) {
+        let mut out = String::with_capacity(self.as_str().len());
+        let mut map = SpanMap::new();
+        let mut out_len = 0;
+        let mut run: Option<usize> = None;
+        let mut line_start = true;
+        for (i, g) in self.iter_indices() {
+            if g.is_whitespace() && !is_newline(g) {
+                run.get_or_insert(i);
+                continue;
+            }
+            let newline = is_newline(g);
+            if let Some(start) = run.take() {
+                if line_start || newline {
+                    map.push(out_len..out_len, start..i);
+                } else {
+                    out.push(' ');
+                    map.push(out_len..out_len + 1, start..i);
+                    out_len += 1;
+                }
+            }
+            out += g.as_str();
+            map.push(out_len..out_len + 1, i..i + 1);
+            out_len += 1;
+            line_start = newline;
+        }
+        if let Some(start) = run {
+            map.push(out_len..out_len, start..self.len());
+        }
+        (EzStr::new(out), map)
+    }
+
+    /// Rewrites every line break ("\r\n", "\r", "\n", NEL, LS, PS) in `style`.
+    pub fn normalize_newlines(&self, style: NewlineStyle) -> (EzStr, SpanMap) {
+        self.map_graphemes(|g| Some(EzStr::new(if is_newline(g) { style.as_str() } else { g.as_str() })))
+    }
+
+    /// Removes a leading byte order mark (U+FEFF), if any.
+    pub fn strip_bom(&self) -> (EzStr, SpanMap) {
+        let mut first = true;
+        self.map_graphemes(|g| {
+            let bom = first && g.as_str() == "\u{FEFF}";
+            first = false;
+            (!bom).then(|| EzStr::new(g.as_str()))
+        })
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{emoji, is_combining_mark, EzStr, Grapheme, GraphemeRef, SpanMap};
+
+// East Asian Wide and Fullwidth code points.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0), (0x23F3, 0x23F3), (0x25FD, 0x25FE), (0x2614, 0x2615),
+    (0x2648, 0x2653), (0x267F, 0x267F), (0x2693, 0x2693), (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5), (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4), (0x26EA, 0x26EA), (0x26F2, 0x26F3), (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B),
+    (0x2728, 0x2728), (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755),
+    (0x2757, 0x2757), (0x2795, 0x2797), (0x27B0, 0x27B0), (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x2E80, 0x303E),
+    (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF),
+    (0xA960, 0xA97F), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F), (0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x16FE0, 0x16FE4),
+    (0x17000, 0x18AFF), (0x1B000, 0x1B2FF), (0x1F004, 0x1F004), (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E), (0x1F191, 0x1F19A), (0x1F200, 0x1F202), (0x1F210, 0x1F23B),
+    (0x1F240, 0x1F248), (0x1F250, 0x1F251), (0x1F260, 0x1F265), (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF), (0x1F7E0, 0x1F7EB), (0x1F90C, 0x1F9FF), (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD), (0x30000, 0x3FFFD),
+];
+
+/// Terminal column width of a single char: 0, 1 or 2.
+pub fn char_width(c: char) -> usize {
+    if c.is_control() || is_combining_mark(c) || matches!(c, '\u{200B}'..='\u{200F}' | '\u{2060}' | '\u{FEFF}') {
+        0
+    } else if emoji::in_table(c, WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+impl Grapheme {
+    /// Columns the cluster takes in a monospace terminal: emoji and East Asian
+    /// wide characters take 2, combining marks and controls 0. Ambiguous-width
+    /// characters such as ♪ count as 1.
+    pub fn display_width(&self) -> usize {
+        self.as_grapheme_ref().display_width()
+    }
+}
+
+impl GraphemeRef<'_> {
+    /// Columns the cluster takes in a monospace terminal; see `Grapheme::display_width`.
+    pub fn display_width(&self) -> usize {
+        if emoji::is_emoji(self) {
+            return 2;
+        }
+        self.as_str().chars().next().map_or(0, char_width)
+    }
+}
+
+impl EzStr {
+    /// Sum of the display widths of the graphemes.
+    pub fn display_width(&self) -> usize {
+        self.graphemes().iter().map(Grapheme::display_width).sum()
+    }
+
+    /// Replaces each tab by the spaces that reach the next multiple of `tab_width`
+    /// columns, counting display width from the start of the line, so chord grids
+    /// with wide characters still line up. The SpanMap maps the spaces of each tab
+    /// back to it. A `tab_width` of 0 removes tabs.
+    pub fn expand_tabs(&self, tab_width: usize) -> (EzStr, SpanMap) {
+        let mut column = 0;
+        self.map_graphemes(|g| {
+            if g.as_str() == "\t" {
+                let spaces = if tab_width == 0 { 0 } else { tab_width - column % tab_width };
+                column += spaces;
+                return (spaces > 0).then(|| EzStr::new(" ".repeat(spaces)));
+            }
+            if g.as_str().contains(['\n', '\r']) {
+                column = 0;
+            } else {
+                column += g.display_width();
+            }
+            Some(EzStr::new(g.as_str()))
+        })
+    }
+}
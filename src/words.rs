@@ -0,0 +1,121 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{EzStr, GraphemeMatch};
+
+/// Adjustments to UAX #29 word boundaries for `EzStr::words_with`, e.g. to keep chord
+/// names like "N.C" or "A♭maj7" in one piece.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WordRules {
+    /// Chars that belong to the words they touch, such as "♭♯#/" for chord names.
+    pub word_chars: String,
+    /// Chars that join the words on both sides of them, such as "." for "N.C" or "-"
+    /// for "jean-luc".
+    pub joiners: String,
+    /// Keep apostrophes at the start or end of a word ("'til", "dancin'"); UAX #29
+    /// already keeps them inside one ("don't").
+    pub keep_apostrophes: bool,
+}
+
+impl WordRules {
+    pub fn new() -> Self {
+        WordRules::default()
+    }
+
+    pub fn word_chars<T: AsRef<str>>(mut self, chars: T) -> Self {
+        self.word_chars = chars.as_ref().to_string();
+        self
+    }
+
+    pub fn joiners<T: AsRef<str>>(mut self, chars: T) -> Self {
+        self.joiners = chars.as_ref().to_string();
+        self
+    }
+
+    pub fn keep_apostrophes(mut self, yes: bool) -> Self {
+        self.keep_apostrophes = yes;
+        self
+    }
+
+    fn part(&self, segment: &str) -> Part {
+        let mut chars = segment.chars();
+        let single = chars.next().filter(|_| chars.next().is_none());
+        if segment.chars().any(char::is_alphanumeric) {
+            Part::Word
+        } else if segment.chars().all(|c| self.word_chars.contains(c)) {
+            Part::Extra
+        } else if single.is_some_and(|c| self.joiners.contains(c)) {
+            Part::Joiner
+        } else if self.keep_apostrophes && matches!(single, Some('\'' | '\u{2019}')) {
+            Part::Apostrophe
+        } else {
+            Part::Other
+        }
+    }
+}
+
+/// How a UAX #29 segment takes part in a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    /// Letters or digits.
+    Word,
+    /// Made only of `word_chars`: merges with the words next to it.
+    Extra,
+    Joiner,
+    Apostrophe,
+    Other,
+}
+
+impl EzStr {
+    /// The words of the text by UAX #29 word boundaries: segments with a letter or
+    /// digit, so punctuation and whitespace are skipped.
+    pub fn words(&self) -> Vec<GraphemeMatch> {
+        self.words_with(&WordRules::default())
+    }
+
+    /// Like `words`, with boundaries adjusted by `rules`. Segments made of word chars
+    /// join the words they touch, a joiner between two words joins them, and kept
+    /// apostrophes stick to the word they touch.
+    pub fn words_with(&self, rules: &WordRules) -> Vec<GraphemeMatch> {
+        let data = self.as_str();
+        let segments: Vec<(usize, usize, Part)> =
+            data.split_word_bound_indices().map(|(i, s)| (i, i + s.len(), rules.part(s))).collect();
+        let is_word = |i: usize| segments.get(i).is_some_and(|s| matches!(s.2, Part::Word | Part::Extra));
+
+        let mut words: Vec<(usize, usize)> = Vec::new();
+        let mut i = 0;
+        while i < segments.len() {
+            let (start, _, part) = segments[i];
+            let leading_apostrophe = part == Part::Apostrophe && is_word(i + 1);
+            if !is_word(i) && !leading_apostrophe {
+                i += 1;
+                continue;
+            }
+            let mut end = i;
+            loop {
+                let (current, next) = (segments[end].2, segments.get(end + 1).map(|s| s.2));
+                let joined = match next {
+                    // CJK ideographs are words of their own, so plain words only merge
+                    // through word chars
+                    Some(Part::Extra) => true,
+                    Some(Part::Word) => current == Part::Extra || current == Part::Apostrophe,
+                    Some(Part::Joiner) => current != Part::Apostrophe && is_word(end + 2),
+                    Some(Part::Apostrophe) => current != Part::Joiner,
+                    _ => false,
+                };
+                if !joined {
+                    break;
+                }
+                end += 1;
+            }
+            words.push((start, segments[end].1));
+            i = end + 1;
+        }
+        words
+            .into_iter()
+            .map(|(start, end)| {
+                let (g_start, g_end) = self.byte_range_to_grapheme_indices(start, end);
+                GraphemeMatch::from_source(self, g_start, g_end)
+            })
+            .collect()
+    }
+}
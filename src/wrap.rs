@@ -0,0 +1,145 @@
+use crate::{emoji, EzStr, GraphemeMatch, GraphemeRef};
+
+/// How `EzStr::justify` places a wrapped line in its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+    /// Stretches the spaces between words so the line fills the width. The last line
+    /// of each paragraph, and lines without stretchable spaces, stay left-aligned.
+    Full,
+}
+
+/// East Asian text may break between any two wide graphemes, without a space.
+fn is_wide(g: GraphemeRef<'_>) -> bool {
+    g.display_width() == 2 && !emoji::is_emoji(g)
+}
+
+impl EzStr {
+    /// Greedy word wrap to `width` display columns. Lines break at whitespace, which is
+    /// dropped at the break, and between wide East Asian graphemes; a word wider than
+    /// `width` is cut between graphemes. Line breaks in the text are kept: each source
+    /// line wraps on its own and an empty one gives an empty line.
+    pub fn wrap(&self, width: usize) -> Vec<GraphemeMatch> {
+        let graphemes: Vec<GraphemeRef<'_>> = self.iter().collect();
+        let mut lines = Vec::new();
+        for line in self.lines_with_terminators() {
+            let (from, to) = (line.content.start, line.content.end);
+            // (start, end) of the line being built, and its width
+            let mut current: Option<(usize, usize)> = None;
+            let mut current_width = 0;
+            let mut i = from;
+            while i < to {
+                if graphemes[i].is_whitespace() {
+                    i += 1;
+                    continue;
+                }
+                // the next unbreakable piece: a word, or a single wide grapheme
+                let mut end = i + 1;
+                if !is_wide(graphemes[i]) {
+                    while end < to && !graphemes[end].is_whitespace() && !is_wide(graphemes[end]) {
+                        end += 1;
+                    }
+                }
+                let gap: usize = current.map_or(0, |(_, e)| graphemes[e..i].iter().map(|g| g.display_width()).sum());
+                let word_width: usize = graphemes[i..end].iter().map(|g| g.display_width()).sum();
+                match current {
+                    Some((start, _)) if current_width + gap + word_width <= width => {
+                        current = Some((start, end));
+                        current_width += gap + word_width;
+                    }
+                    _ => {
+                        if let Some((start, e)) = current.take() {
+                            lines.push(GraphemeMatch::from_source(self, start, e));
+                        }
+                        // cut a word that does not fit on a line of its own
+                        let mut start = i;
+                        let mut used = 0;
+                        for (j, g) in graphemes.iter().enumerate().take(end).skip(i) {
+                            let w = g.display_width();
+                            if used + w > width && j > start {
+                                lines.push(GraphemeMatch::from_source(self, start, j));
+                                start = j;
+                                used = 0;
+                            }
+                            used += w;
+                        }
+                        current = Some((start, end));
+                        current_width = used;
+                    }
+                }
+                i = end;
+            }
+            match current {
+                Some((start, end)) => lines.push(GraphemeMatch::from_source(self, start, end)),
+                None => lines.push(GraphemeMatch::from_source(self, from, from)),
+            }
+        }
+        lines
+    }
+
+    /// Wraps to `width` columns like `wrap` and pads each line to exactly `width`
+    /// (lines holding a single overlong grapheme excepted). `Align::Full` spreads the
+    /// missing width over the runs of plain spaces between words, leftmost runs first.
+    /// Spaces touching East Asian wide text are never stretched, so CJK lines keep
+    /// their spacing.
+    pub fn justify(&self, width: usize, align: Align) -> Vec<EzStr> {
+        let lines = self.wrap(width);
+        let line_ends: Vec<usize> = self.lines_with_terminators().iter().map(|l| l.content.end).collect();
+        lines
+            .iter()
+            .map(|line| {
+                let missing = width.saturating_sub(line.text.display_width());
+                let last_of_paragraph = line_ends.contains(&line.end);
+                match align {
+                    Align::Left => EzStr::new(format!("{}{}", line.text, " ".repeat(missing))),
+                    Align::Right => EzStr::new(format!("{}{}", " ".repeat(missing), line.text)),
+                    Align::Center => {
+                        let left = missing / 2;
+                        EzStr::new(format!("{}{}{}", " ".repeat(left), line.text, " ".repeat(missing - left)))
+                    }
+                    Align::Full if last_of_paragraph => EzStr::new(format!("{}{}", line.text, " ".repeat(missing))),
+                    Align::Full => stretch(&line.text, missing),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Adds `extra` columns to the runs of spaces between words of `line`, or pads the
+/// end if none of them may stretch.
+fn stretch(line: &EzStr, extra: usize) -> EzStr {
+    let graphemes: Vec<GraphemeRef<'_>> = line.iter().collect();
+    // index of the last space of each stretchable run
+    let mut gaps = Vec::new();
+    let mut i = 0;
+    while i < graphemes.len() {
+        if graphemes[i].as_str() != " " {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < graphemes.len() && graphemes[i].as_str() == " " {
+            i += 1;
+        }
+        if start > 0 && i < graphemes.len() && !is_wide(graphemes[start - 1]) && !is_wide(graphemes[i]) {
+            gaps.push(i - 1);
+        }
+    }
+    if gaps.is_empty() {
+        return EzStr::new(format!("{}{}", line, " ".repeat(extra)));
+    }
+    let mut out = String::with_capacity(line.as_str().len() + extra);
+    let (each, rest) = (extra / gaps.len(), extra % gaps.len());
+    let mut gap = 0;
+    for (i, g) in graphemes.iter().enumerate() {
+        out += g.as_str();
+        if gaps.get(gap) == Some(&i) {
+            out.extend(std::iter::repeat(' ').take(each + (gap < rest) as usize));
+            gap += 1;
+        }
+    }
+    EzStr::new(out)
+}